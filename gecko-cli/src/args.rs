@@ -0,0 +1,237 @@
+pub const USAGE: &str = "\
+Usage: gecko <command> [options] <file>
+       gecko -e <code>
+
+Use `-` as the file to read the program from stdin.
+
+Commands:
+    run <file>       Run a program, or a .gkc file written by `build`
+    check <file>     Check a program for errors without running it
+    lint <file>      Check a program against the lint rules of its project
+    ast <file>       Print the syntax tree of a program
+    tokens <file>    Print the tokens of a program
+    types <file>     Print the type of every declared name
+    build <file>     Compile a program to bytecode and save it as a .gkc file,
+                     or translate it to C with `--emit c`
+    disasm <file>    Print the bytecode of a program or .gkc file
+    repl             Start an interactive session
+
+Options:
+    -e <code>        Use <code> as the program instead of a file; it can use
+                     `io` without `import std.io;`
+    -w, --watch      Re-run `run` or `check` whenever the file or its imports change
+    -O, --optimize   Fold constants before printing the tree with `ast`
+    --vm             Compile `run`'s program to bytecode and run it on the VM,
+                     loading the .gkc file next to it instead if it is up to date
+    -o <file>        Where `build` saves the program (default: <file>.gkc or <file>.c)
+    --emit <kind>    What `build` produces: gkc (default) or c
+    --format <fmt>   Output format for `ast`: json (default), sexpr, tree or dot
+                     Output format for `tokens` and `types`: table (default) or json
+    -h, --help       Print this help
+    -V, --version    Print the version";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TokenFormat {
+    Table,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TypeFormat {
+    Table,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AstFormat {
+    Json,
+    Sexpr,
+    Tree,
+    Dot,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Emit {
+    Bytecode,
+    C,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Input {
+    File(String),
+    Stdin,
+    Eval(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Run { input: Input, watch: bool, vm: bool },
+    Check { input: Input, watch: bool },
+    Ast { input: Input, format: AstFormat, optimize: bool },
+    Tokens(Input, TokenFormat),
+    Types(Input, TypeFormat),
+    Lint(Input),
+    Build { input: Input, output: Option<String>, emit: Emit },
+    Disasm(Input),
+    Repl,
+    Help,
+    Version,
+}
+
+pub fn parse(args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let mut args = args.peekable();
+
+    let command = match args.peek().map(String::as_str) {
+        // `gecko -e <code>` is shorthand for `gecko run -e <code>`
+        Some("-e") | Some("--eval") => String::from("run"),
+        Some(_) => args.next().unwrap(),
+        None => return Ok(Command::Help),
+    };
+
+    match command.as_str() {
+        "-h" | "--help" | "help" => return Ok(Command::Help),
+        "-V" | "--version" => return Ok(Command::Version),
+        "repl" => return match args.next() {
+            None => Ok(Command::Repl),
+            Some(arg) if arg == "-h" || arg == "--help" => Ok(Command::Help),
+            Some(arg) => Err(format!("unexpected argument '{}'", arg)),
+        },
+        "run" | "check" | "ast" | "tokens" | "types" | "lint" | "build" | "disasm" => {}
+        other => return Err(format!("unknown command '{}'", other)),
+    }
+
+    let mut input = None;
+    let mut format = None;
+    let mut watch = false;
+    let mut optimize = false;
+    let mut vm = false;
+    let mut output = None;
+    let mut emit = None;
+
+    while let Some(arg) = args.next() {
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+            _ => (arg.clone(), None),
+        };
+
+        match flag.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--format" => {
+                let value = match inline_value.or_else(|| args.next()) {
+                    Some(value) => value,
+                    None => return Err(String::from("'--format' needs a value")),
+                };
+                format = Some(value);
+            }
+            "-e" | "--eval" => {
+                let code = match inline_value.or_else(|| args.next()) {
+                    Some(code) => code,
+                    None => return Err(format!("'{}' needs a value", flag)),
+                };
+                set_input(&mut input, Input::Eval(code))?;
+            }
+            "--emit" => {
+                let kind = match inline_value.or_else(|| args.next()) {
+                    Some(kind) => kind,
+                    None => return Err(String::from("'--emit' needs a value")),
+                };
+                emit = Some(kind);
+            }
+            "-o" | "--output" => {
+                let path = match inline_value.or_else(|| args.next()) {
+                    Some(path) => path,
+                    None => return Err(format!("'{}' needs a value", flag)),
+                };
+                output = Some(path);
+            }
+            "-w" | "--watch" => watch = true,
+            "-O" | "--optimize" => optimize = true,
+            "--vm" => vm = true,
+            "-" => set_input(&mut input, Input::Stdin)?,
+            _ if arg.starts_with('-') => return Err(format!("unknown option '{}'", arg)),
+            _ => set_input(&mut input, Input::File(arg))?,
+        }
+    }
+
+    let input = match input {
+        Some(input) => input,
+        None => return Err(String::from("missing input file")),
+    };
+
+    if optimize && command != "ast" {
+        return Err(format!("'{}' does not take '--optimize'", command));
+    }
+    if vm && command != "run" {
+        return Err(format!("'{}' does not take '--vm'", command));
+    }
+    if output.is_some() && command != "build" {
+        return Err(format!("'{}' does not take '--output'", command));
+    }
+    if emit.is_some() && command != "build" {
+        return Err(format!("'{}' does not take '--emit'", command));
+    }
+
+    match command.as_str() {
+        "tokens" if watch => Err(String::from("'tokens' does not take '--watch'")),
+        "tokens" => {
+            let format = match format.as_deref() {
+                None | Some("table") => TokenFormat::Table,
+                Some("json") => TokenFormat::Json,
+                Some(other) => return Err(format!("unknown token format '{}'", other)),
+            };
+            Ok(Command::Tokens(input, format))
+        }
+        "types" if watch => Err(String::from("'types' does not take '--watch'")),
+        "types" => {
+            let format = match format.as_deref() {
+                None | Some("table") => TypeFormat::Table,
+                Some("json") => TypeFormat::Json,
+                Some(other) => return Err(format!("unknown types format '{}'", other)),
+            };
+            Ok(Command::Types(input, format))
+        }
+        "lint" if watch => Err(String::from("'lint' does not take '--watch'")),
+        "lint" if format.is_some() => Err(String::from("'lint' does not take '--format'")),
+        "lint" => Ok(Command::Lint(input)),
+        "build" | "disasm" if watch => Err(format!("'{}' does not take '--watch'", command)),
+        "build" | "disasm" if format.is_some() => Err(format!("'{}' does not take '--format'", command)),
+        "build" if output.is_none() && !matches!(input, Input::File(_)) => {
+            Err(String::from("'build' needs '-o' when the program isn't a file"))
+        }
+        "build" => {
+            let emit = match emit.as_deref() {
+                None | Some("gkc") => Emit::Bytecode,
+                Some("c") => Emit::C,
+                Some(other) => return Err(format!("unknown output kind '{}'", other)),
+            };
+            Ok(Command::Build { input, output, emit })
+        }
+        "disasm" => Ok(Command::Disasm(input)),
+        "ast" if watch => Err(String::from("'ast' does not take '--watch'")),
+        "ast" => {
+            let format = match format.as_deref() {
+                None | Some("json") => AstFormat::Json,
+                Some("sexpr") => AstFormat::Sexpr,
+                Some("tree") => AstFormat::Tree,
+                Some("dot") => AstFormat::Dot,
+                Some(other) => return Err(format!("unknown AST format '{}'", other)),
+            };
+            Ok(Command::Ast { input, format, optimize })
+        }
+        _ if format.is_some() => Err(format!("'{}' does not take '--format'", command)),
+        _ if watch && !matches!(input, Input::File(_)) => {
+            Err(String::from("'--watch' needs an input file"))
+        }
+        "run" => Ok(Command::Run { input, watch, vm }),
+        _ => Ok(Command::Check { input, watch }),
+    }
+}
+
+fn set_input(input: &mut Option<Input>, value: Input) -> Result<(), String> {
+    if input.is_some() {
+        return Err(String::from("only one input file or '-e' may be given"));
+    }
+
+    *input = Some(value);
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use gecko_lexer::{symbol::Symbol, Lexer};
+use gecko_parser::{
+    ast::{Ast, StmtId},
+    nodes::stmt::FileImport,
+    visit::{walk_ast, Visitor},
+    Parser,
+};
+use gecko_resolver::{top_level_names, Imports};
+
+/// Reads and parses a file, or `None` if either fails.
+pub fn parse_file(path: &Path) -> Option<Ast> {
+    let text = fs::read_to_string(path).ok()?;
+    let tokens = Lexer::new(text).scan_tokens().ok()?;
+    Parser::new(tokens).parse().ok()
+}
+
+/// The paths of every `import "file.gk";` in `ast`, as written.
+pub fn file_imports(ast: &Ast) -> Vec<Symbol> {
+    let mut imports = ImportCollector(vec![]);
+    walk_ast(&mut imports, ast);
+    imports.0
+}
+
+/// What each file `ast` imports declares: its own top-level names and
+/// those of the files it imports in turn, as they all run in the global
+/// scope. Imports resolve relative to `base_dir`, the same way the
+/// interpreter resolves them. Files that can't be read or parsed are left
+/// out.
+pub fn imported_names(ast: &Ast, base_dir: &Path) -> Imports {
+    let mut imports = Imports::new();
+    for path in file_imports(ast) {
+        let mut seen = HashSet::new();
+        if let Some(names) = names_in(base_dir, path, &mut seen) {
+            imports.insert(path, names);
+        }
+    }
+    imports
+}
+
+fn names_in(base_dir: &Path, path: Symbol, seen: &mut HashSet<PathBuf>) -> Option<Vec<Symbol>> {
+    let full_path = base_dir.join(path.as_str());
+    if !seen.insert(full_path.clone()) {
+        return Some(vec![]);
+    }
+
+    let ast = parse_file(&full_path)?;
+    let mut names = top_level_names(&ast);
+    for import in file_imports(&ast) {
+        names.extend(names_in(base_dir, import, seen)?);
+    }
+    Some(names)
+}
+
+struct ImportCollector(Vec<Symbol>);
+
+impl Visitor for ImportCollector {
+    fn visit_file_import(&mut self, _ast: &Ast, _id: StmtId, import: &FileImport) {
+        self.0.push(import.path);
+    }
+}
@@ -1,31 +1,439 @@
-use gecko_lexer::Lexer;
-use gecko_parser::Parser;
-use gecko_inspector::inspect;
+mod args;
+mod imports;
+mod repl;
+mod watch;
 
-use std::{fs::File, io::Read};
+use gecko_error::{Error, LineInfo};
+use gecko_inspector::{
+    inspect, inspect_dot, inspect_sexpr, inspect_token_table, inspect_tokens, inspect_tree, inspect_types,
+};
+use gecko_interpreter::{value::Value, Interpreter};
+use gecko_lexer::{symbol::Symbol, token::Token, ttype::TType, Lexer};
+use gecko_lint::Config as LintConfig;
+use gecko_vm::{chunk::Function, disassemble::disassemble, gkc, Vm};
+use gecko_parser::{
+    ast::Ast,
+    nodes::stmt::{LangImport, Stmt},
+    Parser,
+};
 
-fn read_file(path: &String) -> String {
-    let mut file = File::open(path).unwrap();
-    let mut contents = String::new();
-    file.read_to_string(&mut contents).unwrap();
+use std::{
+    env,
+    ffi::OsStr,
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    process::ExitCode,
+    rc::Rc,
+};
 
-    contents
+use args::{AstFormat, Command, Emit, Input, TokenFormat, TypeFormat};
+
+/// Exit code for errors in the program being run or checked.
+const EXIT_FAILURE: u8 = 1;
+/// Exit code for bad command-line usage or unreadable input.
+const EXIT_USAGE: u8 = 2;
+
+struct Source {
+    name: String,
+    text: String,
+    /// Whether `io` can be used without `import std.io;`, as in
+    /// `gecko -e 'io.println("hi");'`.
+    implicit_io: bool,
+}
+
+impl Source {
+    fn read(input: &Input) -> Result<Source, ExitCode> {
+        match input {
+            Input::File(path) => match fs::read_to_string(path) {
+                Ok(text) => Ok(Source {
+                    name: path.to_string(),
+                    text,
+                    implicit_io: false,
+                }),
+                Err(err) => {
+                    eprintln!("error: cannot read '{}': {}", path, err);
+                    Err(ExitCode::from(EXIT_USAGE))
+                }
+            },
+            Input::Stdin => {
+                let mut text = String::new();
+                match io::stdin().read_to_string(&mut text) {
+                    Ok(_) => Ok(Source {
+                        name: String::from("<stdin>"),
+                        text,
+                        implicit_io: false,
+                    }),
+                    Err(err) => {
+                        eprintln!("error: cannot read stdin: {}", err);
+                        Err(ExitCode::from(EXIT_USAGE))
+                    }
+                }
+            }
+            Input::Eval(code) => Ok(Source {
+                name: String::from("<eval>"),
+                text: code.clone(),
+                implicit_io: true,
+            }),
+        }
+    }
+
+    /// Directory that file imports in this source are resolved from.
+    fn base_dir(input: &Input) -> PathBuf {
+        match input {
+            Input::File(path) => Path::new(path).parent().unwrap_or(Path::new(".")).to_path_buf(),
+            Input::Stdin | Input::Eval(_) => PathBuf::from("."),
+        }
+    }
+
+    fn report(&self, err: &Error) -> ExitCode {
+        eprint!("{}", err.display(&self.name, &self.text));
+        ExitCode::from(EXIT_FAILURE)
+    }
+
+    fn lex(&self) -> Result<Vec<Token>, ExitCode> {
+        Lexer::new(self.text.clone())
+            .scan_tokens()
+            .map_err(|err| self.report(&err))
+    }
+
+    fn parse(&self) -> Result<Ast, ExitCode> {
+        let tokens = self.lex()?;
+        let io = Symbol::intern("io");
+        let uses_io = self.implicit_io && tokens.iter().any(|token| token.ttype == TType::Identifier(io));
+        let mut ast = Parser::new(tokens).parse().map_err(|err| self.report(&err))?;
+
+        if uses_io && !ast.root.iter().any(|&id| declares(&ast[id], io)) {
+            let path = vec![Symbol::intern("std"), io];
+            let import = ast.add_stmt(Stmt::LangImport(LangImport::new(path, LineInfo::new(0, 0, 0))));
+            ast.root.insert(0, import);
+        }
+        Ok(ast)
+    }
+
+    /// Parses, optimizes and compiles the source to bytecode.
+    fn compile(&self) -> Result<Rc<Function>, ExitCode> {
+        let mut ast = self.parse()?;
+        // Its only diagnostics are warnings, which `check` shows.
+        gecko_optimizer::optimize(&mut ast);
+
+        gecko_vm::compiler::compile(&ast).map_err(|err| self.report(&err))
+    }
 }
 
-fn main() {
-    let content = read_file(&String::from("test.gk"));
-    let input = String::from(content);
-    let stmts;
+/// Whether `stmt` declares `name` in the scope it is in.
+fn declares(stmt: &Stmt, name: Symbol) -> bool {
+    match stmt {
+        Stmt::VarDecl(var) => var.name.name == name,
+        Stmt::FnDecl(func) => func.name.name == name,
+        Stmt::LangImport(import) => import.path.last() == Some(&name),
+        _ => false,
+    }
+}
+
+/// Whether the input is a program compiled by `build` rather than source.
+fn compiled_path(input: &Input) -> Option<&str> {
+    match input {
+        Input::File(path) if Path::new(path).extension() == Some(OsStr::new(gkc::EXTENSION)) => Some(path),
+        _ => None,
+    }
+}
+
+fn load_compiled(path: &str) -> Result<gkc::Compiled, ExitCode> {
+    let bytes = fs::read(path).map_err(|err| {
+        eprintln!("error: cannot read '{}': {}", path, err);
+        ExitCode::from(EXIT_USAGE)
+    })?;
+    gkc::decode(&bytes).map_err(|message| {
+        eprintln!("error: cannot load '{}': {}", path, message);
+        ExitCode::from(EXIT_USAGE)
+    })
+}
+
+fn run(input: &Input, vm: bool) -> Result<ExitCode, ExitCode> {
+    if let Some(path) = compiled_path(input) {
+        return run_compiled(path);
+    }
+
+    let source = Source::read(input)?;
 
-    let mut lexer = Lexer::new(input);
-    let tokens = lexer.scan_tokens().unwrap();
+    if vm {
+        let cached = match input {
+            Input::File(path) => gkc::load_fresh(Path::new(path), &source.text),
+            Input::Stdin | Input::Eval(_) => None,
+        };
+        let function = match cached {
+            Some(function) => function,
+            None => source.compile()?,
+        };
+        return run_on_vm(function, Source::base_dir(input), |err| source.report(err));
+    }
 
-    let mut parser = Parser::new(tokens);
-    stmts = parser.parse().unwrap();
+    let mut ast = source.parse()?;
+    gecko_optimizer::optimize(&mut ast);
 
+    let mut interpreter = Interpreter::new().with_base_dir(Source::base_dir(input));
 
-    let output = inspect(stmts);
+    interpreter.run(Rc::new(ast)).map_err(|err| source.report(&err))?;
 
+    match interpreter.call_main().map_err(|err| source.report(&err))? {
+        Some(Value::Number(code)) => Ok(exit_code(code)),
+        _ => Ok(ExitCode::SUCCESS),
+    }
+}
+
+/// The exit code for a number returned from `main`: its integer part
+/// modulo 256, so `-1` exits with 255 as it would from C's `exit`.
+fn exit_code(code: f64) -> ExitCode {
+    ExitCode::from((code as i64).rem_euclid(256) as u8)
+}
+
+/// Runs a `.gkc` file. Errors are shown in the source it was compiled from
+/// if that is still next to it and unchanged, and by line number otherwise.
+fn run_compiled(path: &str) -> Result<ExitCode, ExitCode> {
+    let compiled = load_compiled(path)?;
+    let source_path = Path::new(path).with_extension("gk");
+    let source = fs::read_to_string(&source_path)
+        .ok()
+        .filter(|text| gkc::source_hash(text) == compiled.source_hash)
+        .map(|text| Source { name: source_path.display().to_string(), text, implicit_io: false });
+
+    let base_dir = Source::base_dir(&Input::File(path.to_string()));
+    run_on_vm(compiled.function, base_dir, |err| match &source {
+        Some(source) => source.report(err),
+        None => {
+            match err.line_info.line {
+                0 => eprintln!("error: {}\n --> {}", err.message, path),
+                line => eprintln!("error: {}\n --> {}:{}", err.message, path, line),
+            }
+            for note in &err.notes {
+                eprintln!("  = {}", note);
+            }
+            ExitCode::from(EXIT_FAILURE)
+        }
+    })
+}
+
+fn run_on_vm(function: Rc<Function>, base_dir: PathBuf, report: impl Fn(&Error) -> ExitCode) -> Result<ExitCode, ExitCode> {
+    let mut vm = Vm::new().with_base_dir(base_dir);
+    vm.run_function(function).map_err(|err| report(&err))?;
+
+    match vm.call_main().map_err(|err| report(&err))? {
+        Some(gecko_vm::value::Value::Number(code)) => Ok(exit_code(code)),
+        _ => Ok(ExitCode::SUCCESS),
+    }
+}
+
+fn build(input: &Input, output: Option<&str>, emit: Emit) -> Result<ExitCode, ExitCode> {
+    let source = Source::read(input)?;
+    let contents = match emit {
+        Emit::Bytecode => gkc::encode(&*source.compile()?, &source.text),
+        Emit::C => emit_c(&source)?.into_bytes(),
+    };
+
+    let output = match (output, input) {
+        (Some(output), _) => PathBuf::from(output),
+        (None, Input::File(path)) => match emit {
+            Emit::Bytecode => gkc::path_for(Path::new(path)),
+            Emit::C => Path::new(path).with_extension("c"),
+        },
+        (None, Input::Stdin | Input::Eval(_)) => unreachable!("'build' without '-o' needs a file"),
+    };
+    fs::write(&output, contents).map_err(|err| {
+        eprintln!("error: cannot write '{}': {}", output.display(), err);
+        ExitCode::from(EXIT_USAGE)
+    })?;
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Translates the source to C once it checks without errors.
+fn emit_c(source: &Source) -> Result<String, ExitCode> {
+    let mut ast = source.parse()?;
+
+    let mut errors = gecko_optimizer::optimize(&mut ast);
+    let (resolution, resolve_errors) = gecko_resolver::resolve(&ast);
+    errors.extend(resolve_errors);
+    let (types, type_errors) = gecko_typeck::check(&ast, &resolution);
+    errors.extend(type_errors);
+    errors.retain(|err| !err.is_warning());
+    errors.sort_by_key(|err| err.line_info.start);
+    for err in &errors {
+        source.report(err);
+    }
+    if !errors.is_empty() {
+        return Err(ExitCode::from(EXIT_FAILURE));
+    }
+
+    gecko_codegen::c::emit(&ast, &resolution, &types).map_err(|err| source.report(&err))
+}
+
+fn disasm(input: &Input) -> Result<ExitCode, ExitCode> {
+    let function = match compiled_path(input) {
+        Some(path) => load_compiled(path)?.function,
+        None => Source::read(input)?.compile()?,
+    };
+    print!("{}", disassemble(&function));
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn check(input: &Input) -> Result<ExitCode, ExitCode> {
+    let source = Source::read(input)?;
+    let mut ast = source.parse()?;
+
+    let imports = imports::imported_names(&ast, &Source::base_dir(input));
+    let (resolution, mut errors) = gecko_resolver::resolve_with_imports(&ast, &imports);
+    errors.extend(gecko_typeck::check(&ast, &resolution).1);
+    errors.extend(gecko_lint::lint(&ast, &resolution));
+    errors.extend(gecko_optimizer::optimize(&mut ast));
+    errors.sort_by_key(|err| err.line_info.start);
+    for err in &errors {
+        source.report(err);
+    }
+    if errors.iter().any(|err| !err.is_warning()) {
+        return Err(ExitCode::from(EXIT_FAILURE));
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn lint(input: &Input) -> Result<ExitCode, ExitCode> {
+    let source = Source::read(input)?;
+    let config = lint_config(input)?;
+    let ast = source.parse()?;
+
+    let errors = gecko_lint::run_rules(&ast, &source.text, &config);
+    for err in &errors {
+        source.report(err);
+    }
+    if errors.iter().any(|err| !err.is_warning()) {
+        return Err(ExitCode::from(EXIT_FAILURE));
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// The config in the nearest `gecko-lint.json` at or above the input's
+/// directory, or the default rules if there is none.
+fn lint_config(input: &Input) -> Result<LintConfig, ExitCode> {
+    let dir = Source::base_dir(input);
+    let dir = fs::canonicalize(&dir).unwrap_or(dir);
+    let Some(path) = dir.ancestors().map(|dir| dir.join(LintConfig::FILE_NAME)).find(|path| path.is_file()) else {
+        return Ok(LintConfig::default());
+    };
+
+    let text = fs::read_to_string(&path).map_err(|err| {
+        eprintln!("error: cannot read '{}': {}", path.display(), err);
+        ExitCode::from(EXIT_USAGE)
+    })?;
+    LintConfig::parse(&text).map_err(|message| {
+        eprintln!("error: invalid '{}': {}", path.display(), message);
+        ExitCode::from(EXIT_USAGE)
+    })
+}
+
+fn ast(input: &Input, format: AstFormat, optimize: bool) -> Result<ExitCode, ExitCode> {
+    let source = Source::read(input)?;
+    let mut ast = source.parse()?;
+
+    if optimize {
+        gecko_optimizer::optimize(&mut ast);
+    }
+
+    let output = match format {
+        AstFormat::Json => inspect(&ast),
+        AstFormat::Sexpr => inspect_sexpr(&ast),
+        AstFormat::Tree => inspect_tree(&ast),
+        AstFormat::Dot => inspect_dot(&ast),
+    };
     println!("{}", output);
 
+    Ok(ExitCode::SUCCESS)
+}
+
+fn tokens(input: &Input, format: TokenFormat) -> Result<ExitCode, ExitCode> {
+    let source = Source::read(input)?;
+    let tokens = source.lex()?;
+
+    match format {
+        TokenFormat::Json => println!("{}", inspect_tokens(tokens, &source.text)),
+        TokenFormat::Table => print!("{}", inspect_token_table(&tokens, &source.text)),
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn types(input: &Input, format: TypeFormat) -> Result<ExitCode, ExitCode> {
+    let source = Source::read(input)?;
+    let ast = source.parse()?;
+
+    let imports = imports::imported_names(&ast, &Source::base_dir(input));
+    let (resolution, _) = gecko_resolver::resolve_with_imports(&ast, &imports);
+    let (types, _) = gecko_typeck::check(&ast, &resolution);
+
+    match format {
+        TypeFormat::Json => println!("{}", inspect_types(&ast, &types, &source.text)),
+        TypeFormat::Table => print_type_table(&ast, &types, &source.text),
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn print_type_table(ast: &Ast, types: &gecko_typeck::TypeTable, source: &str) {
+    let mut rows: Vec<_> = types
+        .bindings()
+        .map(|(decl, ty)| {
+            let span = decl.span(ast);
+            let (line, column) = span.start_position(source);
+            (span.start, format!("{}:{}", line, column), decl.name(ast).as_str(), ty.to_string())
+        })
+        .collect();
+    rows.sort_by_key(|row| row.0);
+
+    let span_width = rows.iter().map(|row| row.1.len()).chain([4]).max().unwrap_or(0);
+    let name_width = rows.iter().map(|row| row.2.len()).chain([4]).max().unwrap_or(0);
+
+    println!("{:span_width$}  {:name_width$}  TYPE", "SPAN", "NAME");
+    for (_, span, name, ty) in rows {
+        println!("{:span_width$}  {:name_width$}  {}", span, name, ty);
+    }
+}
+
+fn main() -> ExitCode {
+    let command = match args::parse(env::args().skip(1)) {
+        Ok(command) => command,
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, args::USAGE);
+            return ExitCode::from(EXIT_USAGE);
+        }
+    };
+
+    let result = match command {
+        Command::Run { input: Input::File(path), watch: true, vm } => {
+            Ok(watch::watch(&path, || run(&Input::File(path.clone()), vm)))
+        }
+        Command::Check { input: Input::File(path), watch: true } => {
+            Ok(watch::watch(&path, || check(&Input::File(path.clone()))))
+        }
+        Command::Run { input, vm, .. } => run(&input, vm),
+        Command::Check { input, .. } => check(&input),
+        Command::Ast { input, format, optimize } => ast(&input, format, optimize),
+        Command::Tokens(input, format) => tokens(&input, format),
+        Command::Types(input, format) => types(&input, format),
+        Command::Lint(input) => lint(&input),
+        Command::Build { input, output, emit } => build(&input, output.as_deref(), emit),
+        Command::Disasm(input) => disasm(&input),
+        Command::Repl => repl::repl(),
+        Command::Help => {
+            println!("{}", args::USAGE);
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Version => {
+            println!("gecko {}", env!("CARGO_PKG_VERSION"));
+            Ok(ExitCode::SUCCESS)
+        }
+    };
+
+    result.unwrap_or_else(|code| code)
 }
@@ -0,0 +1,210 @@
+use std::{env, path::PathBuf, process::ExitCode, rc::Rc};
+
+use gecko_error::Error;
+use gecko_inspector::{inspect, inspect_token_table};
+use gecko_interpreter::{value::Value, Interpreter};
+use gecko_lexer::{token::Token, ttype::TType, Lexer};
+use gecko_parser::{ast::Ast, nodes::stmt::Stmt, Parser};
+use rustyline::{error::ReadlineError, DefaultEditor};
+
+const NAME: &str = "<repl>";
+
+const HELP: &str = "\
+Enter statements or expressions; expression results are echoed.
+Input continues on the next line while braces or parentheses are open.
+
+Commands:
+    :ast [code]       Print the AST of code, or of the previous input
+    :tokens [code]    Print the tokens of code, or of the previous input
+    :help             Print this help
+    :quit             Exit the REPL";
+
+/// How a chunk of input lexes: complete, still open, or broken.
+enum Scan {
+    Complete(Vec<Token>),
+    Incomplete,
+    Error(Error),
+}
+
+fn scan(input: &str) -> Scan {
+    let tokens = match Lexer::new(input.to_string()).scan_tokens() {
+        Ok(tokens) => tokens,
+        Err(err) if err.message == "Unterminated string" => return Scan::Incomplete,
+        Err(err) => return Scan::Error(err),
+    };
+
+    let mut depth = 0;
+    for token in &tokens {
+        match token.ttype {
+            TType::LBRACE | TType::LPAREN => depth += 1,
+            TType::RBRACE | TType::RPAREN => depth -= 1,
+            _ => {}
+        }
+    }
+
+    if depth > 0 {
+        Scan::Incomplete
+    } else {
+        Scan::Complete(tokens)
+    }
+}
+
+/// Parses a complete input. A bare expression like `1 + 2` is accepted
+/// without its trailing `;`.
+fn parse(input: &str, tokens: Vec<Token>) -> Result<Ast, Error> {
+    match Parser::new(tokens).parse() {
+        Ok(ast) => Ok(ast),
+        Err(err) => {
+            let with_semicolon = format!("{};", input);
+            Lexer::new(with_semicolon)
+                .scan_tokens()
+                .and_then(|tokens| Parser::new(tokens).parse())
+                .map_err(|_| err)
+        }
+    }
+}
+
+fn history_path() -> Option<PathBuf> {
+    env::var_os("HOME").map(|home| PathBuf::from(home).join(".gecko_history"))
+}
+
+struct Repl {
+    interpreter: Interpreter,
+    previous: String,
+}
+
+impl Repl {
+    fn eval(&mut self, input: &str, tokens: Vec<Token>) -> Result<(), Error> {
+        let mut ast = parse(input, tokens)?;
+
+        let last = match ast.root.last().map(|&id| &ast[id]) {
+            Some(Stmt::ExprStmt(stmt)) => Some(stmt.expr),
+            _ => None,
+        };
+        if last.is_some() {
+            ast.root.pop();
+        }
+
+        self.interpreter.run(Rc::new(ast))?;
+
+        if let Some(expr) = last {
+            let value = self.interpreter.evaluate(expr)?;
+            if !matches!(value, Value::Void) {
+                println!("{:?}", value);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn command(&mut self, line: &str) -> bool {
+        let (command, code) = match line.split_once(char::is_whitespace) {
+            Some((command, code)) => (command, code.trim()),
+            None => (line, ""),
+        };
+        let code = if code.is_empty() { self.previous.clone() } else { code.to_string() };
+
+        match command {
+            ":q" | ":quit" | ":exit" => return false,
+            ":h" | ":help" => println!("{}", HELP),
+            ":ast" => match scan(&code) {
+                Scan::Complete(tokens) => match parse(&code, tokens) {
+                    Ok(ast) => println!("{}", inspect(&ast)),
+                    Err(err) => eprint!("{}", err.display(NAME, &code)),
+                },
+                Scan::Incomplete => eprintln!("error: incomplete input"),
+                Scan::Error(err) => eprint!("{}", err.display(NAME, &code)),
+            },
+            ":tokens" => match Lexer::new(code.clone()).scan_tokens() {
+                Ok(tokens) => print!("{}", inspect_token_table(&tokens, &code)),
+                Err(err) => eprint!("{}", err.display(NAME, &code)),
+            },
+            other => eprintln!("error: unknown command '{}', try :help", other),
+        }
+
+        true
+    }
+}
+
+pub fn repl() -> Result<ExitCode, ExitCode> {
+    let mut editor = match DefaultEditor::new() {
+        Ok(editor) => editor,
+        Err(err) => {
+            eprintln!("error: cannot start the REPL: {}", err);
+            return Err(ExitCode::FAILURE);
+        }
+    };
+
+    let history = history_path();
+    if let Some(path) = &history {
+        let _ = editor.load_history(path);
+    }
+
+    println!("gecko {} - type :help for help, :quit to exit", env!("CARGO_PKG_VERSION"));
+
+    let mut repl = Repl {
+        interpreter: Interpreter::new(),
+        previous: String::new(),
+    };
+    let mut buffer = String::new();
+
+    loop {
+        let prompt = if buffer.is_empty() { "> " } else { "... " };
+
+        let line = match editor.readline(prompt) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => {
+                buffer.clear();
+                continue;
+            }
+            Err(ReadlineError::Eof) => break,
+            Err(err) => {
+                eprintln!("error: {}", err);
+                break;
+            }
+        };
+
+        if buffer.is_empty() && line.trim_start().starts_with(':') {
+            let _ = editor.add_history_entry(line.as_str());
+            if !repl.command(line.trim()) {
+                break;
+            }
+            continue;
+        }
+
+        if !buffer.is_empty() {
+            buffer.push('\n');
+        }
+        buffer.push_str(&line);
+
+        if buffer.trim().is_empty() {
+            buffer.clear();
+            continue;
+        }
+
+        let tokens = match scan(&buffer) {
+            Scan::Incomplete => continue,
+            Scan::Complete(tokens) => tokens,
+            Scan::Error(err) => {
+                eprint!("{}", err.display(NAME, &buffer));
+                let _ = editor.add_history_entry(buffer.as_str());
+                buffer.clear();
+                continue;
+            }
+        };
+
+        let input = std::mem::take(&mut buffer);
+        let _ = editor.add_history_entry(input.as_str());
+
+        if let Err(err) = repl.eval(&input, tokens) {
+            eprint!("{}", err.display(NAME, &input));
+        }
+        repl.previous = input;
+    }
+
+    if let Some(path) = &history {
+        let _ = editor.save_history(path);
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,127 @@
+use std::{
+    collections::HashSet,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use crate::imports::{file_imports, parse_file};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Runs `action` now and again every time `path` or one of the files it
+/// imports changes, until Ctrl-C is pressed. Returns the exit code of the
+/// last run.
+pub fn watch(path: &str, mut action: impl FnMut() -> Result<ExitCode, ExitCode>) -> ExitCode {
+    loop {
+        // clear the screen and move the cursor to the top-left corner
+        print!("\x1b[2J\x1b[H");
+        io::stdout().flush().ok();
+
+        let code = action().unwrap_or_else(|code| code);
+
+        let files = watched_files(Path::new(path));
+        let stamps = modified_times(&files);
+
+        eprintln!(
+            "\n[{}; watching {} file{}, press Ctrl-C to exit]",
+            if code == ExitCode::SUCCESS { "passed" } else { "failed" },
+            files.len(),
+            if files.len() == 1 { "" } else { "s" }
+        );
+
+        interrupt::catch();
+        while modified_times(&files) == stamps {
+            if interrupt::interrupted() {
+                return code;
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+        interrupt::release();
+    }
+}
+
+/// While the loop waits for changes, Ctrl-C stops it instead of killing
+/// the process, so it can exit with the last run's code. During a run,
+/// Ctrl-C kills the process as usual. Elsewhere than Unix, Ctrl-C always
+/// kills it.
+#[cfg(unix)]
+mod interrupt {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+    extern "C" fn handle(_: libc::c_int) {
+        INTERRUPTED.store(true, Ordering::SeqCst);
+    }
+
+    /// Catches Ctrl-C until [`release`] is called.
+    pub fn catch() {
+        // SAFETY: the handler only stores to an atomic.
+        unsafe {
+            libc::signal(libc::SIGINT, handle as extern "C" fn(libc::c_int) as libc::sighandler_t);
+        }
+    }
+
+    /// Restores the default action for Ctrl-C.
+    pub fn release() {
+        // SAFETY: SIG_DFL is always a valid disposition.
+        unsafe {
+            libc::signal(libc::SIGINT, libc::SIG_DFL);
+        }
+    }
+
+    pub fn interrupted() -> bool {
+        INTERRUPTED.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(not(unix))]
+mod interrupt {
+    pub fn catch() {}
+
+    pub fn release() {}
+
+    pub fn interrupted() -> bool {
+        false
+    }
+}
+
+fn modified_times(files: &[PathBuf]) -> Vec<Option<SystemTime>> {
+    files
+        .iter()
+        .map(|file| fs::metadata(file).and_then(|meta| meta.modified()).ok())
+        .collect()
+}
+
+/// The file itself plus everything it transitively imports with
+/// `import "file.gk";`. Imports resolve relative to the entry file's
+/// directory, the same way the interpreter resolves them.
+fn watched_files(entry: &Path) -> Vec<PathBuf> {
+    let base_dir = entry.parent().unwrap_or(Path::new(".")).to_path_buf();
+
+    let mut files = vec![entry.to_path_buf()];
+    let mut seen: HashSet<PathBuf> = files.iter().cloned().collect();
+    let mut next = 0;
+
+    while next < files.len() {
+        let file = files[next].clone();
+        next += 1;
+
+        let Some(ast) = parse_file(&file) else {
+            continue;
+        };
+
+        for import in file_imports(&ast) {
+            let import = base_dir.join(import.as_str());
+            if seen.insert(import.clone()) {
+                files.push(import);
+            }
+        }
+    }
+
+    files
+}
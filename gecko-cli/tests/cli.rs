@@ -0,0 +1,100 @@
+use std::{
+    env, fs,
+    process::{Command, Output},
+};
+
+fn gecko(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_gecko")).args(args).output().unwrap()
+}
+
+fn stdout(output: &Output) -> &str {
+    std::str::from_utf8(&output.stdout).unwrap()
+}
+
+fn stderr(output: &Output) -> &str {
+    std::str::from_utf8(&output.stderr).unwrap()
+}
+
+#[test]
+fn eval_snippets_can_use_io_without_importing_it() {
+    let output = gecko(&["-e", "io.println(\"hi\");"]);
+    assert_eq!(stdout(&output), "hi\n", "{}", stderr(&output));
+    assert!(output.status.success());
+
+    let output = gecko(&["run", "--vm", "-e", "io.println(\"hi\");"]);
+    assert_eq!(stdout(&output), "hi\n", "{}", stderr(&output));
+
+    let output = gecko(&["check", "-e", "io.println(\"hi\");"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    // A snippet that imports it itself, or declares its own `io`.
+    let output = gecko(&["-e", "import std.io; io.println(\"hi\");"]);
+    assert_eq!(stdout(&output), "hi\n", "{}", stderr(&output));
+    let output = gecko(&["-e", "let io = 1; io.println(\"hi\");"]);
+    assert!(stderr(&output).contains("error: Cannot access 'println' on a number"), "{}", stderr(&output));
+}
+
+#[test]
+fn files_still_need_to_import_io() {
+    let dir = env::temp_dir().join(format!("gecko-cli-io-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("prog.gk");
+    fs::write(&path, "io.println(\"hi\");").unwrap();
+
+    let output = gecko(&["run", path.to_str().unwrap()]);
+    assert!(stderr(&output).contains("error: Undefined variable 'io'"), "{}", stderr(&output));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn main_returns_the_exit_code() {
+    for (value, code) in [("3", 3), ("-1", 255), ("256", 0), ("300", 44), ("2.9", 2)] {
+        let program = format!("fn main() -> number {{ return {}; }}", value);
+        assert_eq!(gecko(&["-e", &program]).status.code(), Some(code), "{}", program);
+        assert_eq!(gecko(&["run", "--vm", "-e", &program]).status.code(), Some(code), "{}", program);
+    }
+}
+
+#[test]
+fn check_knows_the_names_imported_files_declare() {
+    let dir = env::temp_dir().join(format!("gecko-cli-imports-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("lib.gk"), "import \"util.gk\";\nfn helper(n: number) -> number { return n + limit; }").unwrap();
+    fs::write(dir.join("util.gk"), "let limit = 3;").unwrap();
+    let path = dir.join("prog.gk");
+    let path = path.to_str().unwrap();
+
+    fs::write(path, "import \"lib.gk\";\nhelper(limit);").unwrap();
+    let output = gecko(&["check", path]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stderr(&output), "");
+
+    fs::write(path, "import \"lib.gk\";\nhelper(missing);").unwrap();
+    let output = gecko(&["check", path]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("error: Undefined name 'missing'"), "{}", stderr(&output));
+
+    // A file that can't be read only makes undefined names warnings.
+    fs::write(path, "import \"gone.gk\";\nhelper(1);").unwrap();
+    let output = gecko(&["check", path]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stderr(&output).contains("warning: Undefined name 'helper'"), "{}", stderr(&output));
+    assert!(stderr(&output).contains("it may be declared by \"gone.gk\""), "{}", stderr(&output));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn division_by_zero_that_never_runs_does_not_stop_the_program() {
+    let program = "fn never() -> number { return 1 / 0; }\nif false { io.println(1 / 0); }\nio.println(\"ok\");";
+    for args in [&["-e", program][..], &["run", "--vm", "-e", program]] {
+        let output = gecko(args);
+        assert_eq!(stdout(&output), "ok\n", "{}", stderr(&output));
+        assert!(output.status.success());
+    }
+
+    let output = gecko(&["check", "-e", program]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stderr(&output).matches("warning: Division by zero").count(), 1, "{}", stderr(&output));
+}
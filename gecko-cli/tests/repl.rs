@@ -0,0 +1,107 @@
+use std::{
+    env, fs,
+    io::Write,
+    process::{Command, Stdio},
+};
+
+/// Feeds `input` to `gecko repl` and returns what it printed to stdout,
+/// without the banner, and to stderr.
+fn repl(name: &str, input: &str) -> (String, String) {
+    // The history file is saved under $HOME.
+    let home = env::temp_dir().join(format!("gecko-repl-{}-{}", std::process::id(), name));
+    fs::create_dir_all(&home).unwrap();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_gecko"))
+        .arg("repl")
+        .env("HOME", &home)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    fs::remove_dir_all(&home).unwrap();
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let (banner, rest) = stdout.split_once('\n').unwrap();
+    assert!(banner.starts_with("gecko "), "{}", banner);
+    (rest.to_string(), String::from_utf8(output.stderr).unwrap())
+}
+
+#[test]
+fn expressions_are_echoed_without_a_semicolon() {
+    let (stdout, stderr) = repl("echo", "1 + 2\nlet a = 4;\na * 2;\nimport std.io;\nio.println(\"hi\")\n");
+
+    assert_eq!(stdout, "3\n8\nhi\n");
+    assert_eq!(stderr, "");
+}
+
+#[test]
+fn input_continues_while_braces_parentheses_or_strings_are_open() {
+    let input = "fn add(a: number,
+       b: number) -> number {
+    return a + b;
+}
+add(1,
+    2)
+let s = \"two
+lines\";
+s
+";
+    let (stdout, stderr) = repl("continue", input);
+
+    assert_eq!(stdout, "3\n\"two\\nlines\"\n");
+    assert_eq!(stderr, "");
+}
+
+#[test]
+fn errors_are_reported_and_the_session_goes_on() {
+    let input = "let a = 1;
+1 +;
+@
+missing
+fn broken() {
+    return 1 +;
+}
+a + 1
+";
+    let (stdout, stderr) = repl("errors", input);
+
+    assert_eq!(stdout, "2\n");
+    let messages: Vec<_> = stderr.lines().filter(|line| line.starts_with("error: ")).collect();
+    assert_eq!(
+        messages,
+        [
+            "error: Expect expression.",
+            "error: Unexpected character: @",
+            "error: Undefined variable 'missing'",
+            "error: Expect expression.",
+        ]
+    );
+    // Errors point at the line within the input they came from.
+    assert!(stderr.contains(" --> <repl>:2:"), "{}", stderr);
+}
+
+#[test]
+fn commands_default_to_the_previous_input() {
+    let (stdout, stderr) = repl("commands", "1 + 2\n:tokens\n:tokens x\n:nope\n:quit\n4\n");
+
+    let lines: Vec<_> = stdout.lines().map(str::trim_end).collect();
+    assert_eq!(
+        lines,
+        [
+            "3",
+            "SPAN     TYPE    LEXEME",
+            "1:1-1:2  Number  1",
+            "1:3-1:4  PLUS    +",
+            "1:5-1:6  Number  2",
+            "1:6-1:6  EOF",
+            "SPAN     TYPE        LEXEME",
+            "1:1-1:2  Identifier  x",
+            "1:2-1:2  EOF",
+        ]
+    );
+    assert_eq!(stderr, "error: unknown command ':nope', try :help\n");
+}
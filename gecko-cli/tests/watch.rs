@@ -0,0 +1,80 @@
+#![cfg(unix)]
+
+use std::{
+    env, fs,
+    io::{BufRead, BufReader},
+    os::unix::process::ExitStatusExt,
+    path::Path,
+    process::{Child, Command, Stdio},
+    time::Duration,
+};
+
+/// Starts `gecko <command> --watch <path>`.
+fn watch(command: &str, path: &Path) -> Child {
+    Command::new(env!("CARGO_BIN_EXE_gecko"))
+        .args([command, "--watch", path.to_str().unwrap()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap()
+}
+
+/// Reads stderr up to the next status line and returns that line.
+fn status(stderr: &mut impl BufRead) -> String {
+    let mut line = String::new();
+    loop {
+        line.clear();
+        assert_ne!(stderr.read_line(&mut line).unwrap(), 0, "gecko exited before reporting a status");
+        if line.contains("watching") {
+            return line.trim().to_string();
+        }
+    }
+}
+
+fn interrupt(child: &Child) {
+    let status = Command::new("kill").args(["-s", "INT", &child.id().to_string()]).status().unwrap();
+    assert!(status.success());
+}
+
+#[test]
+fn each_run_reports_a_status_and_ctrl_c_exits_with_the_last_code() {
+    let dir = env::temp_dir().join(format!("gecko-cli-watch-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("prog.gk");
+    fs::write(&path, "fn main() -> number { return 0; }").unwrap();
+
+    let mut child = watch("run", &path);
+    let mut stderr = BufReader::new(child.stderr.take().unwrap());
+    assert_eq!(status(&mut stderr), "[passed; watching 1 file, press Ctrl-C to exit]");
+
+    // Modification times can be too coarse to see a change made right away.
+    std::thread::sleep(Duration::from_millis(1100));
+    fs::write(&path, "fn main() -> number { return missing; }").unwrap();
+    assert_eq!(status(&mut stderr), "[failed; watching 1 file, press Ctrl-C to exit]");
+
+    interrupt(&child);
+    assert_eq!(child.wait().unwrap().code(), Some(1));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn ctrl_c_during_a_run_stops_the_program() {
+    let dir = env::temp_dir().join(format!("gecko-cli-watch-busy-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("prog.gk");
+    // Takes several seconds in a debug build.
+    fs::write(
+        &path,
+        "fn fib(n: number) -> number { if n < 2 { return n; } return fib(n - 1) + fib(n - 2); }\n\
+         fn main() -> number { return fib(30); }",
+    )
+    .unwrap();
+
+    let mut child = watch("run", &path);
+    std::thread::sleep(Duration::from_millis(300));
+    interrupt(&child);
+    assert_eq!(child.wait().unwrap().signal(), Some(libc::SIGINT));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
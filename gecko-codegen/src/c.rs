@@ -0,0 +1,499 @@
+//! Translates a type checked program to one C99 file that needs nothing but
+//! the C standard library.
+//!
+//! `number`, `string` and `bool` become `double`, `const char *` and
+//! `bool`. Functions become C functions, declared before any code so they
+//! can call each other in any order, and nested ones are lifted out of the
+//! function that declares them. That only works because C functions can't
+//! capture names, so a function that uses a local of an enclosing function
+//! is rejected, as are functions and modules used as values. Top-level
+//! names become file-scope variables set by `gecko_init`, which runs the
+//! top-level statements before `main` is called.
+//!
+//! C leaves the order in which arguments and operands are evaluated
+//! unspecified, so a program whose output depends on it may print in
+//! another order than under the interpreter.
+
+use std::collections::{HashMap, HashSet};
+
+use gecko_error::{Error, LineInfo};
+use gecko_lexer::symbol::Symbol;
+use gecko_parser::{
+    ast::{Ast, ExprId, StmtId},
+    nodes::{
+        expr::{BinaryExpr, BinaryOp, CallExpr, Expr, LiteralExpr, Type, UnaryOp},
+        stmt::{If, Stmt},
+    },
+};
+use gecko_resolver::{Decl, Resolution};
+use gecko_typeck::{always_returns, Ty, TypeTable};
+
+/// The helpers generated code calls, included at the top of every file.
+const RUNTIME: &str = include_str!("c/runtime.c");
+
+/// Translates `ast`, which must have resolved and type checked without
+/// errors, to C.
+pub fn emit(ast: &Ast, resolution: &Resolution, types: &TypeTable) -> Result<String, Error> {
+    let mut emitter = Emitter {
+        ast,
+        resolution,
+        types,
+        names: HashMap::new(),
+        globals: HashSet::new(),
+        locals: HashSet::new(),
+        owners: HashMap::new(),
+        function: None,
+        pending: vec![],
+        prototypes: String::new(),
+        definitions: String::new(),
+    };
+
+    // Top-level names first, so nested functions are the ones renamed if
+    // their names clash.
+    let mut variables = String::new();
+    for &id in &ast.root {
+        match &ast[id] {
+            Stmt::VarDecl(var) => {
+                let ty = c_type(types.decl(Decl::Var(id)), var.name.span)?;
+                let name = emitter.global(Decl::Var(id), var.name.name);
+                variables.push_str(&format!("static {};\n", declaration(ty, &name)));
+            }
+            Stmt::FnDecl(func) => {
+                emitter.global(Decl::Fn(id), func.name.name);
+            }
+            _ => {}
+        }
+    }
+
+    let init = emitter.block(&ast.root, 1)?;
+    let mut next = 0;
+    while let Some(&id) = emitter.pending.get(next) {
+        emitter.function(id)?;
+        next += 1;
+    }
+
+    let mut out = format!("/* Generated by gecko. */\n\n{}\n", RUNTIME);
+    if !variables.is_empty() {
+        out.push_str(&format!("{}\n", variables));
+    }
+    if !emitter.prototypes.is_empty() {
+        out.push_str(&format!("{}\n", emitter.prototypes));
+    }
+    out.push_str(&format!("static void gecko_init(void) {{\n{}}}\n", init));
+    out.push_str(&emitter.definitions);
+    out.push_str(&format!("\nint main(void) {{\n    gecko_init();\n{}}}\n", emitter.main()?));
+
+    Ok(out)
+}
+
+struct Emitter<'a> {
+    ast: &'a Ast,
+    resolution: &'a Resolution,
+    types: &'a TypeTable,
+    /// The C name of every name declared so far.
+    names: HashMap<Decl, String>,
+    /// The C names at file scope and in the function being emitted, kept
+    /// apart by their prefixes.
+    globals: HashSet<String>,
+    locals: HashSet<String>,
+    /// The function each local was declared in, or `None` for a local of
+    /// `gecko_init`.
+    owners: HashMap<Decl, Option<StmtId>>,
+    /// The function being emitted, or `None` for `gecko_init`.
+    function: Option<StmtId>,
+    /// Functions declared but not emitted yet.
+    pending: Vec<StmtId>,
+    prototypes: String,
+    definitions: String,
+}
+
+impl Emitter<'_> {
+    /// Names a file-scope variable or function `gk_<name>`, with a number
+    /// after it if a nested function of the same name took that already.
+    fn global(&mut self, decl: Decl, name: Symbol) -> String {
+        let name = unique(&mut self.globals, format!("gk_{}", name));
+        self.names.insert(decl, name.clone());
+        name
+    }
+
+    /// Names a local `l_<name>`. Numbering the names that are reused within
+    /// a function keeps `let x = x + 1;` in a nested block reading the outer
+    /// `x`, which C would otherwise shadow in its own initializer.
+    fn local(&mut self, decl: Decl, name: Symbol) -> String {
+        let name = unique(&mut self.locals, format!("l_{}", name));
+        self.names.insert(decl, name.clone());
+        self.owners.insert(decl, self.function);
+        name
+    }
+
+    fn function(&mut self, id: StmtId) -> Result<(), Error> {
+        let ast = self.ast;
+        let Stmt::FnDecl(func) = &ast[id] else {
+            unreachable!("{:?} is not a function", ast[id]);
+        };
+        let Ty::Fn { ret, .. } = self.types.decl(Decl::Fn(id)) else {
+            return Err(unknown_type(func.name.span));
+        };
+
+        self.function = Some(id);
+        self.locals.clear();
+
+        let ret = match **ret {
+            Ty::Void => "void",
+            ref ty => c_type(ty, func.name.span)?,
+        };
+        let mut params = vec![];
+        for (index, param) in func.params.iter().enumerate() {
+            let decl = Decl::Param { func: id, index };
+            let ty = c_type(self.types.decl(decl), param.name.span)?;
+            let name = self.local(decl, param.name.name);
+            params.push(declaration(ty, &name));
+        }
+        let params = if params.is_empty() { String::from("void") } else { params.join(", ") };
+
+        let signature = format!("static {}({})", declaration(ret, &self.names[&Decl::Fn(id)]), params);
+        let mut body = self.block(&func.body, 1)?;
+        if ret != "void" && !always_returns(ast, &func.body) {
+            body.push_str(&format!("    gecko_no_return(\"{}\", {});\n", func.name.name, func.name.span.line));
+        }
+        self.prototypes.push_str(&format!("{};\n", signature));
+        self.definitions.push_str(&format!("\n{} {{\n{}}}\n", signature, body));
+        Ok(())
+    }
+
+    /// The body of C's `main`, after `gecko_init` has run.
+    fn main(&self) -> Result<String, Error> {
+        let ast = self.ast;
+        let main = ast.root.iter().rev().find_map(|&id| match &ast[id] {
+            Stmt::FnDecl(func) if func.name.name.as_str() == "main" => Some((id, func)),
+            _ => None,
+        });
+
+        let Some((id, func)) = main else {
+            return match ast.root.iter().find(|&&id| matches!(&ast[id], Stmt::VarDecl(var) if var.name.name.as_str() == "main")) {
+                Some(&id) => Err(Error::new(*ast[id].span(), String::from("'main' is not a function"))),
+                None => Ok(String::from("    return 0;\n")),
+            };
+        };
+        if !func.params.is_empty() {
+            return Err(Error::new(func.span, String::from("'main' cannot take parameters")));
+        }
+
+        let name = &self.names[&Decl::Fn(id)];
+        Ok(match self.types.decl(Decl::Fn(id)) {
+            Ty::Fn { ret, .. } if **ret == Ty::Number => format!("    return gecko_exit_code({}());\n", name),
+            _ => format!("    {}();\n    return 0;\n", name),
+        })
+    }
+
+    fn block(&mut self, stmts: &[StmtId], depth: usize) -> Result<String, Error> {
+        let mut out = String::new();
+        for &id in stmts {
+            out.push_str(&self.stmt(id, depth)?);
+        }
+        Ok(out)
+    }
+
+    fn stmt(&mut self, id: StmtId, depth: usize) -> Result<String, Error> {
+        let ast = self.ast;
+        let indent = "    ".repeat(depth);
+
+        Ok(match &ast[id] {
+            Stmt::ExprStmt(stmt) => match self.effect(stmt.expr)? {
+                Some(code) => format!("{}{};\n", indent, code),
+                None => String::new(),
+            },
+            Stmt::VarDecl(var) => {
+                let ty = c_type(self.types.decl(Decl::Var(id)), var.name.span)?;
+                let initializer = match var.initializer {
+                    Some(initializer) => strip_parens(&self.expr(initializer)?).to_string(),
+                    None => return Err(unknown_type(var.name.span)),
+                };
+                match self.names.get(&Decl::Var(id)) {
+                    Some(name) => format!("{}{} = {};\n", indent, name, initializer),
+                    None => {
+                        let name = self.local(Decl::Var(id), var.name.name);
+                        format!("{}{} = {};\n", indent, declaration(ty, &name), initializer)
+                    }
+                }
+            }
+            Stmt::FnDecl(func) => {
+                if !self.names.contains_key(&Decl::Fn(id)) {
+                    self.global(Decl::Fn(id), func.name.name);
+                }
+                self.pending.push(id);
+                String::new()
+            }
+            Stmt::Return(ret) => {
+                let returns_value = match self.function {
+                    Some(func) => !matches!(self.types.decl(Decl::Fn(func)), Ty::Fn { ret, .. } if **ret == Ty::Void),
+                    None => false,
+                };
+                match ret.value {
+                    Some(value) if returns_value => {
+                        format!("{}return {};\n", indent, strip_parens(&self.expr(value)?))
+                    }
+                    Some(value) => match self.effect(value)? {
+                        Some(code) => format!("{}{};\n{}return;\n", indent, code, indent),
+                        None => format!("{}return;\n", indent),
+                    },
+                    None => format!("{}return;\n", indent),
+                }
+            }
+            Stmt::If(stmt) => format!("{}{}\n", indent, self.if_stmt(stmt, depth)?),
+            Stmt::LangImport(import) => {
+                let path = import.path.iter().map(|s| s.as_str()).collect::<Vec<_>>().join(".");
+                if path != "std.io" {
+                    return Err(Error::new(import.span, format!("Unknown module '{}'", path)));
+                }
+                String::new()
+            }
+            Stmt::FileImport(import) => return Err(unsupported(import.span, "file imports")),
+        })
+    }
+
+    /// An `if` and its `else` branches, from `if` to the last `}`.
+    fn if_stmt(&mut self, stmt: &If, depth: usize) -> Result<String, Error> {
+        let indent = "    ".repeat(depth);
+        let condition = self.value(stmt.condition)?;
+        let mut out = format!("if ({}) {{\n{}{}}}", strip_parens(&condition), self.block(&stmt.then_branch, depth + 1)?, indent);
+
+        match stmt.else_branch.as_deref() {
+            Some(&[id]) if matches!(self.ast[id], Stmt::If(_)) => {
+                let Stmt::If(else_if) = &self.ast[id] else { unreachable!() };
+                out.push_str(&format!(" else {}", self.if_stmt(else_if, depth)?));
+            }
+            Some(else_branch) => {
+                out.push_str(&format!(" else {{\n{}{}}}", self.block(else_branch, depth + 1)?, indent));
+            }
+            None => {}
+        }
+        Ok(out)
+    }
+
+    /// The code that evaluates an expression for its side effects, if it
+    /// has any.
+    fn effect(&mut self, id: ExprId) -> Result<Option<String>, Error> {
+        Ok(match &self.ast[id] {
+            Expr::Call(call) => Some(self.call(call)?),
+            Expr::Grouping(grouping) => self.effect(grouping.expression)?,
+            Expr::Literal(LiteralExpr { value: Type::Void, .. }) => None,
+            _ => Some(format!("(void) {}", self.value(id)?)),
+        })
+    }
+
+    /// An expression whose value C can hold.
+    fn value(&mut self, id: ExprId) -> Result<String, Error> {
+        c_type(self.types.expr(id), *self.ast[id].span())?;
+        self.expr(id)
+    }
+
+    fn expr(&mut self, id: ExprId) -> Result<String, Error> {
+        let ast = self.ast;
+        match &ast[id] {
+            Expr::Literal(literal) => self.literal(id, literal),
+            Expr::Grouping(grouping) => self.expr(grouping.expression),
+            Expr::Unary(unary) => {
+                let right = self.value(unary.right)?;
+                Ok(match unary.operator {
+                    UnaryOp::Neg => format!("(-{})", right),
+                    UnaryOp::Not => format!("(!{})", right),
+                })
+            }
+            Expr::Binary(binary) => self.binary(binary),
+            Expr::Call(call) => self.call(call),
+            Expr::Get(get) => Err(unsupported(get.span, "modules as values")),
+        }
+    }
+
+    fn literal(&mut self, id: ExprId, literal: &LiteralExpr) -> Result<String, Error> {
+        Ok(match literal.value {
+            Type::Int(n) => format!("{:?}", n as f64),
+            Type::Float(n) => format!("{:?}", n),
+            Type::String(s) => string_literal(s.as_str()),
+            Type::Bool(b) => b.to_string(),
+            Type::Void => return Err(unsupported(literal.span, "void values")),
+            Type::Iden(name) => {
+                let decl = match self.resolution.get(id) {
+                    Some(decl @ (Decl::Var(_) | Decl::Param { .. })) => decl,
+                    Some(Decl::Fn(_)) => return Err(unsupported(literal.span, "functions as values")),
+                    Some(Decl::Module(_)) => return Err(unsupported(literal.span, "modules as values")),
+                    None => return Err(Error::new(literal.span, format!("Undefined name '{}'", name))),
+                };
+                if let Some(&owner) = self.owners.get(&decl) {
+                    if owner != self.function {
+                        return Err(Error::new(
+                            literal.span,
+                            format!("C functions can't capture '{}' from outside them", name),
+                        ));
+                    }
+                }
+                self.names[&decl].clone()
+            }
+            Type::Unknown => {
+                return Err(Error::new(literal.span, String::from("Cannot evaluate an expression of unknown type")))
+            }
+        })
+    }
+
+    fn binary(&mut self, binary: &BinaryExpr) -> Result<String, Error> {
+        let left = self.value(binary.left)?;
+        let right = self.value(binary.right)?;
+        let strings = *self.types.expr(binary.left) == Ty::String || *self.types.expr(binary.right) == Ty::String;
+
+        let operator = match binary.operator {
+            BinaryOp::Add if strings => return Ok(format!("gecko_concat({}, {})", left, right)),
+            BinaryOp::Eq if strings => return Ok(format!("gecko_string_eq({}, {})", left, right)),
+            BinaryOp::NotEq if strings => return Ok(format!("(!gecko_string_eq({}, {}))", left, right)),
+            BinaryOp::Div => {
+                let line = binary.operator_span.line;
+                return Ok(format!("gecko_div({}, {}, {})", left, right, line));
+            }
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::Mul => "*",
+            BinaryOp::Eq => "==",
+            BinaryOp::NotEq => "!=",
+            BinaryOp::Less => "<",
+            BinaryOp::LessEq => "<=",
+            BinaryOp::Greater => ">",
+            BinaryOp::GreaterEq => ">=",
+        };
+        Ok(format!("({} {} {})", left, operator, right))
+    }
+
+    fn call(&mut self, call: &CallExpr) -> Result<String, Error> {
+        let ast = self.ast;
+        match &ast[call.callee] {
+            Expr::Literal(LiteralExpr { value: Type::Iden(_), span }) => {
+                let Some(decl @ Decl::Fn(_)) = self.resolution.get(call.callee) else {
+                    return Err(Error::new(*span, String::from("C can only call functions by their name")));
+                };
+                let mut args = vec![];
+                for &arg in &call.args {
+                    args.push(strip_parens(&self.value(arg)?).to_string());
+                }
+                Ok(format!("{}({})", self.names[&decl], args.join(", ")))
+            }
+            Expr::Get(get) => {
+                let Some(Decl::Module(module)) = self.resolution.get(get.object) else {
+                    return Err(Error::new(get.span, String::from("C can only call functions by their name")));
+                };
+                let Stmt::LangImport(import) = &ast[module] else {
+                    unreachable!("{:?} is not an import", ast[module]);
+                };
+
+                let function = match get.name.name.as_str() {
+                    "println" => "gecko_println",
+                    "print" => "gecko_print",
+                    member => {
+                        let module = import.path.last().unwrap();
+                        return Err(Error::new(get.name.span, format!("Module '{}' has no member '{}'", module, member)));
+                    }
+                };
+                if call.args.len() != 1 {
+                    return Err(Error::new(call.paren, format!("Expected 1 arguments but got {}", call.args.len())));
+                }
+                Ok(format!("{}({})", function, self.string(call.args[0])?))
+            }
+            other => Err(Error::new(*other.span(), String::from("C can only call functions by their name"))),
+        }
+    }
+
+    /// An expression as the string the interpreter would print for it.
+    fn string(&mut self, id: ExprId) -> Result<String, Error> {
+        Ok(match self.types.expr(id) {
+            Ty::Number => format!("gecko_number_string({})", self.expr(id)?),
+            Ty::String => self.expr(id)?,
+            Ty::Bool => format!("gecko_bool_string({})", self.expr(id)?),
+            Ty::Void => match self.effect(id)? {
+                Some(code) => format!("({}, \"void\")", code),
+                None => String::from("\"void\""),
+            },
+            ty => return Err(c_type(ty, *self.ast[id].span()).unwrap_err()),
+        })
+    }
+}
+
+/// The C type of values of `ty`, or an error at `span` if C can't hold
+/// them.
+fn c_type(ty: &Ty, span: LineInfo) -> Result<&'static str, Error> {
+    match ty {
+        Ty::Number => Ok("double"),
+        Ty::String => Ok("const char *"),
+        Ty::Bool => Ok("bool"),
+        Ty::Void => Err(unsupported(span, "void values")),
+        Ty::Fn { .. } => Err(unsupported(span, "functions as values")),
+        Ty::Module => Err(unsupported(span, "modules as values")),
+        Ty::Unknown => Err(unknown_type(span)),
+    }
+}
+
+fn unsupported(span: LineInfo, what: &str) -> Error {
+    Error::new(span, format!("C code can't use {}", what))
+}
+
+fn unknown_type(span: LineInfo) -> Error {
+    Error::new(span, String::from("C needs to know the type of this"))
+}
+
+/// `ty name`, without a space after a `*`.
+fn declaration(ty: &str, name: &str) -> String {
+    match ty.ends_with('*') {
+        true => format!("{}{}", ty, name),
+        false => format!("{} {}", ty, name),
+    }
+}
+
+/// `name`, or `name` with the first number after it that isn't in `taken`.
+fn unique(taken: &mut HashSet<String>, name: String) -> String {
+    let name = match taken.contains(&name) {
+        false => name,
+        true => (2..).map(|n| format!("{}_{}", name, n)).find(|n| !taken.contains(n)).unwrap(),
+    };
+    taken.insert(name.clone());
+    name
+}
+
+/// An operand without parentheses, where it stands alone.
+fn strip_parens(code: &str) -> &str {
+    match code.strip_prefix('(').and_then(|code| code.strip_suffix(')')) {
+        Some(inner) if balanced(inner) => inner,
+        _ => code,
+    }
+}
+
+fn balanced(code: &str) -> bool {
+    let mut depth = 0i32;
+    for c in code.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => {}
+        }
+        if depth < 0 {
+            return false;
+        }
+    }
+    depth == 0
+}
+
+/// A C string literal for `s`. Anything but printable ASCII is written as
+/// an octal escape, which is never longer than three digits.
+fn string_literal(s: &str) -> String {
+    let mut out = String::from("\"");
+    for byte in s.bytes() {
+        match byte {
+            b'"' => out.push_str("\\\""),
+            b'\\' => out.push_str("\\\\"),
+            b'\n' => out.push_str("\\n"),
+            b'\t' => out.push_str("\\t"),
+            // `??` starts a trigraph in C99.
+            b'?' => out.push_str("\\?"),
+            b' '..=b'~' => out.push(byte as char),
+            _ => out.push_str(&format!("\\{:03o}", byte)),
+        }
+    }
+    out.push('"');
+    out
+}
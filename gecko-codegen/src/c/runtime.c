@@ -0,0 +1,148 @@
+#include <float.h>
+#include <limits.h>
+#include <stdbool.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+/* Strings made while the program runs are never freed: gecko has no loops,
+ * so a program can only make as many as its recursion allows. */
+char *gecko_alloc(size_t size) {
+    char *memory = malloc(size);
+    if (memory == NULL) {
+        fputs("error: Out of memory\n", stderr);
+        exit(1);
+    }
+    return memory;
+}
+
+const char *gecko_concat(const char *a, const char *b) {
+    size_t a_length = strlen(a), b_length = strlen(b);
+    char *out = gecko_alloc(a_length + b_length + 1);
+    memcpy(out, a, a_length);
+    memcpy(out + a_length, b, b_length + 1);
+    return out;
+}
+
+bool gecko_string_eq(const char *a, const char *b) {
+    return strcmp(a, b) == 0;
+}
+
+#ifdef __GNUC__
+#define GECKO_NORETURN __attribute__((noreturn))
+#else
+#define GECKO_NORETURN
+#endif
+
+/* Called at the end of a function that must return a value, in case the
+ * checks that it always does were skipped. */
+GECKO_NORETURN void gecko_no_return(const char *name, int line) {
+    fprintf(stderr, "line %d: '%s' ended without returning a value\n", line, name);
+    exit(1);
+}
+
+double gecko_div(double a, double b, int line) {
+    if (b == 0) {
+        fprintf(stderr, "line %d: Division by zero\n", line);
+        exit(1);
+    }
+    return a / b;
+}
+
+/* Formats a number like the interpreter: the fewest digits that read back
+ * as the same number, written out without an exponent. */
+const char *gecko_number_string(double n) {
+    char scientific[32], digits[20];
+    char *out, *at;
+    int precision, exponent, count = 0, i;
+    bool negative;
+
+    if (n != n) {
+        return "NaN";
+    }
+    if (n > DBL_MAX) {
+        return "inf";
+    }
+    if (n < -DBL_MAX) {
+        return "-inf";
+    }
+
+    for (precision = 1; precision < 17; precision++) {
+        snprintf(scientific, sizeof scientific, "%.*e", precision - 1, n);
+        if (strtod(scientific, NULL) == n) {
+            break;
+        }
+    }
+    snprintf(scientific, sizeof scientific, "%.*e", precision - 1, n);
+
+    /* "-d.ddde+xx": collect the digits and the exponent. */
+    negative = scientific[0] == '-';
+    for (at = scientific + negative; *at != 'e'; at++) {
+        if (*at != '.') {
+            digits[count++] = *at;
+        }
+    }
+    exponent = atoi(at + 1);
+    while (count > 1 && digits[count - 1] == '0') {
+        count--;
+    }
+
+    out = gecko_alloc(count + (exponent < 0 ? -exponent : exponent) + 4);
+    at = out;
+    if (negative) {
+        *at++ = '-';
+    }
+    if (exponent < 0) {
+        *at++ = '0';
+        *at++ = '.';
+        for (i = -1; i > exponent; i--) {
+            *at++ = '0';
+        }
+        for (i = 0; i < count; i++) {
+            *at++ = digits[i];
+        }
+    } else {
+        for (i = 0; i <= exponent; i++) {
+            *at++ = i < count ? digits[i] : '0';
+        }
+        if (count > exponent + 1) {
+            *at++ = '.';
+            for (i = exponent + 1; i < count; i++) {
+                *at++ = digits[i];
+            }
+        }
+    }
+    *at = '\0';
+    return out;
+}
+
+const char *gecko_bool_string(bool b) {
+    return b ? "true" : "false";
+}
+
+void gecko_print(const char *s) {
+    fputs(s, stdout);
+    fflush(stdout);
+}
+
+void gecko_println(const char *s) {
+    puts(s);
+}
+
+/* The exit code for what `main` returned: its integer part modulo 256, as
+ * under the interpreter. Out of range values saturate first, as Rust's
+ * `as i64` does, since converting them to an integer is undefined in C. */
+int gecko_exit_code(double n) {
+    long long whole;
+    if (n != n) {
+        whole = 0;
+    } else if (n >= 9223372036854775807.0) {
+        whole = LLONG_MAX;
+    } else if (n <= -9223372036854775808.0) {
+        whole = LLONG_MIN;
+    } else {
+        whole = (long long) n;
+    }
+    whole %= 256;
+    return (int) (whole < 0 ? whole + 256 : whole);
+}
@@ -0,0 +1,4 @@
+//! Backends that translate a type checked program for running without
+//! gecko: [`c`] emits portable C.
+
+pub mod c;
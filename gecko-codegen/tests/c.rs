@@ -0,0 +1,190 @@
+use std::{
+    env, fs,
+    io::ErrorKind,
+    path::PathBuf,
+    process::{Command, Output},
+};
+
+use gecko_codegen::c::emit;
+use gecko_lexer::Lexer;
+use gecko_parser::Parser;
+
+fn to_c(source: &str) -> Result<String, String> {
+    let tokens = Lexer::new(source.to_string()).scan_tokens().unwrap();
+    let ast = Parser::new(tokens).parse().unwrap();
+    let (resolution, errors) = gecko_resolver::resolve(&ast);
+    assert!(errors.is_empty(), "{:?}", errors);
+    let (types, errors) = gecko_typeck::check(&ast, &resolution);
+    assert!(errors.is_empty(), "{:?}", errors);
+
+    emit(&ast, &resolution, &types).map_err(|err| format!("{}: {}", err.line_info.line, err.message))
+}
+
+/// Compiles the C for `source` with the system compiler (`$CC`, or `cc`)
+/// and runs it, or returns `None` if there is no compiler.
+fn compile_and_run(name: &str, source: &str) -> Option<Output> {
+    run_c(name, &to_c(source).unwrap())
+}
+
+fn run_c(name: &str, c: &str) -> Option<Output> {
+    let dir = env::temp_dir().join(format!("gecko-c-{}-{}", std::process::id(), name));
+    fs::create_dir_all(&dir).unwrap();
+    let c_file = dir.join("program.c");
+    let binary = dir.join("program");
+    fs::write(&c_file, c).unwrap();
+
+    let compiler = env::var("CC").unwrap_or_else(|_| String::from("cc"));
+    let compiled = Command::new(&compiler)
+        .args(["-std=c99", "-pedantic-errors", "-Wall", "-o"])
+        .arg(&binary)
+        .arg(&c_file)
+        .output();
+    let compiled = match compiled {
+        Ok(output) => output,
+        Err(err) if err.kind() == ErrorKind::NotFound => {
+            eprintln!("skipping: no C compiler '{}'", compiler);
+            fs::remove_dir_all(&dir).unwrap();
+            return None;
+        }
+        Err(err) => panic!("cannot run '{}': {}", compiler, err),
+    };
+    assert!(compiled.status.success(), "{}", String::from_utf8_lossy(&compiled.stderr));
+
+    let output = Command::new(&binary).output().unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    Some(output)
+}
+
+#[test]
+fn test_gk_prints_hello_world() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../test.gk");
+    let Some(output) = compile_and_run("hello", &fs::read_to_string(path).unwrap()) else {
+        return;
+    };
+
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "Hello, world!\n");
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn programs_print_what_the_interpreter_prints() {
+    let source = "import std.io;
+let greeting = \"hi\";
+fn fib(n: number) -> number {
+    if n < 2 {
+        return n;
+    } else if n == 2 {
+        return 1;
+    }
+    return fib(n - 1) + fib(n - 2);
+}
+fn describe(n: number) {
+    fn sign(x: number) -> string {
+        if x < 0 {
+            return \"negative\";
+        } else {
+            return \"not negative\";
+        }
+    }
+    io.println(sign(n));
+}
+fn nothing() {}
+fn main() -> number {
+    io.println(fib(20));
+    io.println(greeting + \", \" + \"gecko\");
+    io.println(0.1 + 0.2);
+    io.println(1000000000 * 1000000000 * 1000);
+    io.println(-1 / 800000);
+    io.println(!(1 < 2) == false);
+    io.println(\"a\" != \"a\");
+    io.print(\"no newline \");
+    io.println(\"??= back\\slash \u{e9}\");
+    io.println(nothing());
+    describe(-3);
+    let x = 1;
+    if x == 1 {
+        let x = x + 1;
+        io.println(x);
+    }
+    io.println(x);
+    return fib(10);
+}";
+    let Some(output) = compile_and_run("program", source) else {
+        return;
+    };
+
+    let expected = "6765
+hi, gecko
+0.30000000000000004
+1000000000000000000000
+-0.00000125
+true
+false
+no newline ??= back\\slash \u{e9}
+void
+negative
+2
+1
+";
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), expected);
+    assert_eq!(output.status.code(), Some(55));
+}
+
+#[test]
+fn main_returns_the_exit_code_modulo_256() {
+    for (value, code) in [("-1", 255), ("300", 44), ("2.9", 2)] {
+        let source = format!("fn main() -> number {{ return {}; }}", value);
+        let Some(output) = compile_and_run("exit", &source) else {
+            return;
+        };
+        assert_eq!(output.status.code(), Some(code), "{}", source);
+    }
+}
+
+#[test]
+fn division_by_zero_exits_with_its_line() {
+    let source = "fn divide(a: number, b: number) -> number {\n    return a / b;\n}\nlet a = divide(1, 0);";
+    let Some(output) = compile_and_run("division", source) else {
+        return;
+    };
+
+    assert_eq!(String::from_utf8(output.stderr).unwrap(), "line 2: Division by zero\n");
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn falling_off_the_end_of_a_function_exits_with_its_line() {
+    // Emitted despite the type error, as if the checks had been skipped.
+    let source = "fn f(a: bool) -> number {\n    if a {\n        return 1;\n    }\n}\nlet x = f(false);";
+    let tokens = Lexer::new(source.to_string()).scan_tokens().unwrap();
+    let ast = Parser::new(tokens).parse().unwrap();
+    let (resolution, _) = gecko_resolver::resolve(&ast);
+    let (types, errors) = gecko_typeck::check(&ast, &resolution);
+    assert_eq!(errors.len(), 1);
+
+    let Some(output) = run_c("fallthrough", &emit(&ast, &resolution, &types).unwrap()) else {
+        return;
+    };
+    assert_eq!(String::from_utf8(output.stderr).unwrap(), "line 1: 'f' ended without returning a value\n");
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn code_without_a_c_equivalent_is_rejected() {
+    let errors = [
+        (
+            "fn adder(n: number) -> number {\n fn add(x: number) -> number { return x + n; }\n return add(1);\n}",
+            "2: C functions can't capture 'n' from outside them",
+        ),
+        ("fn f() {}\nlet g = f;", "2: C code can't use functions as values"),
+        ("import std.io;\nlet out = io;", "2: C code can't use modules as values"),
+        ("import \"lib.gk\";", "1: C code can't use file imports"),
+        ("import std.io;\nio.flush();", "2: Module 'io' has no member 'flush'"),
+        ("import std.math;", "1: Unknown module 'std.math'"),
+        ("fn main(a: number) {}", "1: 'main' cannot take parameters"),
+    ];
+
+    for (source, error) in errors {
+        assert_eq!(to_c(source).unwrap_err(), error, "for {}", source);
+    }
+}
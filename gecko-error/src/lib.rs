@@ -1,4 +1,6 @@
-#[derive(Debug, Clone, PartialEq)]
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct LineInfo {
     pub line: usize,
     pub start: usize,
@@ -13,6 +15,36 @@ impl LineInfo {
             end,
         }
     }
+
+    /// The span running from the start of `self` to the end of `other`.
+    pub fn to(&self, other: &LineInfo) -> LineInfo {
+        LineInfo::new(self.line, self.start, other.end)
+    }
+
+    /// 1-based (line, column) of the start of the span in `source`.
+    pub fn start_position(&self, source: &str) -> (usize, usize) {
+        position(source, self.start)
+    }
+
+    /// 1-based (line, column) of the end of the span in `source`.
+    pub fn end_position(&self, source: &str) -> (usize, usize) {
+        position(source, self.end)
+    }
+}
+
+/// Converts a byte offset into `source` to a 1-based (line, column) pair,
+/// counting columns in characters.
+pub fn position(source: &str, offset: usize) -> (usize, usize) {
+    let mut offset = offset.min(source.len());
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+
+    let before = &source[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+    (line, before[line_start..].chars().count() + 1)
 }
 
 #[derive(Debug)]
@@ -21,11 +53,20 @@ pub enum Note {
     Expected(String),
 }
 
+/// How serious a diagnostic is. Warnings are reported but don't stop a
+/// program from running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
 #[derive(Debug)]
 pub struct Error {
     pub line_info: LineInfo,
     pub message: String,
     pub notes: Vec<Note>,
+    pub severity: Severity,
 }
 
 impl Error {
@@ -34,6 +75,7 @@ impl Error {
             line_info,
             message,
             notes: vec![],
+            severity: Severity::Error,
         }
     }
 
@@ -42,6 +84,7 @@ impl Error {
             line_info: LineInfo::new(0, 0, 0),
             message,
             notes: vec![],
+            severity: Severity::Error,
         }
     }
 
@@ -50,11 +93,98 @@ impl Error {
             line_info,
             message,
             notes,
+            severity: Severity::Error,
+        }
+    }
+
+    pub fn warning(line_info: LineInfo, message: String, notes: Vec<Note>) -> Error {
+        Error {
+            line_info,
+            message,
+            notes,
+            severity: Severity::Warning,
+        }
+    }
+
+    pub fn is_warning(&self) -> bool {
+        self.severity == Severity::Warning
+    }
+
+    /// Renders the error against the source it came from, with the offending
+    /// line and a `^^^` marker under the span.
+    pub fn display(&self, filename: &str, source: &str) -> String {
+        let mut out = format!("{}: {}\n", self.severity, self.message);
+
+        match self.source_line(source) {
+            Some((text, offset)) => {
+                // Columns and the marker count characters, like `position`.
+                let (line, column) = self.line_info.start_position(source);
+                let number = line.to_string();
+                let gutter = " ".repeat(number.len());
+                let marked = &text[offset..];
+                let len = self.line_info.end.saturating_sub(self.line_info.start).min(marked.len());
+                let width = marked.get(..len).map_or(len, |span| span.chars().count()).max(1);
+
+                out.push_str(&format!("{}--> {}:{}:{}\n", gutter, filename, line, column));
+                out.push_str(&format!("{} |\n", gutter));
+                out.push_str(&format!("{} | {}\n", number, text));
+                out.push_str(&format!("{} | {}{}\n", gutter, " ".repeat(column - 1), "^".repeat(width)));
+
+                for note in &self.notes {
+                    out.push_str(&format!("{} = {}\n", gutter, note));
+                }
+            }
+            None => {
+                out.push_str(&format!(" --> {}\n", filename));
+
+                for note in &self.notes {
+                    out.push_str(&format!("  = {}\n", note));
+                }
+            }
         }
+
+        out
     }
 
-    #[allow(dead_code)]
-    fn display(&self) {
-        todo!()
+    /// Finds the text of the line the error starts on and the byte offset of
+    /// the error within it. `LineInfo` offsets are byte offsets into the whole
+    /// source, so the line is located from `start` rather than `line`.
+    fn source_line<'a>(&self, source: &'a str) -> Option<(&'a str, usize)> {
+        if self.line_info.line == 0 || self.line_info.start > source.len() {
+            return None;
+        }
+
+        let line_start = source[..self.line_info.start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = source[line_start..].find('\n').map_or(source.len(), |i| line_start + i);
+
+        Some((&source[line_start..line_end], self.line_info.start - line_start))
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => f.write_str("error"),
+            Severity::Warning => f.write_str("warning"),
+        }
+    }
+}
+
+impl fmt::Display for Note {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Note::Note(note) => write!(f, "note: {}", note),
+            Note::Expected(expected) => write!(f, "expected: {}", expected),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line_info.line == 0 {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "line {}: {}", self.line_info.line, self.message)
+        }
     }
 }
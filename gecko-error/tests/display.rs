@@ -0,0 +1,27 @@
+use gecko_error::{Error, LineInfo};
+
+#[test]
+fn columns_and_markers_count_characters() {
+    let source = "let s = \"é\"; let x = s - 1;";
+    let start = source.find("s - 1").unwrap();
+    let err = Error::new(LineInfo::new(1, start, start + 5), String::from("Cannot apply '-'"));
+
+    assert_eq!(
+        err.display("prog.gk", source),
+        "\
+error: Cannot apply '-'
+ --> prog.gk:1:22
+  |
+1 | let s = \"é\"; let x = s - 1;
+  |                      ^^^^^
+"
+    );
+}
+
+#[test]
+fn markers_stop_at_the_end_of_the_line() {
+    let source = "fn é() {\n}";
+    let err = Error::new(LineInfo::new(1, 3, source.len()), String::from("Bad"));
+
+    assert!(err.display("prog.gk", source).ends_with("1 | fn é() {\n  |    ^^^^^\n"));
+}
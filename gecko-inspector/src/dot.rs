@@ -0,0 +1,59 @@
+use gecko_parser::ast::Ast;
+
+use crate::tree::{stmt_to_node, TreeNode};
+
+fn escape(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+struct DotWriter {
+    lines: Vec<String>,
+    next_id: usize,
+}
+
+impl DotWriter {
+    fn node(&mut self, label: &str) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.lines.push(format!("    n{} [label=\"{}\"];", id, escape(label)));
+        id
+    }
+
+    fn edge(&mut self, from: usize, to: usize, label: &str) {
+        self.lines.push(format!("    n{} -> n{} [label=\"{}\"];", from, to, escape(label)));
+    }
+
+    fn tree(&mut self, node: &TreeNode) -> usize {
+        let id = self.node(&node.label);
+        for (edge, child) in &node.children {
+            let child_id = self.tree(child);
+            self.edge(id, child_id, edge);
+        }
+        id
+    }
+}
+
+/// Prints the AST as a Graphviz DOT graph rooted at a `Program` node, with
+/// edges labelled by the field they follow (`left`, `callee`, `args[0]`).
+pub fn inspect_dot(ast: &Ast) -> String {
+    let mut writer = DotWriter {
+        lines: vec![],
+        next_id: 0,
+    };
+
+    let root = writer.node("Program");
+    for (i, &stmt) in ast.root.iter().enumerate() {
+        let id = writer.tree(&stmt_to_node(ast, stmt));
+        writer.edge(root, id, &format!("stmts[{}]", i));
+    }
+
+    let mut out = vec![
+        String::from("digraph ast {"),
+        String::from("    node [shape=box, fontname=\"monospace\"];"),
+        String::from("    edge [fontname=\"monospace\", fontsize=10];"),
+    ];
+    out.extend(writer.lines);
+    out.push(String::from("}"));
+
+    out.join("\n")
+}
@@ -1,130 +1,257 @@
-use std::collections::HashMap;
+mod dot;
+pub mod json;
+mod load;
+mod sexpr;
+pub mod tree;
+mod types;
 
-use gecko_parser::nodes::{stmt::Stmt, expr::Type};
-use gecko_parser::nodes::expr::Expr;
-use tinyjson::{JsonGenerator, JsonValue};
+pub use dot::inspect_dot;
+pub use load::load;
+pub use sexpr::inspect_sexpr;
+pub use tree::inspect_tree;
+pub use types::inspect_types;
 
-fn add_name_to_json(name: String, json: JsonValue) -> JsonValue {
-    let mut map = HashMap::new();
-    map.insert(name, json);
-    JsonValue::Object(map)
+use gecko_parser::{
+    ast::{Ast, ExprId, StmtId},
+    nodes::{expr::{Expr, Type}, stmt::Stmt, Ident, Param},
+};
+use gecko_lexer::{token::Token, ttype::TType};
+use gecko_error::LineInfo;
+use json::Json;
+
+fn add_name_to_json(name: &str, json: Json) -> Json {
+    Json::object(vec![(name, json)])
 }
 
-fn type_to_string(t: Type) -> String {
-    match t {
-        Type::Int(i) => format!("Int({})", i),
-        Type::Float(f) => format!("Float({})", f),
-        Type::String(s) => format!("String({})", s),
-        Type::Bool(b) => format!("Bool({})", b),
-        Type::Iden(i) => format!("Iden({})", i),
-        Type::Void => String::from("Void"),
-        Type::Unknown => String::from("Unknown"),
-    }
+fn type_to_json(t: Type) -> Json {
+    let (name, value) = match t {
+        // A string, since JSON numbers are f64s and can't hold every i64.
+        Type::Int(i) => ("Int", Json::string(&i.to_string())),
+        Type::Float(f) => ("Float", Json::Number(f)),
+        Type::String(s) => ("String", Json::string(s.as_str())),
+        Type::Bool(b) => ("Bool", Json::Bool(b)),
+        Type::Iden(i) => ("Iden", Json::string(i.as_str())),
+        Type::Void => ("Void", Json::Null),
+        Type::Unknown => ("Unknown", Json::Null),
+    };
+
+    Json::object(vec![
+        ("type", Json::string(name)),
+        ("value", value),
+    ])
+}
+
+fn span_to_json(line_info: LineInfo) -> Json {
+    Json::object(vec![
+        ("line", Json::Number(line_info.line as f64)),
+        ("start", Json::Number(line_info.start as f64)),
+        ("end", Json::Number(line_info.end as f64)),
+    ])
+}
+
+fn token_node_to_json(ttype: TType, lexeme: &str, span: LineInfo) -> Json {
+    Json::object(vec![
+        ("type", Json::string(ttype.name())),
+        ("lexeme", Json::string(lexeme)),
+        ("span", span_to_json(span)),
+    ])
 }
 
-fn expr_to_json(expr: Expr) -> JsonValue {
-    match expr {
+fn ident_to_json(ident: Ident) -> Json {
+    token_node_to_json(TType::Identifier(ident.name), ident.name.as_str(), ident.span)
+}
+
+/// Type annotations are written as tokens: the `number`/`string` keywords
+/// or an identifier.
+fn type_name_to_json(type_name: Ident) -> Json {
+    let lexeme = type_name.name.as_str();
+    let ttype = match lexeme {
+        "number" => TType::NUMBER,
+        "string" => TType::STRING,
+        _ => TType::Identifier(type_name.name),
+    };
+    token_node_to_json(ttype, lexeme, type_name.span)
+}
+
+fn optional<T>(value: Option<T>, to_json: impl FnOnce(T) -> Json) -> Json {
+    value.map_or(Json::Null, to_json)
+}
+
+fn param_to_json(param: &Param) -> Json {
+    Json::object(vec![
+        ("name", Json::string(param.name.name.as_str())),
+        ("name_span", span_to_json(param.name.span)),
+        ("type", type_name_to_json(param.type_)),
+        ("span", span_to_json(param.span)),
+    ])
+}
+
+fn expr_to_json(ast: &Ast, id: ExprId) -> Json {
+    match &ast[id] {
         Expr::Literal(lit) => {
-            let mut literal: HashMap<String, JsonValue> = HashMap::new();
-            literal.insert(String::from("value"), JsonValue::String(type_to_string(lit.value)));
-            let json = JsonValue::Object(literal);
-            add_name_to_json(String::from("Literal"), json)
+            let json = Json::object(vec![
+                ("value", type_to_json(lit.value)),
+                ("span", span_to_json(lit.span)),
+            ]);
+            add_name_to_json("Literal", json)
+        },
+        Expr::Grouping(expr) => {
+            let json = Json::object(vec![
+                ("expression", expr_to_json(ast, expr.expression)),
+                ("span", span_to_json(expr.span)),
+            ]);
+            add_name_to_json("Grouping", json)
         },
         Expr::Binary(expr) => {
-            let mut binary: HashMap<String, JsonValue> = HashMap::new();
-            binary.insert(String::from("left"), expr_to_json(expr.left.as_ref().clone()));
-            binary.insert(String::from("operator"), JsonValue::String(expr.operator.lexeme));
-            binary.insert(String::from("right"), expr_to_json(expr.right.as_ref().clone()));
-            let json = JsonValue::Object(binary);
-            add_name_to_json(String::from("Binary"), json)
+            let json = Json::object(vec![
+                ("left", expr_to_json(ast, expr.left)),
+                ("operator", token_node_to_json(expr.operator.ttype(), expr.operator.lexeme(), expr.operator_span)),
+                ("right", expr_to_json(ast, expr.right)),
+                ("span", span_to_json(expr.span)),
+            ]);
+            add_name_to_json("Binary", json)
+        },
+        Expr::Unary(expr) => {
+            let json = Json::object(vec![
+                ("operator", token_node_to_json(expr.operator.ttype(), expr.operator.lexeme(), expr.operator_span)),
+                ("right", expr_to_json(ast, expr.right)),
+                ("span", span_to_json(expr.span)),
+            ]);
+            add_name_to_json("Unary", json)
         },
         Expr::Call(expr) => {
-            let mut call: HashMap<String, JsonValue> = HashMap::new();
-            call.insert(String::from("callee"), expr_to_json(expr.callee.as_ref().clone()));
-            call.insert(String::from("args"), JsonValue::Array(expr.args.into_iter().map(|a| expr_to_json(a.as_ref().clone())).collect()));
-            let json = JsonValue::Object(call);
-            add_name_to_json(String::from("Call"), json)
+            let json = Json::object(vec![
+                ("callee", expr_to_json(ast, expr.callee)),
+                ("paren", token_node_to_json(TType::RPAREN, ")", expr.paren)),
+                ("args", Json::Array(expr.args.iter().map(|&a| expr_to_json(ast, a)).collect())),
+                ("span", span_to_json(expr.span)),
+            ]);
+            add_name_to_json("Call", json)
         },
         Expr::Get(expr) => {
-            let mut get: HashMap<String, JsonValue> = HashMap::new();
-            get.insert(String::from("object"), expr_to_json(expr.object.as_ref().clone()));
-            get.insert(String::from("name"), JsonValue::String(expr.name.lexeme));
-            let json = JsonValue::Object(get);
-            add_name_to_json(String::from("Get"), json)
+            let json = Json::object(vec![
+                ("object", expr_to_json(ast, expr.object)),
+                ("name", ident_to_json(expr.name)),
+                ("span", span_to_json(expr.span)),
+            ]);
+            add_name_to_json("Get", json)
         },
-        // Expr::Variable(var) => {
-        //     let mut variable: HashMap<String, JsonValue> = HashMap::new();
-        //     variable.insert(String::from("name"), JsonValue::String(var.lexeme));
-        //     let json = JsonValue::Object(variable);
-        //     add_name_to_json(String::from("Variable"), json)
-        // },
-        _ => JsonValue::Null,
     }
 }
 
-#[allow(unreachable_patterns)]
-fn stmt_to_json(stmt: Stmt) -> JsonValue {
-    match stmt {
+fn stmt_to_json(ast: &Ast, id: StmtId) -> Json {
+    match &ast[id] {
         Stmt::VarDecl(var) => {
-            let mut var_decl: HashMap<String, JsonValue> = HashMap::new();
-            var_decl.insert(String::from("name"), JsonValue::String(var.name));
-            var_decl.insert(String::from("initializer"), expr_to_json(var.initializer.unwrap()));
-            let json = JsonValue::Object(var_decl);
-            add_name_to_json(String::from("VarDeclStmt"), json)
+            let json = Json::object(vec![
+                ("name", Json::string(var.name.name.as_str())),
+                ("name_span", span_to_json(var.name.span)),
+                ("initializer", optional(var.initializer, |e| expr_to_json(ast, e))),
+                ("span", span_to_json(var.span)),
+            ]);
+            add_name_to_json("VarDeclStmt", json)
         },
-        Stmt::ExprStmt(expr) => {
-            let mut expr_stmt: HashMap<String, JsonValue> = HashMap::new();
-            expr_stmt.insert(String::from("expression"), expr_to_json(expr));
-            let json = JsonValue::Object(expr_stmt);
-            add_name_to_json(String::from("ExprStmt"), json)
+        Stmt::ExprStmt(stmt) => {
+            let json = Json::object(vec![
+                ("expression", expr_to_json(ast, stmt.expr)),
+                ("span", span_to_json(stmt.span)),
+            ]);
+            add_name_to_json("ExprStmt", json)
         },
         Stmt::FnDecl(func) => {
-            let mut fn_decl: HashMap<String, JsonValue> = HashMap::new();
-            fn_decl.insert(String::from("name"), JsonValue::String(func.name));
-            fn_decl.insert(String::from("params"), JsonValue::Array(func.params.into_iter().map(|p| JsonValue::String(p.name)).collect()));
-            fn_decl.insert(String::from("body"), JsonValue::Array(func.body.into_iter().map(|s| stmt_to_json(s)).collect()));
-            if let Some(t) = func.return_type {
-                fn_decl.insert(String::from("rtype"), JsonValue::String(t.lexeme));
-            } else {
-                fn_decl.insert(String::from("rtype"), JsonValue::Null);
-            }
-            let json = JsonValue::Object(fn_decl);
-            add_name_to_json(String::from("FnDecl"), json)
+            let json = Json::object(vec![
+                ("name", Json::string(func.name.name.as_str())),
+                ("name_span", span_to_json(func.name.span)),
+                ("params", Json::Array(func.params.iter().map(param_to_json).collect())),
+                ("body", Json::Array(func.body.iter().map(|&s| stmt_to_json(ast, s)).collect())),
+                ("rtype", optional(func.return_type, type_name_to_json)),
+                ("span", span_to_json(func.span)),
+            ]);
+            add_name_to_json("FnDecl", json)
+        },
+        Stmt::Return(ret) => {
+            let json = Json::object(vec![
+                ("expression", optional(ret.value, |e| expr_to_json(ast, e))),
+                ("span", span_to_json(ret.span)),
+            ]);
+            add_name_to_json("ReturnStmt", json)
         },
-        Stmt::Return(expr) => {
-            let mut return_stmt: HashMap<String, JsonValue> = HashMap::new();
-            return_stmt.insert(String::from("expression"), expr_to_json(expr.unwrap()));
-            let json = JsonValue::Object(return_stmt);
-            add_name_to_json(String::from("ReturnStmt"), json)
+        Stmt::If(stmt) => {
+            let block = |stmts: &Vec<StmtId>| Json::Array(stmts.iter().map(|&s| stmt_to_json(ast, s)).collect());
+            let json = Json::object(vec![
+                ("condition", expr_to_json(ast, stmt.condition)),
+                ("then", block(&stmt.then_branch)),
+                ("else", optional(stmt.else_branch.as_ref(), block)),
+                ("span", span_to_json(stmt.span)),
+            ]);
+            add_name_to_json("IfStmt", json)
         },
-        Stmt::FileImport(path) => {
-            let mut file_import: HashMap<String, JsonValue> = HashMap::new();
-            file_import.insert(String::from("path"), JsonValue::String(path));
-            let json = JsonValue::Object(file_import);
-            add_name_to_json(String::from("FileImportStmt"), json)
+        Stmt::FileImport(import) => {
+            let json = Json::object(vec![
+                ("path", Json::string(import.path.as_str())),
+                ("span", span_to_json(import.span)),
+            ]);
+            add_name_to_json("FileImportStmt", json)
         },
-        Stmt::LangImport(langs) => {
-            let mut lang_import: HashMap<String, JsonValue> = HashMap::new();
-            lang_import.insert(String::from("modules"), JsonValue::Array(langs.into_iter().map(|l| JsonValue::String(l)).collect()));
-            let json = JsonValue::Object(lang_import);
-            add_name_to_json(String::from("LangImportStmt"), json)
+        Stmt::LangImport(import) => {
+            let json = Json::object(vec![
+                ("modules", Json::Array(import.path.iter().map(|&m| Json::string(m.as_str())).collect())),
+                ("span", span_to_json(import.span)),
+            ]);
+            add_name_to_json("LangImportStmt", json)
         },
-        _ => JsonValue::Null,
     }
 }
 
-pub fn inspect(tree: Vec<Stmt>) -> String {
-    let mut buf = vec![];
-    let mut gen = JsonGenerator::new(&mut buf).indent(" ");
+pub fn inspect(ast: &Ast) -> String {
+    let root: Vec<Json> = ast.root.iter().map(|&stmt| stmt_to_json(ast, stmt)).collect();
 
-    let mut root: Vec<JsonValue> = vec![];
+    Json::Array(root).pretty()
+}
 
-    for stmt in tree {
-        root.push(stmt_to_json(stmt));
-    }
+fn token_to_json(token: Token, source: &str) -> Json {
+    let (line, column) = token.lineinfo.start_position(source);
+    let (end_line, end_column) = token.lineinfo.end_position(source);
 
-    let json_root = JsonValue::Array(root);
-    gen.generate(&json_root).unwrap();
+    Json::object(vec![
+        ("type", Json::string(token.ttype.name())),
+        ("lexeme", Json::String(token.lexeme.into_owned())),
+        ("line", Json::Number(line as f64)),
+        ("column", Json::Number(column as f64)),
+        ("end_line", Json::Number(end_line as f64)),
+        ("end_column", Json::Number(end_column as f64)),
+        ("start", Json::Number(token.lineinfo.start as f64)),
+        ("end", Json::Number(token.lineinfo.end as f64)),
+    ])
+}
 
-    String::from_utf8(buf).unwrap()
+/// Serializes the lexer's output as a JSON array, one object per token.
+/// `source` is the text the tokens were scanned from, used for columns.
+pub fn inspect_tokens(tokens: Vec<Token>, source: &str) -> String {
+    let root = Json::Array(tokens.into_iter().map(|t| token_to_json(t, source)).collect());
+
+    root.pretty()
+}
+
+/// Lays the tokens out as a table with a column each for the span
+/// (`line:column-line:column`), the token type and the lexeme, under a
+/// header row. Newlines and tabs in lexemes are escaped.
+pub fn inspect_token_table(tokens: &[Token], source: &str) -> String {
+    let rows: Vec<(String, &str, String)> = tokens
+        .iter()
+        .map(|token| {
+            let (line, column) = token.lineinfo.start_position(source);
+            let (end_line, end_column) = token.lineinfo.end_position(source);
+            let span = format!("{}:{}-{}:{}", line, column, end_line, end_column);
+            (span, token.ttype.name(), token.lexeme.replace('\n', "\\n").replace('\t', "\\t"))
+        })
+        .collect();
+
+    let span_width = rows.iter().map(|row| row.0.len()).chain([4]).max().unwrap_or(0);
+    let type_width = rows.iter().map(|row| row.1.len()).chain([4]).max().unwrap_or(0);
+
+    let mut out = format!("{:span_width$}  {:type_width$}  LEXEME\n", "SPAN", "TYPE");
+    for (span, ttype, lexeme) in rows {
+        out.push_str(&format!("{:span_width$}  {:type_width$}  {}\n", span, ttype, lexeme));
+    }
+    out
 }
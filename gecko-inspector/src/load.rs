@@ -0,0 +1,247 @@
+use gecko_error::{Error, LineInfo};
+use gecko_lexer::{symbol::Symbol, ttype::TType};
+use gecko_parser::{
+    ast::{Ast, ExprId, StmtId},
+    nodes::{
+        expr::{BinaryExpr, BinaryOp, CallExpr, Expr, GetExpr, GroupingExpr, LiteralExpr, Type, UnaryExpr, UnaryOp},
+        stmt::{ExprStmt, FileImport, Fn, If, LangImport, Return, Stmt, Var},
+        Ident, Param,
+    },
+};
+
+use crate::json::Json;
+
+type LoadResult<T> = Result<T, String>;
+
+/// Reads an AST back from the JSON produced by [`crate::inspect`].
+pub fn load(input: &str) -> Result<Ast, Error> {
+    let load = || -> LoadResult<Ast> {
+        let root = Json::parse(input)?;
+        let mut loader = Loader { ast: Ast::new() };
+        for stmt in array(&root, "program")? {
+            let stmt = loader.stmt(stmt)?;
+            loader.ast.root.push(stmt);
+        }
+        Ok(loader.ast)
+    };
+
+    load().map_err(|message| Error::new_without_line_info(format!("Invalid AST JSON: {}", message)))
+}
+
+/// Splits a `{"Name": {fields}}` node into its name and fields.
+fn node(json: &Json) -> LoadResult<(&str, &Json)> {
+    match json {
+        Json::Object(fields) if fields.len() == 1 => Ok((fields[0].0.as_str(), &fields[0].1)),
+        _ => Err(String::from("expected an object with a single node name")),
+    }
+}
+
+fn field<'a>(json: &'a Json, key: &str) -> LoadResult<&'a Json> {
+    json.get(key).ok_or_else(|| format!("missing field '{}'", key))
+}
+
+fn string(json: &Json, what: &str) -> LoadResult<String> {
+    match json {
+        Json::String(s) => Ok(s.clone()),
+        _ => Err(format!("expected a string for '{}'", what)),
+    }
+}
+
+fn number(json: &Json, what: &str) -> LoadResult<f64> {
+    match json {
+        Json::Number(n) => Ok(*n),
+        _ => Err(format!("expected a number for '{}'", what)),
+    }
+}
+
+fn array<'a>(json: &'a Json, what: &str) -> LoadResult<&'a Vec<Json>> {
+    match json {
+        Json::Array(items) => Ok(items),
+        _ => Err(format!("expected an array for '{}'", what)),
+    }
+}
+
+fn string_field(json: &Json, key: &str) -> LoadResult<String> {
+    string(field(json, key)?, key)
+}
+
+fn optional<T>(json: &Json, from_json: impl FnOnce(&Json) -> LoadResult<T>) -> LoadResult<Option<T>> {
+    match json {
+        Json::Null => Ok(None),
+        json => from_json(json).map(Some),
+    }
+}
+
+fn json_to_span(json: &Json) -> LoadResult<LineInfo> {
+    let position = |key| number(field(json, key)?, key).map(|n| n as usize);
+    Ok(LineInfo::new(position("line")?, position("start")?, position("end")?))
+}
+
+fn span_field(json: &Json) -> LoadResult<LineInfo> {
+    json_to_span(field(json, "span")?)
+}
+
+/// Reads a `{"type", "lexeme", "span"}` token object.
+fn json_to_token(json: &Json) -> LoadResult<(TType, String, LineInfo)> {
+    let name = string_field(json, "type")?;
+    let lexeme = string_field(json, "lexeme")?;
+    let ttype = TType::from_name(&name, &lexeme)
+        .ok_or_else(|| format!("unknown token type '{}' with lexeme '{}'", name, lexeme))?;
+
+    Ok((ttype, lexeme, span_field(json)?))
+}
+
+struct Loader {
+    ast: Ast,
+}
+
+impl Loader {
+    fn intern(&mut self, json: &Json, what: &str) -> LoadResult<Symbol> {
+        let string = string(json, what)?;
+        Symbol::try_intern(&string).map_err(|err| format!("cannot intern '{}': {}", what, err))
+    }
+
+    /// A declared name, stored as `"name"` plus `"name_span"`.
+    fn name(&mut self, json: &Json) -> LoadResult<Ident> {
+        let name = self.intern(field(json, "name")?, "name")?;
+        Ok(Ident::new(name, json_to_span(field(json, "name_span")?)?))
+    }
+
+    fn ident(&mut self, json: &Json) -> LoadResult<Ident> {
+        let (ttype, lexeme, span) = json_to_token(json)?;
+        match ttype {
+            TType::Identifier(name) => Ok(Ident::new(name, span)),
+            _ => Err(format!("expected an identifier, found '{}'", lexeme)),
+        }
+    }
+
+    fn type_name(&mut self, json: &Json) -> LoadResult<Ident> {
+        let (ttype, lexeme, span) = json_to_token(json)?;
+        match ttype {
+            TType::NUMBER => Ok(Ident::new(Symbol::intern("number"), span)),
+            TType::STRING => Ok(Ident::new(Symbol::intern("string"), span)),
+            TType::Identifier(name) => Ok(Ident::new(name, span)),
+            _ => Err(format!("expected a type name, found '{}'", lexeme)),
+        }
+    }
+
+    fn literal(&mut self, json: &Json) -> LoadResult<Type> {
+        let value = field(json, "value")?;
+
+        match string_field(json, "type")?.as_str() {
+            "Int" => match value {
+                Json::String(s) => s.parse().map(Type::Int).map_err(|_| format!("expected an integer for 'Int', found '{}'", s)),
+                _ => Err(String::from("expected an integer string for 'Int'")),
+            },
+            "Float" => Ok(Type::Float(number(value, "Float")?)),
+            "String" => Ok(Type::String(self.intern(value, "String")?)),
+            "Bool" => match value {
+                Json::Bool(b) => Ok(Type::Bool(*b)),
+                _ => Err(String::from("expected a boolean for 'Bool'")),
+            },
+            "Iden" => Ok(Type::Iden(self.intern(value, "Iden")?)),
+            "Void" => Ok(Type::Void),
+            "Unknown" => Ok(Type::Unknown),
+            other => Err(format!("unknown literal type '{}'", other)),
+        }
+    }
+
+    fn param(&mut self, json: &Json) -> LoadResult<Param> {
+        let name = self.name(json)?;
+        let type_ = self.type_name(field(json, "type")?)?;
+        Ok(Param::new(name, type_, span_field(json)?))
+    }
+
+    fn exprs(&mut self, json: &Json, what: &str) -> LoadResult<Vec<ExprId>> {
+        array(json, what)?.iter().map(|expr| self.expr(expr)).collect()
+    }
+
+    fn stmts(&mut self, json: &Json, what: &str) -> LoadResult<Vec<StmtId>> {
+        array(json, what)?.iter().map(|stmt| self.stmt(stmt)).collect()
+    }
+
+    fn expr(&mut self, json: &Json) -> LoadResult<ExprId> {
+        let (name, fields) = node(json)?;
+
+        let expr = match name {
+            "Literal" => Expr::Literal(LiteralExpr::new(self.literal(field(fields, "value")?)?, span_field(fields)?)),
+            "Grouping" => Expr::Grouping(GroupingExpr::new(self.expr(field(fields, "expression")?)?, span_field(fields)?)),
+            "Binary" => {
+                let left = self.expr(field(fields, "left")?)?;
+                let (ttype, lexeme, operator_span) = json_to_token(field(fields, "operator")?)?;
+                let operator = BinaryOp::from_ttype(&ttype)
+                    .ok_or_else(|| format!("unknown binary operator '{}'", lexeme))?;
+                let right = self.expr(field(fields, "right")?)?;
+                Expr::Binary(BinaryExpr::new(left, operator, operator_span, right, span_field(fields)?))
+            }
+            "Unary" => {
+                let (ttype, lexeme, operator_span) = json_to_token(field(fields, "operator")?)?;
+                let operator = UnaryOp::from_ttype(&ttype)
+                    .ok_or_else(|| format!("unknown unary operator '{}'", lexeme))?;
+                let right = self.expr(field(fields, "right")?)?;
+                Expr::Unary(UnaryExpr::new(operator, operator_span, right, span_field(fields)?))
+            }
+            "Call" => {
+                let callee = self.expr(field(fields, "callee")?)?;
+                let (_, _, paren) = json_to_token(field(fields, "paren")?)?;
+                let args = self.exprs(field(fields, "args")?, "args")?;
+                Expr::Call(CallExpr::new(callee, paren, args, span_field(fields)?))
+            }
+            "Get" => {
+                let object = self.expr(field(fields, "object")?)?;
+                let name = self.ident(field(fields, "name")?)?;
+                Expr::Get(GetExpr::new(object, name, span_field(fields)?))
+            }
+            other => return Err(format!("unknown expression '{}'", other)),
+        };
+
+        Ok(self.ast.add_expr(expr))
+    }
+
+    fn stmt(&mut self, json: &Json) -> LoadResult<StmtId> {
+        let (name, fields) = node(json)?;
+
+        let stmt = match name {
+            "ExprStmt" => Stmt::ExprStmt(ExprStmt::new(self.expr(field(fields, "expression")?)?, span_field(fields)?)),
+            "VarDeclStmt" => {
+                let name = self.name(fields)?;
+                let initializer = optional(field(fields, "initializer")?, |json| self.expr(json))?;
+                Stmt::VarDecl(Var::new(name, initializer, span_field(fields)?))
+            }
+            "FnDecl" => {
+                let name = self.name(fields)?;
+                let params = array(field(fields, "params")?, "params")?
+                    .iter()
+                    .map(|param| self.param(param))
+                    .collect::<LoadResult<_>>()?;
+                let body = self.stmts(field(fields, "body")?, "body")?;
+                let return_type = optional(field(fields, "rtype")?, |json| self.type_name(json))?;
+                Stmt::FnDecl(Fn::new(name, params, body, return_type, span_field(fields)?))
+            }
+            "ReturnStmt" => {
+                let value = optional(field(fields, "expression")?, |json| self.expr(json))?;
+                Stmt::Return(Return::new(value, span_field(fields)?))
+            }
+            "IfStmt" => {
+                let condition = self.expr(field(fields, "condition")?)?;
+                let then_branch = self.stmts(field(fields, "then")?, "then")?;
+                let else_branch = optional(field(fields, "else")?, |json| self.stmts(json, "else"))?;
+                Stmt::If(If::new(condition, then_branch, else_branch, span_field(fields)?))
+            }
+            "FileImportStmt" => {
+                let path = self.intern(field(fields, "path")?, "path")?;
+                Stmt::FileImport(FileImport::new(path, span_field(fields)?))
+            }
+            "LangImportStmt" => {
+                let path = array(field(fields, "modules")?, "modules")?
+                    .iter()
+                    .map(|module| self.intern(module, "modules"))
+                    .collect::<LoadResult<_>>()?;
+                Stmt::LangImport(LangImport::new(path, span_field(fields)?))
+            }
+            other => return Err(format!("unknown statement '{}'", other)),
+        };
+
+        Ok(self.ast.add_stmt(stmt))
+    }
+}
@@ -0,0 +1,100 @@
+use gecko_parser::{
+    ast::{Ast, ExprId, StmtId},
+    nodes::{
+        expr::{Expr, Type},
+        stmt::Stmt,
+    },
+};
+
+fn quote(s: &str) -> String {
+    format!("{:?}", s)
+}
+
+fn literal_to_sexpr(value: Type) -> String {
+    match value {
+        Type::Int(i) => i.to_string(),
+        Type::Float(f) => f.to_string(),
+        Type::String(s) => quote(s.as_str()),
+        Type::Bool(b) => b.to_string(),
+        Type::Iden(name) => name.as_str().to_string(),
+        Type::Void => String::from("void"),
+        Type::Unknown => String::from("unknown"),
+    }
+}
+
+fn list(head: &str, items: impl IntoIterator<Item = String>) -> String {
+    let mut out = format!("({}", head);
+    for item in items {
+        out.push(' ');
+        out.push_str(&item);
+    }
+    out.push(')');
+    out
+}
+
+fn expr_to_sexpr(ast: &Ast, id: ExprId) -> String {
+    match &ast[id] {
+        Expr::Literal(lit) => literal_to_sexpr(lit.value),
+        Expr::Grouping(group) => list("group", [expr_to_sexpr(ast, group.expression)]),
+        Expr::Binary(binary) => list(
+            binary.operator.lexeme(),
+            [expr_to_sexpr(ast, binary.left), expr_to_sexpr(ast, binary.right)],
+        ),
+        Expr::Unary(unary) => list(unary.operator.lexeme(), [expr_to_sexpr(ast, unary.right)]),
+        Expr::Call(call) => list(
+            "call",
+            std::iter::once(expr_to_sexpr(ast, call.callee)).chain(call.args.iter().map(|&arg| expr_to_sexpr(ast, arg))),
+        ),
+        Expr::Get(get) => list("get", [expr_to_sexpr(ast, get.object), get.name.name.as_str().to_string()]),
+    }
+}
+
+fn stmt_to_sexpr(ast: &Ast, id: StmtId) -> String {
+    match &ast[id] {
+        Stmt::ExprStmt(stmt) => expr_to_sexpr(ast, stmt.expr),
+        Stmt::VarDecl(var) => list(
+            "let",
+            std::iter::once(var.name.name.as_str().to_string())
+                .chain(var.initializer.map(|init| expr_to_sexpr(ast, init))),
+        ),
+        Stmt::FnDecl(func) => {
+            let params: Vec<String> = func
+                .params
+                .iter()
+                .map(|p| format!("({} {})", p.name.name.as_str(), p.type_.name.as_str()))
+                .collect();
+            let return_type = match func.return_type {
+                Some(t) => t.name.as_str().to_string(),
+                None => String::from("void"),
+            };
+
+            list(
+                "fn",
+                [func.name.name.as_str().to_string(), format!("({})", params.join(" ")), return_type]
+                    .into_iter()
+                    .chain(func.body.iter().map(|&stmt| stmt_to_sexpr(ast, stmt))),
+            )
+        }
+        Stmt::Return(ret) => list("return", ret.value.map(|value| expr_to_sexpr(ast, value))),
+        Stmt::If(stmt) => {
+            let block = |stmts: &Vec<StmtId>| list("do", stmts.iter().map(|&stmt| stmt_to_sexpr(ast, stmt)));
+            list(
+                "if",
+                [expr_to_sexpr(ast, stmt.condition), block(&stmt.then_branch)]
+                    .into_iter()
+                    .chain(stmt.else_branch.as_ref().map(block)),
+            )
+        }
+        Stmt::LangImport(import) => {
+            let path: Vec<&str> = import.path.iter().map(|&m| m.as_str()).collect();
+            list("import", [path.join(".")])
+        }
+        Stmt::FileImport(import) => list("import", [quote(import.path.as_str())]),
+    }
+}
+
+/// Prints the AST as S-expressions, one top-level statement per line, e.g.
+/// `(fn main () number (call (get io println) "Hello"))`.
+pub fn inspect_sexpr(ast: &Ast) -> String {
+    ast.root.iter().map(|&stmt| stmt_to_sexpr(ast, stmt)).collect::<Vec<_>>().join("\n")
+}
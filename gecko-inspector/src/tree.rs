@@ -0,0 +1,154 @@
+use gecko_parser::{
+    ast::{Ast, ExprId, StmtId},
+    nodes::{
+        expr::{Expr, Type},
+        stmt::Stmt,
+    },
+};
+
+/// A display-oriented view of the AST: every node has a label and its
+/// children are reached through named edges (`left`, `callee`, `args[0]`).
+/// Both the tree printer and the DOT exporter are built on it.
+pub struct TreeNode {
+    pub label: String,
+    pub children: Vec<(String, TreeNode)>,
+}
+
+impl TreeNode {
+    fn leaf(label: String) -> TreeNode {
+        TreeNode {
+            label,
+            children: vec![],
+        }
+    }
+
+    fn new(label: String, children: Vec<(String, TreeNode)>) -> TreeNode {
+        TreeNode { label, children }
+    }
+}
+
+fn literal_label(value: Type) -> String {
+    match value {
+        Type::Int(i) => format!("Literal {}", i),
+        Type::Float(f) => format!("Literal {}", f),
+        Type::String(s) => format!("Literal {:?}", s.as_str()),
+        Type::Bool(b) => format!("Literal {}", b),
+        Type::Iden(name) => format!("Iden {}", name.as_str()),
+        Type::Void => String::from("Literal void"),
+        Type::Unknown => String::from("Literal unknown"),
+    }
+}
+
+fn indexed<T: Copy>(field: &str, items: &[T], to_node: impl Fn(T) -> TreeNode) -> Vec<(String, TreeNode)> {
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, &item)| (format!("{}[{}]", field, i), to_node(item)))
+        .collect()
+}
+
+pub fn expr_to_node(ast: &Ast, id: ExprId) -> TreeNode {
+    match &ast[id] {
+        Expr::Literal(lit) => TreeNode::leaf(literal_label(lit.value)),
+        Expr::Grouping(group) => TreeNode::new(
+            String::from("Grouping"),
+            vec![(String::from("expression"), expr_to_node(ast, group.expression))],
+        ),
+        Expr::Binary(binary) => TreeNode::new(
+            format!("Binary {}", binary.operator.lexeme()),
+            vec![
+                (String::from("left"), expr_to_node(ast, binary.left)),
+                (String::from("right"), expr_to_node(ast, binary.right)),
+            ],
+        ),
+        Expr::Unary(unary) => TreeNode::new(
+            format!("Unary {}", unary.operator.lexeme()),
+            vec![(String::from("right"), expr_to_node(ast, unary.right))],
+        ),
+        Expr::Call(call) => {
+            let mut children = vec![(String::from("callee"), expr_to_node(ast, call.callee))];
+            children.extend(indexed("args", &call.args, |arg| expr_to_node(ast, arg)));
+            TreeNode::new(String::from("Call"), children)
+        }
+        Expr::Get(get) => TreeNode::new(
+            format!("Get {}", get.name.name.as_str()),
+            vec![(String::from("object"), expr_to_node(ast, get.object))],
+        ),
+    }
+}
+
+pub fn stmt_to_node(ast: &Ast, id: StmtId) -> TreeNode {
+    match &ast[id] {
+        Stmt::ExprStmt(stmt) => TreeNode::new(
+            String::from("ExprStmt"),
+            vec![(String::from("expression"), expr_to_node(ast, stmt.expr))],
+        ),
+        Stmt::VarDecl(var) => TreeNode::new(
+            format!("Let {}", var.name.name.as_str()),
+            var.initializer
+                .iter()
+                .map(|&init| (String::from("initializer"), expr_to_node(ast, init)))
+                .collect(),
+        ),
+        Stmt::FnDecl(func) => {
+            let name = func.name.name.as_str();
+            let label = match func.return_type {
+                Some(t) => format!("Fn {} -> {}", name, t.name.as_str()),
+                None => format!("Fn {}", name),
+            };
+
+            let mut children: Vec<(String, TreeNode)> = func
+                .params
+                .iter()
+                .enumerate()
+                .map(|(i, p)| {
+                    let label = format!("Param {}: {}", p.name.name.as_str(), p.type_.name.as_str());
+                    (format!("params[{}]", i), TreeNode::leaf(label))
+                })
+                .collect();
+            children.extend(indexed("body", &func.body, |stmt| stmt_to_node(ast, stmt)));
+            TreeNode::new(label, children)
+        }
+        Stmt::Return(ret) => TreeNode::new(
+            String::from("Return"),
+            ret.value.iter().map(|&e| (String::from("value"), expr_to_node(ast, e))).collect(),
+        ),
+        Stmt::If(stmt) => {
+            let mut children = vec![(String::from("condition"), expr_to_node(ast, stmt.condition))];
+            children.extend(indexed("then", &stmt.then_branch, |stmt| stmt_to_node(ast, stmt)));
+            if let Some(else_branch) = &stmt.else_branch {
+                children.extend(indexed("else", else_branch, |stmt| stmt_to_node(ast, stmt)));
+            }
+            TreeNode::new(String::from("If"), children)
+        }
+        Stmt::LangImport(import) => {
+            let path: Vec<&str> = import.path.iter().map(|&m| m.as_str()).collect();
+            TreeNode::leaf(format!("Import {}", path.join(".")))
+        }
+        Stmt::FileImport(import) => TreeNode::leaf(format!("Import {:?}", import.path.as_str())),
+    }
+}
+
+fn render(node: &TreeNode, prefix: &str, out: &mut Vec<String>) {
+    for (i, (edge, child)) in node.children.iter().enumerate() {
+        let last = i + 1 == node.children.len();
+        let (branch, extension) = if last { ("└── ", "    ") } else { ("├── ", "│   ") };
+
+        out.push(format!("{}{}{}: {}", prefix, branch, edge, child.label));
+        render(child, &format!("{}{}", prefix, extension), out);
+    }
+}
+
+/// Prints the AST as an indented tree drawn with box-drawing characters,
+/// one tree per top-level statement.
+pub fn inspect_tree(ast: &Ast) -> String {
+    let mut out = vec![];
+
+    for &stmt in &ast.root {
+        let node = stmt_to_node(ast, stmt);
+        out.push(node.label.clone());
+        render(&node, "", &mut out);
+    }
+
+    out.join("\n")
+}
@@ -0,0 +1,40 @@
+use gecko_parser::ast::Ast;
+use gecko_resolver::Decl;
+use gecko_typeck::TypeTable;
+
+use crate::json::Json;
+
+fn kind(decl: Decl) -> &'static str {
+    match decl {
+        Decl::Param { .. } => "param",
+        Decl::Var(_) => "let",
+        Decl::Fn(_) => "fn",
+        Decl::Module(_) => "module",
+    }
+}
+
+/// Serializes the type of every declared name as a JSON array in source
+/// order, one object per declaration. `source` is the text `ast` was parsed
+/// from, used for columns.
+pub fn inspect_types(ast: &Ast, types: &TypeTable, source: &str) -> String {
+    let mut bindings: Vec<_> = types.bindings().map(|(decl, ty)| (decl, decl.span(ast), ty)).collect();
+    bindings.sort_by_key(|(_, span, _)| span.start);
+
+    let root = bindings
+        .into_iter()
+        .map(|(decl, span, ty)| {
+            let (line, column) = span.start_position(source);
+            Json::object(vec![
+                ("name", Json::string(decl.name(ast).as_str())),
+                ("kind", Json::string(kind(decl))),
+                ("type", Json::String(ty.to_string())),
+                ("line", Json::Number(line as f64)),
+                ("column", Json::Number(column as f64)),
+                ("start", Json::Number(span.start as f64)),
+                ("end", Json::Number(span.end as f64)),
+            ])
+        })
+        .collect();
+
+    Json::Array(root).pretty()
+}
@@ -0,0 +1,347 @@
+use std::fs;
+
+use gecko_error::LineInfo;
+use gecko_inspector::{inspect, inspect_dot, inspect_sexpr, inspect_tree, inspect_types};
+use gecko_lexer::{symbol::Symbol, Lexer};
+use gecko_parser::{Parser, ast::{Ast, ExprId, StmtId}, nodes::{
+    expr::{BinaryExpr, BinaryOp, CallExpr, Expr, GetExpr, GroupingExpr, LiteralExpr, Type, UnaryExpr, UnaryOp},
+    stmt::{ExprStmt, FileImport, Fn, If, LangImport, Return, Stmt, Var},
+    Ident, Param,
+}};
+use tinyjson::JsonValue;
+
+fn span(start: usize, end: usize) -> LineInfo {
+    LineInfo::new(1, start, end)
+}
+
+fn ident(name: &str, start: usize) -> Ident {
+    let symbol = Symbol::intern(name);
+    Ident::new(symbol, span(start, start + name.len()))
+}
+
+fn lit(value: Type) -> LiteralExpr {
+    LiteralExpr::new(value, span(0, 1))
+}
+
+fn literal(ast: &mut Ast, value: Type) -> ExprId {
+    ast.add_expr(Expr::Literal(lit(value)))
+}
+
+/// One of every `Expr` variant, and one of every `Type` inside literals.
+fn every_expr(ast: &mut Ast) -> Vec<ExprId> {
+    let s = Symbol::intern("s");
+    let x = Symbol::intern("x");
+    let f = Symbol::intern("f");
+    let io = Symbol::intern("io");
+
+    let exprs = vec![
+        Expr::Literal(lit(Type::Int(1))),
+        Expr::Literal(lit(Type::Float(2.5))),
+        Expr::Literal(lit(Type::String(s))),
+        Expr::Literal(lit(Type::Bool(true))),
+        Expr::Literal(lit(Type::Iden(x))),
+        Expr::Literal(lit(Type::Void)),
+        Expr::Literal(lit(Type::Unknown)),
+        Expr::Grouping(GroupingExpr::new(literal(ast, Type::Float(1.0)), span(0, 5))),
+        Expr::Binary(BinaryExpr::new(
+            literal(ast, Type::Float(1.0)),
+            BinaryOp::Add,
+            span(2, 3),
+            literal(ast, Type::Float(2.0)),
+            span(0, 5),
+        )),
+        Expr::Unary(UnaryExpr::new(UnaryOp::Not, span(0, 1), literal(ast, Type::Bool(false)), span(0, 6))),
+        Expr::Call(CallExpr::new(
+            literal(ast, Type::Iden(f)),
+            span(3, 4),
+            vec![literal(ast, Type::Float(1.0))],
+            span(0, 4),
+        )),
+        Expr::Get(GetExpr::new(literal(ast, Type::Iden(io)), ident("println", 3), span(0, 10))),
+    ];
+
+    exprs.into_iter().map(|expr| ast.add_expr(expr)).collect()
+}
+
+/// One of every `Stmt` variant, including the optional parts left out.
+fn every_stmt(ast: &mut Ast) -> Vec<StmtId> {
+    let mut stmts: Vec<Stmt> = every_expr(ast)
+        .into_iter()
+        .map(|expr| Stmt::ExprStmt(ExprStmt::new(expr, span(0, 12))))
+        .collect();
+
+    let a = Symbol::intern("a");
+    let one = literal(ast, Type::Float(1.0));
+    let a_ref = literal(ast, Type::Iden(a));
+    let body = vec![
+        ast.add_stmt(Stmt::Return(Return::new(Some(a_ref), span(30, 39)))),
+        ast.add_stmt(Stmt::Return(Return::new(None, span(40, 47)))),
+    ];
+    let params = vec![Param::new(ident("a", 5), ident("number", 8), span(5, 14))];
+    let path = vec![Symbol::intern("std"), Symbol::intern("io")];
+    let condition = literal(ast, Type::Bool(true));
+    let then_branch = vec![ast.add_stmt(Stmt::Return(Return::new(None, span(10, 17))))];
+    let else_condition = literal(ast, Type::Iden(a));
+    let else_branch = vec![ast.add_stmt(Stmt::If(If::new(else_condition, vec![], None, span(25, 32))))];
+
+    stmts.extend([
+        Stmt::VarDecl(Var::new(ident("x", 4), Some(one), span(0, 10))),
+        Stmt::VarDecl(Var::new(ident("y", 4), None, span(0, 6))),
+        Stmt::FnDecl(Fn::new(ident("f", 3), params, body, Some(ident("number", 20)), span(0, 49))),
+        Stmt::FnDecl(Fn::new(ident("g", 3), vec![], vec![], None, span(0, 9))),
+        Stmt::If(If::new(condition, then_branch, Some(else_branch), span(0, 34))),
+        Stmt::LangImport(LangImport::new(path, span(0, 14))),
+        Stmt::FileImport(FileImport::new(Symbol::intern("lib.gk"), span(0, 16))),
+    ]);
+
+    stmts.into_iter().map(|stmt| ast.add_stmt(stmt)).collect()
+}
+
+fn expr_name(expr: &Expr) -> &'static str {
+    match expr {
+        Expr::Literal(_) => "Literal",
+        Expr::Grouping(_) => "Grouping",
+        Expr::Binary(_) => "Binary",
+        Expr::Unary(_) => "Unary",
+        Expr::Call(_) => "Call",
+        Expr::Get(_) => "Get",
+    }
+}
+
+fn stmt_name(stmt: &Stmt) -> &'static str {
+    match stmt {
+        Stmt::ExprStmt(_) => "ExprStmt",
+        Stmt::VarDecl(_) => "VarDeclStmt",
+        Stmt::FnDecl(_) => "FnDecl",
+        Stmt::Return(_) => "ReturnStmt",
+        Stmt::If(_) => "IfStmt",
+        Stmt::LangImport(_) => "LangImportStmt",
+        Stmt::FileImport(_) => "FileImportStmt",
+    }
+}
+
+fn node<'a>(json: &'a JsonValue, name: &str) -> &'a JsonValue {
+    let object: &std::collections::HashMap<String, JsonValue> = json.get().expect("node is an object");
+    assert_eq!(object.len(), 1, "node has a single name key");
+    object.get(name).unwrap_or_else(|| panic!("expected a {} node, found {:?}", name, json))
+}
+
+fn field<'a>(json: &'a JsonValue, key: &str) -> &'a JsonValue {
+    let object: &std::collections::HashMap<String, JsonValue> = json.get().expect("fields are an object");
+    object.get(key).unwrap_or_else(|| panic!("missing field '{}' in {:?}", key, json))
+}
+
+fn check_token(json: &JsonValue, ttype: &str, lexeme: &str, span: &LineInfo) {
+    assert_eq!(field(json, "type"), &JsonValue::String(ttype.to_string()));
+    assert_eq!(field(json, "lexeme"), &JsonValue::String(lexeme.to_string()));
+    check_span(json, span);
+}
+
+fn check_ident(json: &JsonValue, ttype: &str, ident: &Ident) {
+    check_token(json, ttype, ident.name.as_str(), &ident.span);
+}
+
+fn type_name(ident: &Ident) -> &'static str {
+    match ident.name.as_str() {
+        "number" => "NUMBER",
+        "string" => "STRING",
+        _ => "Identifier",
+    }
+}
+
+fn check_span(json: &JsonValue, span: &LineInfo) {
+    check_span_field(json, "span", span);
+}
+
+fn check_span_field(json: &JsonValue, key: &str, span: &LineInfo) {
+    let span_json = field(json, key);
+    assert_eq!(field(span_json, "line"), &JsonValue::Number(span.line as f64));
+    assert_eq!(field(span_json, "start"), &JsonValue::Number(span.start as f64));
+    assert_eq!(field(span_json, "end"), &JsonValue::Number(span.end as f64));
+}
+
+fn check_expr(ast: &Ast, id: ExprId, json: &JsonValue) {
+    let expr = &ast[id];
+    let fields = node(json, expr_name(expr));
+    check_span(fields, expr.span());
+
+    match expr {
+        Expr::Literal(lit) => {
+            let value = field(fields, "value");
+            let (ttype, expected) = match &lit.value {
+                Type::Int(i) => ("Int", JsonValue::String(i.to_string())),
+                Type::Float(f) => ("Float", JsonValue::Number(*f)),
+                Type::String(s) => ("String", JsonValue::String(s.as_str().to_string())),
+                Type::Bool(b) => ("Bool", JsonValue::Boolean(*b)),
+                Type::Iden(i) => ("Iden", JsonValue::String(i.as_str().to_string())),
+                Type::Void => ("Void", JsonValue::Null),
+                Type::Unknown => ("Unknown", JsonValue::Null),
+            };
+            assert_eq!(field(value, "type"), &JsonValue::String(ttype.to_string()));
+            assert_eq!(field(value, "value"), &expected);
+        }
+        Expr::Grouping(group) => check_expr(ast, group.expression, field(fields, "expression")),
+        Expr::Binary(binary) => {
+            check_expr(ast, binary.left, field(fields, "left"));
+            check_token(field(fields, "operator"), binary.operator.ttype().name(), binary.operator.lexeme(), &binary.operator_span);
+            check_expr(ast, binary.right, field(fields, "right"));
+        }
+        Expr::Unary(unary) => {
+            check_token(field(fields, "operator"), unary.operator.ttype().name(), unary.operator.lexeme(), &unary.operator_span);
+            check_expr(ast, unary.right, field(fields, "right"));
+        }
+        Expr::Call(call) => {
+            check_expr(ast, call.callee, field(fields, "callee"));
+            check_token(field(fields, "paren"), "RPAREN", ")", &call.paren);
+            let args: &Vec<JsonValue> = field(fields, "args").get().unwrap();
+            assert_eq!(args.len(), call.args.len());
+            for (&arg, json) in call.args.iter().zip(args) {
+                check_expr(ast, arg, json);
+            }
+        }
+        Expr::Get(get) => {
+            check_expr(ast, get.object, field(fields, "object"));
+            check_ident(field(fields, "name"), "Identifier", &get.name);
+        }
+    }
+}
+
+fn check_stmt(ast: &Ast, id: StmtId, json: &JsonValue) {
+    let stmt = &ast[id];
+    let fields = node(json, stmt_name(stmt));
+    check_span(fields, stmt.span());
+
+    match stmt {
+        Stmt::ExprStmt(stmt) => check_expr(ast, stmt.expr, field(fields, "expression")),
+        Stmt::VarDecl(var) => {
+            assert_eq!(field(fields, "name"), &JsonValue::String(var.name.name.as_str().to_string()));
+            check_span_field(fields, "name_span", &var.name.span);
+            match var.initializer {
+                Some(expr) => check_expr(ast, expr, field(fields, "initializer")),
+                None => assert!(field(fields, "initializer").is_null()),
+            }
+        }
+        Stmt::FnDecl(func) => {
+            assert_eq!(field(fields, "name"), &JsonValue::String(func.name.name.as_str().to_string()));
+            check_span_field(fields, "name_span", &func.name.span);
+
+            let params: &Vec<JsonValue> = field(fields, "params").get().unwrap();
+            assert_eq!(params.len(), func.params.len());
+            for (param, json) in func.params.iter().zip(params) {
+                assert_eq!(field(json, "name"), &JsonValue::String(param.name.name.as_str().to_string()));
+                check_span_field(json, "name_span", &param.name.span);
+                check_ident(field(json, "type"), type_name(&param.type_), &param.type_);
+                check_span(json, &param.span);
+            }
+
+            check_block(ast, &func.body, field(fields, "body"));
+
+            match &func.return_type {
+                Some(t) => check_ident(field(fields, "rtype"), type_name(t), t),
+                None => assert!(field(fields, "rtype").is_null()),
+            }
+        }
+        Stmt::Return(ret) => match ret.value {
+            Some(expr) => check_expr(ast, expr, field(fields, "expression")),
+            None => assert!(field(fields, "expression").is_null()),
+        },
+        Stmt::If(stmt) => {
+            check_expr(ast, stmt.condition, field(fields, "condition"));
+            check_block(ast, &stmt.then_branch, field(fields, "then"));
+            match &stmt.else_branch {
+                Some(branch) => check_block(ast, branch, field(fields, "else")),
+                None => assert!(field(fields, "else").is_null()),
+            }
+        }
+        Stmt::LangImport(import) => {
+            let expected = import.path.iter().map(|&m| JsonValue::String(m.as_str().to_string())).collect();
+            assert_eq!(field(fields, "modules"), &JsonValue::Array(expected));
+        }
+        Stmt::FileImport(import) => {
+            assert_eq!(field(fields, "path"), &JsonValue::String(import.path.as_str().to_string()));
+        }
+    }
+}
+
+fn check_block(ast: &Ast, stmts: &[StmtId], json: &JsonValue) {
+    let json: &Vec<JsonValue> = json.get().unwrap();
+    assert_eq!(json.len(), stmts.len());
+    for (&stmt, json) in stmts.iter().zip(json) {
+        check_stmt(ast, stmt, json);
+    }
+}
+
+#[test]
+fn inspect_serializes_every_node_and_field() {
+    let mut ast = Ast::new();
+    ast.root = every_stmt(&mut ast);
+
+    let output = inspect(&ast);
+    let json: JsonValue = output.parse().expect("inspect produces valid JSON");
+    let root: &Vec<JsonValue> = json.get().expect("root is an array");
+
+    assert_eq!(root.len(), ast.root.len());
+    for (&stmt, json) in ast.root.iter().zip(root) {
+        check_stmt(&ast, stmt, json);
+    }
+}
+
+fn parse_file(path: &str) -> Ast {
+    let source = fs::read_to_string(path).unwrap();
+    let tokens = Lexer::new(source).scan_tokens().unwrap();
+    Parser::new(tokens).parse().unwrap()
+}
+
+#[test]
+fn inspect_output_matches_golden_file() {
+    let expected = fs::read_to_string("tests/golden/hello.json").unwrap();
+
+    let first = inspect(&parse_file("tests/golden/hello.gk"));
+    let second = inspect(&parse_file("tests/golden/hello.gk"));
+
+    assert_eq!(first, second);
+    assert_eq!(first, expected.trim_end());
+}
+
+#[test]
+fn sexpr_tree_and_dot_output_match_golden_files() {
+    let sexpr = fs::read_to_string("tests/golden/hello.sexpr").unwrap();
+    let tree = fs::read_to_string("tests/golden/hello.tree").unwrap();
+    let dot = fs::read_to_string("tests/golden/hello.dot").unwrap();
+
+    assert_eq!(inspect_sexpr(&parse_file("tests/golden/hello.gk")), sexpr.trim_end());
+    assert_eq!(inspect_tree(&parse_file("tests/golden/hello.gk")), tree.trim_end());
+    assert_eq!(inspect_dot(&parse_file("tests/golden/hello.gk")), dot.trim_end());
+}
+
+#[test]
+fn inspect_types_lists_every_declaration_in_source_order() {
+    let source = "import std.io;\nfn twice(n: number) { return n * 2; }\nlet x = twice(1);";
+    let ast = Parser::new(Lexer::new(source.to_string()).scan_tokens().unwrap()).parse().unwrap();
+    let (resolution, _) = gecko_resolver::resolve(&ast);
+    let (types, _) = gecko_typeck::check(&ast, &resolution);
+
+    let json: JsonValue = inspect_types(&ast, &types, source).parse().unwrap();
+    let rows: &Vec<JsonValue> = json.get().unwrap();
+    let summary: Vec<String> = rows
+        .iter()
+        .map(|row| {
+            let text = |key| match field(row, key) {
+                JsonValue::String(s) => s.clone(),
+                JsonValue::Number(n) => n.to_string(),
+                other => panic!("unexpected {:?}", other),
+            };
+            format!("{} {} {} @ {}:{}", text("kind"), text("name"), text("type"), text("line"), text("column"))
+        })
+        .collect();
+
+    assert_eq!(
+        summary,
+        [
+            "module io module @ 1:1",
+            "fn twice fn(number) -> number @ 2:4",
+            "param n number @ 2:10",
+            "let x number @ 3:5",
+        ]
+    );
+}
@@ -0,0 +1,263 @@
+use std::fs;
+
+use gecko_error::LineInfo;
+use gecko_inspector::{inspect, load};
+use gecko_lexer::{symbol::Symbol, Lexer};
+use gecko_parser::{Parser, ast::{Ast, ExprId, StmtId}, nodes::{
+    expr::{BinaryExpr, BinaryOp, CallExpr, Expr, GetExpr, GroupingExpr, LiteralExpr, Type, UnaryExpr, UnaryOp},
+    stmt::{ExprStmt, FileImport, Fn, If, LangImport, Return, Stmt, Var},
+    Ident, Param,
+}};
+
+/// Small deterministic PRNG (xorshift64) so failures are reproducible from
+/// the seed printed in the assertion message.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn chance(&mut self, percent: usize) -> bool {
+        self.below(100) < percent
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+}
+
+fn name(rng: &mut Rng) -> &'static str {
+    let names = ["x", "io", "println", "main", "_tmp", "camelCase", "snake_case2"];
+    names[rng.below(names.len())]
+}
+
+fn text(rng: &mut Rng) -> String {
+    let pieces = ["hello", " ", "\"", "\\", "\n", "\t", "\u{1}", "é", "🦎", "/", "{}", ""];
+    (0..rng.below(5)).map(|_| *rng.pick(&pieces)).collect()
+}
+
+fn span(rng: &mut Rng) -> LineInfo {
+    let start = rng.below(1000);
+    LineInfo::new(rng.below(100) + 1, start, start + rng.below(20))
+}
+
+fn ident(rng: &mut Rng, name: &str) -> Ident {
+    let symbol = Symbol::intern(name);
+    Ident::new(symbol, span(rng))
+}
+
+fn type_name(rng: &mut Rng) -> Ident {
+    let name = match rng.below(3) {
+        0 => "number",
+        1 => "string",
+        _ => name(rng),
+    };
+    ident(rng, name)
+}
+
+fn literal(rng: &mut Rng) -> Type {
+    match rng.below(7) {
+        0 => Type::Int(rng.next() as i64),
+        1 => Type::Float(f64::from(rng.next() as u32) / f64::from(rng.below(1000) as u32 + 1)),
+        2 => Type::String(Symbol::intern(&text(rng))),
+        3 => Type::Bool(rng.chance(50)),
+        4 => Type::Iden(Symbol::intern(name(rng))),
+        5 => Type::Void,
+        _ => Type::Unknown,
+    }
+}
+
+fn expr(rng: &mut Rng, ast: &mut Ast, depth: usize) -> ExprId {
+    if depth == 0 {
+        let value = literal(rng);
+        return ast.add_expr(Expr::Literal(LiteralExpr::new(value, span(rng))));
+    }
+
+    let expr = match rng.below(6) {
+        0 => Expr::Literal(LiteralExpr::new(literal(rng), span(rng))),
+        1 => Expr::Grouping(GroupingExpr::new(expr(rng, ast, depth - 1), span(rng))),
+        2 => {
+            let ops = [
+                BinaryOp::Add,
+                BinaryOp::Sub,
+                BinaryOp::Mul,
+                BinaryOp::Div,
+                BinaryOp::Eq,
+                BinaryOp::NotEq,
+                BinaryOp::Less,
+                BinaryOp::LessEq,
+                BinaryOp::Greater,
+                BinaryOp::GreaterEq,
+            ];
+            let operator = *rng.pick(&ops);
+            let left = expr(rng, ast, depth - 1);
+            let operator_span = span(rng);
+            let right = expr(rng, ast, depth - 1);
+            Expr::Binary(BinaryExpr::new(left, operator, operator_span, right, span(rng)))
+        }
+        3 => {
+            let operator = *rng.pick(&[UnaryOp::Not, UnaryOp::Neg]);
+            let operator_span = span(rng);
+            let right = expr(rng, ast, depth - 1);
+            Expr::Unary(UnaryExpr::new(operator, operator_span, right, span(rng)))
+        }
+        4 => {
+            let callee = expr(rng, ast, depth - 1);
+            let paren = span(rng);
+            let args = (0..rng.below(4)).map(|_| expr(rng, ast, depth - 1)).collect();
+            Expr::Call(CallExpr::new(callee, paren, args, span(rng)))
+        }
+        _ => {
+            let object = expr(rng, ast, depth - 1);
+            let name = name(rng);
+            let name = ident(rng, name);
+            Expr::Get(GetExpr::new(object, name, span(rng)))
+        }
+    };
+    ast.add_expr(expr)
+}
+
+fn stmt(rng: &mut Rng, ast: &mut Ast, depth: usize) -> StmtId {
+    let stmt = match rng.below(if depth == 0 { 5 } else { 7 }) {
+        0 => {
+            let expr = expr(rng, ast, 3);
+            Stmt::ExprStmt(ExprStmt::new(expr, span(rng)))
+        }
+        1 => {
+            let initializer = if rng.chance(70) { Some(expr(rng, ast, 3)) } else { None };
+            let name = name(rng);
+            let name = ident(rng, name);
+            Stmt::VarDecl(Var::new(name, initializer, span(rng)))
+        }
+        2 => {
+            let value = if rng.chance(70) { Some(expr(rng, ast, 3)) } else { None };
+            Stmt::Return(Return::new(value, span(rng)))
+        }
+        3 => {
+            let path = (0..rng.below(3) + 1).map(|_| Symbol::intern(name(rng))).collect();
+            Stmt::LangImport(LangImport::new(path, span(rng)))
+        }
+        4 => {
+            let path = Symbol::intern(&text(rng));
+            Stmt::FileImport(FileImport::new(path, span(rng)))
+        }
+        5 => {
+            let condition = expr(rng, ast, 2);
+            let then_branch = (0..rng.below(3)).map(|_| stmt(rng, ast, depth - 1)).collect();
+            let else_branch = if rng.chance(50) {
+                Some((0..rng.below(3)).map(|_| stmt(rng, ast, depth - 1)).collect())
+            } else {
+                None
+            };
+            Stmt::If(If::new(condition, then_branch, else_branch, span(rng)))
+        }
+        _ => {
+            let params = (0..rng.below(4))
+                .map(|_| {
+                    let name = name(rng);
+                    let name = ident(rng, name);
+                    let type_ = type_name(rng);
+                    Param::new(name, type_, span(rng))
+                })
+                .collect();
+            let body = (0..rng.below(4)).map(|_| stmt(rng, ast, depth - 1)).collect();
+            let return_type = if rng.chance(50) { Some(type_name(rng)) } else { None };
+            let name = name(rng);
+            let name = ident(rng, name);
+            Stmt::FnDecl(Fn::new(name, params, body, return_type, span(rng)))
+        }
+    };
+    ast.add_stmt(stmt)
+}
+
+fn program(rng: &mut Rng) -> Ast {
+    let mut ast = Ast::new();
+    ast.root = (0..rng.below(6)).map(|_| stmt(rng, &mut ast, 2)).collect();
+    ast
+}
+
+#[test]
+fn random_programs_round_trip_through_json() {
+    for seed in 1..=500u64 {
+        let mut rng = Rng(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+        let ast = program(&mut rng);
+
+        let json = inspect(&ast);
+        let loaded = load(&json).unwrap_or_else(|err| panic!("seed {}: {}\n{}", seed, err.message, json));
+
+        assert_eq!(loaded.root.len(), ast.root.len(), "seed {}", seed);
+        assert_eq!(loaded.expr_count(), ast.expr_count(), "seed {}", seed);
+        assert_eq!(loaded.stmt_count(), ast.stmt_count(), "seed {}", seed);
+        assert_eq!(inspect(&loaded), json, "seed {}", seed);
+    }
+}
+
+#[test]
+fn parsed_program_round_trips_through_json() {
+    let source = fs::read_to_string("tests/golden/hello.gk").unwrap();
+    let tokens = Lexer::new(source).scan_tokens().unwrap();
+    let ast = Parser::new(tokens).parse().unwrap();
+
+    let json = inspect(&ast);
+    let loaded = load(&json).unwrap();
+
+    assert_eq!(loaded.root.len(), ast.root.len());
+    assert_eq!(inspect(&loaded), json);
+}
+
+#[test]
+fn integers_beyond_f64_precision_round_trip() {
+    for value in [i64::MAX, i64::MIN, (1 << 53) + 1] {
+        let mut ast = Ast::new();
+        let span = LineInfo::new(1, 0, 1);
+        let expr = ast.add_expr(Expr::Literal(LiteralExpr::new(Type::Int(value), span)));
+        let stmt = ast.add_stmt(Stmt::ExprStmt(ExprStmt::new(expr, span)));
+        ast.root.push(stmt);
+
+        let loaded = load(&inspect(&ast)).unwrap();
+        let Stmt::ExprStmt(stmt) = &loaded[loaded.root[0]] else {
+            panic!("expected an expression statement");
+        };
+        let Expr::Literal(literal) = &loaded[stmt.expr] else {
+            panic!("expected a literal");
+        };
+        assert_eq!(literal.value, Type::Int(value));
+    }
+}
+
+/// A document holding the single statement `<value>;` with `value` as an
+/// `Int` literal's JSON value.
+fn int_literal(value: &str) -> String {
+    let span = "{\"line\": 1, \"start\": 0, \"end\": 1}";
+    format!(
+        "[{{\"ExprStmt\": {{\"expression\": {{\"Literal\": {{\"value\": {{\"type\": \"Int\", \"value\": {}}}, \"span\": {}}}}}, \"span\": {}}}}}]",
+        value, span, span
+    )
+}
+
+#[test]
+fn load_reports_malformed_documents() {
+    let (number, overflow) = (int_literal("1"), int_literal("\"9223372036854775808\""));
+    let cases = [
+        ("{}", "expected an array for 'program'"),
+        ("[{\"Nope\": {}}]", "unknown statement 'Nope'"),
+        ("[{\"VarDeclStmt\": {\"name\": \"x\"}}]", "missing field 'name_span'"),
+        ("[{\"VarDeclStmt\": {\"name\": \"x\", \"name_span\": {\"line\": 1, \"start\": 4, \"end\": 5}}}]", "missing field 'initializer'"),
+        ("[", "unexpected end of input"),
+        (number.as_str(), "expected an integer string for 'Int'"),
+        (overflow.as_str(), "expected an integer for 'Int', found '9223372036854775808'"),
+    ];
+
+    for (input, expected) in cases {
+        let err = load(input).unwrap_err();
+        assert!(err.message.contains(expected), "{:?}: {}", input, err.message);
+    }
+}
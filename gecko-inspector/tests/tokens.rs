@@ -0,0 +1,63 @@
+use gecko_inspector::{inspect_token_table, inspect_tokens};
+use gecko_lexer::Lexer;
+use tinyjson::JsonValue;
+
+const SOURCE: &str = "let greeting = \"hi\tthere\";\nio.println(greeting);";
+
+#[test]
+fn token_json_has_the_type_lexeme_and_span_of_each_token() {
+    let tokens = Lexer::new(SOURCE.to_string()).scan_tokens().unwrap();
+    let json: JsonValue = inspect_tokens(tokens, SOURCE).parse().unwrap();
+    let JsonValue::Array(tokens) = json else {
+        panic!("expected an array, found {:?}", json);
+    };
+
+    let keys = ["type", "lexeme", "line", "column", "end_line", "end_column", "start", "end"];
+    for token in &tokens {
+        let JsonValue::Object(fields) = token else {
+            panic!("expected an object, found {:?}", token);
+        };
+        let mut found: Vec<_> = fields.keys().map(String::as_str).collect();
+        found.sort();
+        let mut expected = keys;
+        expected.sort();
+        assert_eq!(found, expected);
+    }
+
+    let row = |token: &JsonValue| -> Vec<String> {
+        keys.iter()
+            .map(|&key| match &token[key] {
+                JsonValue::String(s) => s.clone(),
+                JsonValue::Number(n) => n.to_string(),
+                other => panic!("unexpected {:?} for '{}'", other, key),
+            })
+            .collect()
+    };
+    // The string spans its quotes and keeps its tab, `io` starts line 2 and
+    // EOF is an empty span at the end.
+    assert_eq!(row(&tokens[3]), ["String", "\"hi\tthere\"", "1", "16", "1", "26", "15", "25"]);
+    assert_eq!(row(&tokens[5]), ["Identifier", "io", "2", "1", "2", "3", "27", "29"]);
+    assert_eq!(row(&tokens[tokens.len() - 1]), ["EOF", "", "2", "22", "2", "22", "48", "48"]);
+}
+
+#[test]
+fn token_table_lines_up_its_columns() {
+    let tokens = Lexer::new(SOURCE.to_string()).scan_tokens().unwrap();
+
+    let expected = "\
+SPAN       TYPE        LEXEME
+1:1-1:4    LET         let
+1:5-1:13   Identifier  greeting
+1:14-1:15  EQ          =
+1:16-1:26  String      \"hi\\tthere\"
+1:26-1:27  SEMICOLON   ;
+2:1-2:3    Identifier  io
+2:3-2:4    DOT         .
+2:4-2:11   Identifier  println
+2:11-2:12  LPAREN      (
+2:12-2:20  Identifier  greeting
+2:20-2:21  RPAREN      )
+2:21-2:22  SEMICOLON   ;
+2:22-2:22  EOF         \n";
+    assert_eq!(inspect_token_table(&tokens, SOURCE), expected);
+}
@@ -0,0 +1,376 @@
+pub mod environment;
+pub mod stdlib;
+pub mod value;
+
+use std::{cell::RefCell, fs, path::PathBuf, rc::Rc};
+
+use environment::Environment;
+use value::{Function, Value};
+
+use gecko_error::{Error, LineInfo};
+use gecko_lexer::Lexer;
+use gecko_parser::{
+    ast::{Ast, ExprId, StmtId},
+    nodes::{
+        expr::{BinaryExpr, BinaryOp, CallExpr, Expr, GetExpr, Type, UnaryExpr, UnaryOp},
+        stmt::Stmt,
+    },
+    Parser,
+};
+
+const MAX_CALL_DEPTH: usize = 512;
+
+pub struct Interpreter {
+    globals: Rc<RefCell<Environment>>,
+    env: Rc<RefCell<Environment>>,
+    base_dir: PathBuf,
+    depth: usize,
+    /// The program whose nodes are currently executing.
+    ast: Rc<Ast>,
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Interpreter::new()
+    }
+}
+
+impl Interpreter {
+    pub fn new() -> Interpreter {
+        let globals = Rc::new(RefCell::new(Environment::new()));
+
+        Interpreter {
+            env: Rc::clone(&globals),
+            globals,
+            base_dir: PathBuf::from("."),
+            depth: 0,
+            ast: Rc::new(Ast::new()),
+        }
+    }
+
+    /// Sets the directory that `import "file.gk";` paths are resolved from.
+    pub fn with_base_dir(mut self, base_dir: PathBuf) -> Interpreter {
+        self.base_dir = base_dir;
+        self
+    }
+
+    /// Executes the top-level statements of `ast` in order. Definitions stay
+    /// in the global scope, so `run` can be called repeatedly, e.g. once per
+    /// REPL input. Afterwards [`Interpreter::evaluate`] resolves expression
+    /// IDs against `ast`.
+    pub fn run(&mut self, ast: Rc<Ast>) -> Result<(), Error> {
+        self.ast = ast;
+
+        for stmt in self.ast.root.clone() {
+            if self.execute(stmt)?.is_some() {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Calls the program's `main` function, if it declared one.
+    pub fn call_main(&mut self) -> Result<Option<Value>, Error> {
+        let main = self.globals.borrow().get("main");
+
+        match main {
+            Some(Value::Function(func)) if func.decl.params.is_empty() => {
+                Ok(Some(self.call_function(&func, vec![], None)?))
+            }
+            Some(Value::Function(func)) => Err(Error::new(
+                func.decl.span,
+                String::from("'main' cannot take parameters"),
+            )),
+            Some(_) => Err(Error::new_without_line_info(String::from("'main' is not a function"))),
+            None => Ok(None),
+        }
+    }
+
+    pub fn get_global(&self, name: &str) -> Option<Value> {
+        self.globals.borrow().get(name)
+    }
+
+    /// Executes one statement, returning the value of a `return` if one ran.
+    fn execute(&mut self, id: StmtId) -> Result<Option<Value>, Error> {
+        let ast = Rc::clone(&self.ast);
+
+        match &ast[id] {
+            Stmt::ExprStmt(stmt) => {
+                self.evaluate(stmt.expr)?;
+            }
+            Stmt::VarDecl(var) => {
+                let value = match var.initializer {
+                    Some(expr) => self.evaluate(expr)?,
+                    None => Value::Void,
+                };
+                self.env.borrow_mut().define(var.name.name.as_str().to_string(), value);
+            }
+            Stmt::FnDecl(decl) => {
+                let func = Function {
+                    decl: decl.clone(),
+                    ast: Rc::clone(&ast),
+                    closure: Rc::clone(&self.env),
+                };
+                self.env.borrow_mut().define(func.name().to_string(), Value::Function(Rc::new(func)));
+            }
+            Stmt::Return(ret) => {
+                let value = match ret.value {
+                    Some(expr) => self.evaluate(expr)?,
+                    None => Value::Void,
+                };
+                return Ok(Some(value));
+            }
+            Stmt::If(stmt) => {
+                let branch = match self.evaluate(stmt.condition)? {
+                    Value::Bool(true) => &stmt.then_branch,
+                    Value::Bool(false) => match &stmt.else_branch {
+                        Some(branch) => branch,
+                        None => return Ok(None),
+                    },
+                    other => {
+                        return Err(Error::new(
+                            *ast[stmt.condition].span(),
+                            format!("Condition must be a bool, not a {}", other.type_name()),
+                        ))
+                    }
+                };
+                return self.execute_block(branch);
+            }
+            Stmt::LangImport(import) => {
+                let path: Vec<String> = import.path.iter().map(|&m| m.as_str().to_string()).collect();
+                let module = match stdlib::module(&path) {
+                    Some(module) => module,
+                    None => {
+                        return Err(Error::new(
+                            import.span,
+                            format!("Unknown module '{}'", path.join(".")),
+                        ))
+                    }
+                };
+                self.env.borrow_mut().define(module.name.clone(), Value::Module(Rc::new(module)));
+            }
+            Stmt::FileImport(import) => self.import_file(import.path.as_str())?,
+        }
+
+        Ok(None)
+    }
+
+    /// Executes `stmts` in a new scope.
+    fn execute_block(&mut self, stmts: &[StmtId]) -> Result<Option<Value>, Error> {
+        let env = Environment::with_parent(Rc::clone(&self.env));
+        let previous_env = std::mem::replace(&mut self.env, Rc::new(RefCell::new(env)));
+
+        let mut result = Ok(None);
+        for &stmt in stmts {
+            result = self.execute(stmt);
+            if !matches!(result, Ok(None)) {
+                break;
+            }
+        }
+
+        self.env = previous_env;
+        result
+    }
+
+    fn import_file(&mut self, path: &str) -> Result<(), Error> {
+        let full_path = self.base_dir.join(path);
+        let source = fs::read_to_string(&full_path).map_err(|e| {
+            Error::new_without_line_info(format!("Cannot import '{}': {}", path, e))
+        })?;
+
+        let in_file = |err: Error| {
+            Error::new_with_notes(
+                LineInfo::new(0, 0, 0),
+                format!("{}:{}: {}", path, err.line_info.line, err.message),
+                err.notes,
+            )
+        };
+
+        let tokens = Lexer::new(source).scan_tokens().map_err(in_file)?;
+        let ast = Parser::new(tokens).parse().map_err(in_file)?;
+
+        let previous_env = std::mem::replace(&mut self.env, Rc::clone(&self.globals));
+        let previous_ast = Rc::clone(&self.ast);
+        let result = self.run(Rc::new(ast));
+        self.env = previous_env;
+        self.ast = previous_ast;
+
+        result.map_err(in_file)
+    }
+
+    /// Evaluates an expression of the program last passed to
+    /// [`Interpreter::run`].
+    pub fn evaluate(&mut self, id: ExprId) -> Result<Value, Error> {
+        let ast = Rc::clone(&self.ast);
+
+        match &ast[id] {
+            Expr::Literal(lit) => match lit.value {
+                Type::Int(i) => Ok(Value::Number(i as f64)),
+                Type::Float(f) => Ok(Value::Number(f)),
+                Type::String(s) => Ok(Value::String(s.as_str().to_string())),
+                Type::Bool(b) => Ok(Value::Bool(b)),
+                Type::Iden(name) => {
+                    let name = name.as_str();
+                    self.env.borrow().get(name).ok_or_else(|| {
+                        Error::new(lit.span, format!("Undefined variable '{}'", name))
+                    })
+                }
+                Type::Void => Ok(Value::Void),
+                Type::Unknown => Err(Error::new(
+                    lit.span,
+                    String::from("Cannot evaluate an expression of unknown type"),
+                )),
+            },
+            Expr::Grouping(group) => self.evaluate(group.expression),
+            Expr::Unary(unary) => self.unary(unary),
+            Expr::Binary(binary) => self.binary(binary),
+            Expr::Call(call) => self.call(call),
+            Expr::Get(get) => self.get(get),
+        }
+    }
+
+    fn unary(&mut self, unary: &UnaryExpr) -> Result<Value, Error> {
+        let right = self.evaluate(unary.right)?;
+
+        match (unary.operator, right) {
+            (UnaryOp::Neg, Value::Number(n)) => Ok(Value::Number(-n)),
+            (UnaryOp::Not, Value::Bool(b)) => Ok(Value::Bool(!b)),
+            (op, right) => Err(Error::new(
+                unary.operator_span,
+                format!("Cannot apply '{}' to a {}", op.lexeme(), right.type_name()),
+            )),
+        }
+    }
+
+    fn binary(&mut self, binary: &BinaryExpr) -> Result<Value, Error> {
+        let left = self.evaluate(binary.left)?;
+        let right = self.evaluate(binary.right)?;
+        let span = binary.operator_span;
+
+        match (binary.operator, left, right) {
+            (BinaryOp::Eq, left, right) => Ok(Value::Bool(left.equals(&right))),
+            (BinaryOp::NotEq, left, right) => Ok(Value::Bool(!left.equals(&right))),
+            (BinaryOp::Add, Value::Number(a), Value::Number(b)) => Ok(Value::Number(a + b)),
+            (BinaryOp::Add, Value::String(a), Value::String(b)) => Ok(Value::String(a + &b)),
+            (BinaryOp::Sub, Value::Number(a), Value::Number(b)) => Ok(Value::Number(a - b)),
+            (BinaryOp::Mul, Value::Number(a), Value::Number(b)) => Ok(Value::Number(a * b)),
+            (BinaryOp::Div, Value::Number(_), Value::Number(0.0)) => {
+                Err(Error::new(span, String::from("Division by zero")))
+            }
+            (BinaryOp::Div, Value::Number(a), Value::Number(b)) => Ok(Value::Number(a / b)),
+            (BinaryOp::Less, Value::Number(a), Value::Number(b)) => Ok(Value::Bool(a < b)),
+            (BinaryOp::LessEq, Value::Number(a), Value::Number(b)) => Ok(Value::Bool(a <= b)),
+            (BinaryOp::Greater, Value::Number(a), Value::Number(b)) => Ok(Value::Bool(a > b)),
+            (BinaryOp::GreaterEq, Value::Number(a), Value::Number(b)) => Ok(Value::Bool(a >= b)),
+            (op, left, right) => Err(Error::new(
+                span,
+                format!(
+                    "Cannot apply '{}' to a {} and a {}",
+                    op.lexeme(),
+                    left.type_name(),
+                    right.type_name()
+                ),
+            )),
+        }
+    }
+
+    fn call(&mut self, call: &CallExpr) -> Result<Value, Error> {
+        let callee = self.evaluate(call.callee)?;
+
+        let mut args = Vec::with_capacity(call.args.len());
+        for &arg in &call.args {
+            args.push(self.evaluate(arg)?);
+        }
+
+        match callee {
+            Value::Function(func) => self.call_function(&func, args, Some(call.paren)),
+            Value::Native(native) => {
+                check_arity(native.arity, args.len(), call.paren)?;
+                (native.func)(&args).map_err(|message| Error::new(call.paren, message))
+            }
+            other => Err(Error::new(
+                call.paren,
+                format!("Cannot call a {}", other.type_name()),
+            )),
+        }
+    }
+
+    fn call_function(
+        &mut self,
+        func: &Function,
+        args: Vec<Value>,
+        paren: Option<LineInfo>,
+    ) -> Result<Value, Error> {
+        if let Some(paren) = paren {
+            check_arity(func.decl.params.len(), args.len(), paren)?;
+        }
+
+        if self.depth >= MAX_CALL_DEPTH {
+            let message = format!("Stack overflow in '{}'", func.name());
+            return Err(match paren {
+                Some(paren) => Error::new(paren, message),
+                None => Error::new_without_line_info(message),
+            });
+        }
+
+        let mut env = Environment::with_parent(Rc::clone(&func.closure));
+        for (param, arg) in func.decl.params.iter().zip(args) {
+            env.define(param.name.name.as_str().to_string(), arg);
+        }
+
+        let previous_env = std::mem::replace(&mut self.env, Rc::new(RefCell::new(env)));
+        let previous_ast = std::mem::replace(&mut self.ast, Rc::clone(&func.ast));
+        self.depth += 1;
+
+        let mut result = Ok(Value::Void);
+        for &stmt in &func.decl.body {
+            match self.execute(stmt) {
+                Ok(None) => {}
+                Ok(Some(value)) => {
+                    result = Ok(value);
+                    break;
+                }
+                Err(err) => {
+                    result = Err(err);
+                    break;
+                }
+            }
+        }
+
+        self.depth -= 1;
+        self.env = previous_env;
+        self.ast = previous_ast;
+
+        result
+    }
+
+    fn get(&mut self, get: &GetExpr) -> Result<Value, Error> {
+        let object = self.evaluate(get.object)?;
+        let name = get.name.name.as_str();
+
+        match object {
+            Value::Module(module) => module.members.get(name).cloned().ok_or_else(|| {
+                Error::new(
+                    get.name.span,
+                    format!("Module '{}' has no member '{}'", module.name, name),
+                )
+            }),
+            other => Err(Error::new(
+                get.name.span,
+                format!("Cannot access '{}' on a {}", name, other.type_name()),
+            )),
+        }
+    }
+}
+
+fn check_arity(expected: usize, got: usize, paren: LineInfo) -> Result<(), Error> {
+    if expected == got {
+        Ok(())
+    } else {
+        Err(Error::new(
+            paren,
+            format!("Expected {} arguments but got {}", expected, got),
+        ))
+    }
+}
@@ -0,0 +1,42 @@
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+};
+
+use crate::value::{Module, NativeFn, Value};
+
+/// Looks up a built-in module by its import path, e.g. `["std", "io"]`.
+pub fn module(path: &[String]) -> Option<Module> {
+    match path.join(".").as_str() {
+        "std.io" => Some(io()),
+        _ => None,
+    }
+}
+
+fn io() -> Module {
+    let mut members = HashMap::new();
+    members.insert(
+        String::from("println"),
+        Value::Native(NativeFn { name: "println", arity: 1, func: println }),
+    );
+    members.insert(
+        String::from("print"),
+        Value::Native(NativeFn { name: "print", arity: 1, func: print }),
+    );
+
+    Module {
+        name: String::from("io"),
+        members,
+    }
+}
+
+fn println(args: &[Value]) -> Result<Value, String> {
+    println!("{}", args[0]);
+    Ok(Value::Void)
+}
+
+fn print(args: &[Value]) -> Result<Value, String> {
+    print!("{}", args[0]);
+    io::stdout().flush().map_err(|e| e.to_string())?;
+    Ok(Value::Void)
+}
@@ -0,0 +1,90 @@
+use std::{cell::RefCell, collections::HashMap, fmt, rc::Rc};
+
+use gecko_parser::{ast::Ast, nodes::stmt::Fn};
+
+use crate::environment::Environment;
+
+#[derive(Clone)]
+pub enum Value {
+    Number(f64),
+    String(String),
+    Bool(bool),
+    Function(Rc<Function>),
+    Native(NativeFn),
+    Module(Rc<Module>),
+    Void,
+}
+
+pub struct Function {
+    pub decl: Fn,
+    /// The program `decl` was parsed from; its body IDs index into it.
+    pub ast: Rc<Ast>,
+    pub closure: Rc<RefCell<Environment>>,
+}
+
+impl Function {
+    pub fn name(&self) -> &str {
+        self.decl.name.name.as_str()
+    }
+}
+
+#[derive(Clone)]
+pub struct NativeFn {
+    pub name: &'static str,
+    pub arity: usize,
+    pub func: fn(&[Value]) -> Result<Value, String>,
+}
+
+pub struct Module {
+    pub name: String,
+    pub members: HashMap<String, Value>,
+}
+
+impl Value {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Bool(_) => "bool",
+            Value::Function(_) | Value::Native(_) => "function",
+            Value::Module(_) => "module",
+            Value::Void => "void",
+        }
+    }
+
+    pub fn equals(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Number(a), Value::Number(b)) => a == b,
+            (Value::String(a), Value::String(b)) => a == b,
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::Function(a), Value::Function(b)) => Rc::ptr_eq(a, b),
+            (Value::Native(a), Value::Native(b)) => a.name == b.name,
+            (Value::Module(a), Value::Module(b)) => Rc::ptr_eq(a, b),
+            (Value::Void, Value::Void) => true,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Number(n) => write!(f, "{}", n),
+            Value::String(s) => write!(f, "{}", s),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Function(func) => write!(f, "<fn {}>", func.name()),
+            Value::Native(native) => write!(f, "<native fn {}>", native.name),
+            Value::Module(module) => write!(f, "<module {}>", module.name),
+            Value::Void => write!(f, "void"),
+        }
+    }
+}
+
+impl fmt::Debug for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::String(s) => write!(f, "{:?}", s),
+            _ => write!(f, "{}", self),
+        }
+    }
+}
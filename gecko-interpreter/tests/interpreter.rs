@@ -0,0 +1,75 @@
+use std::{env, fs, path::PathBuf, rc::Rc, thread};
+
+use gecko_interpreter::Interpreter;
+use gecko_lexer::Lexer;
+use gecko_parser::Parser;
+
+/// What `main` returned, or the error with the line it points at.
+fn run_in(source: &str, base_dir: PathBuf) -> String {
+    let tokens = Lexer::new(source.to_string()).scan_tokens().unwrap();
+    let ast = Parser::new(tokens).parse().unwrap();
+
+    let mut interpreter = Interpreter::new().with_base_dir(base_dir);
+    match interpreter.run(Rc::new(ast)).and_then(|_| interpreter.call_main()) {
+        Ok(value) => format!("{:?}", value),
+        Err(err) => format!("{}: {}", err.line_info.line, err.message),
+    }
+}
+
+fn run(source: &str) -> String {
+    run_in(source, PathBuf::from("."))
+}
+
+#[test]
+fn division_by_zero_is_an_error() {
+    assert_eq!(run("let a = 1;\nlet b = a / 0;"), "2: Division by zero");
+    assert_eq!(run("fn main() -> number {\n    let zero = 2 - 2;\n    return 1 / zero;\n}"), "3: Division by zero");
+    assert_eq!(run("fn main() -> number { return 0 / 4; }"), "Some(0)");
+}
+
+#[test]
+fn conditions_must_be_bools() {
+    assert_eq!(run("if 1 {}"), "1: Condition must be a bool, not a number");
+    assert_eq!(run("let s = \"yes\";\nif s {} else {}"), "2: Condition must be a bool, not a string");
+    assert_eq!(run("fn f() {}\nif f() {}"), "2: Condition must be a bool, not a void");
+    assert_eq!(run("fn main() -> number { if 1 < 2 { return 1; } return 2; }"), "Some(1)");
+}
+
+#[test]
+fn deep_recursion_stops_at_the_call_depth_limit() {
+    // Each call recurses on the native stack, so give it room to reach the
+    // limit.
+    let result = thread::Builder::new()
+        .stack_size(512 * 1024 * 1024)
+        .spawn(|| {
+            let limit = "fn f(n: number) -> number { if n == 0 { return 0; } return f(n - 1); }\n";
+            (
+                run("fn f(n: number) -> number {\n    return f(n + 1);\n}\nf(1);"),
+                run(&format!("{}fn main() -> number {{ return f(510); }}", limit)),
+                run(&format!("{}fn main() -> number {{ return f(511); }}", limit)),
+            )
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+
+    assert_eq!(result.0, "2: Stack overflow in 'f'");
+    assert_eq!(result.1, "Some(0)");
+    assert_eq!(result.2, "1: Stack overflow in 'f'");
+}
+
+#[test]
+fn errors_in_imported_files_name_the_file_and_line() {
+    let dir = env::temp_dir().join(format!("gecko-interpreter-import-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("runtime.gk"), "let a = 1;\nlet b = -\"x\";").unwrap();
+    fs::write(dir.join("syntax.gk"), "let a = 1;\n\nlet b = ;").unwrap();
+    fs::write(dir.join("lexer.gk"), "let a = 1;\n@").unwrap();
+
+    assert_eq!(run_in("import \"runtime.gk\";", dir.clone()), "0: runtime.gk:2: Cannot apply '-' to a string");
+    assert_eq!(run_in("import \"syntax.gk\";", dir.clone()), "0: syntax.gk:3: Expect expression.");
+    assert_eq!(run_in("import \"lexer.gk\";", dir.clone()), "0: lexer.gk:2: Unexpected character: @");
+    assert!(run_in("import \"missing.gk\";", dir.clone()).starts_with("0: Cannot import 'missing.gk': "));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
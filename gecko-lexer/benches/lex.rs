@@ -0,0 +1,205 @@
+//! Lexes a large generated program and compares the throughput with
+//! [`baseline`], which keeps identifiers as strings the way the lexer did
+//! before they were interned.
+//!
+//! Run with `cargo bench -p gecko-lexer`.
+
+use std::time::{Duration, Instant};
+
+use gecko_lexer::Lexer;
+
+const FUNCTIONS: usize = 5_000;
+const RUNS: u32 = 20;
+
+/// A program with a mix of keywords, repeated and distinct identifiers,
+/// numbers, strings and comments.
+fn generate() -> String {
+    let mut source = String::from("import std.io;\n\n");
+    for i in 0..FUNCTIONS {
+        source.push_str(&format!(
+            "// function number {i}\n\
+             fn compute_{i}(value: number, label: string) -> number {{\n    \
+                 let total = value * {i} + 1.5;\n    \
+                 let flag = true;\n    \
+                 if flag != false {{\n        \
+                     io.println(label, \"step {i}\", total);\n    \
+                 }}\n    \
+                 return total / (value - {i});\n\
+             }}\n\n"
+        ));
+    }
+    source
+}
+
+/// Lexes a copy of `source` with `lex`, returning how long it took and the
+/// number of tokens found.
+fn time(source: &str, lex: impl Fn(String) -> usize) -> (Duration, usize) {
+    let source = source.to_string();
+    let start = Instant::now();
+    let tokens = lex(source);
+    (start.elapsed(), tokens)
+}
+
+fn main() {
+    let source = generate();
+    let mb = source.len() as f64 / 1_000_000.0;
+
+    // Alternating the two evens out anything else slowing the machine down.
+    let (mut interned, mut strings) = (Duration::MAX, Duration::MAX);
+    let mut tokens = 0;
+    for _ in 0..RUNS {
+        let (elapsed, count) = time(&source, |source| Lexer::new(source).scan_tokens().unwrap().len());
+        interned = interned.min(elapsed);
+        tokens = count;
+
+        let (elapsed, count) = time(&source, |source| baseline::Lexer::new(source).scan_tokens().len());
+        strings = strings.min(elapsed);
+        assert_eq!(count, tokens, "the baseline must find the same tokens");
+    }
+
+    println!("lexing {:.2} MB ({} tokens), best of {}:", mb, tokens, RUNS);
+    for (name, best) in [("interned identifiers", interned), ("string identifiers", strings)] {
+        println!("  {:<22} {:>10.2?}  {:>6.1} MB/s", name, best, mb / best.as_secs_f64());
+    }
+    println!("  {:.2}x the baseline's throughput", strings.as_secs_f64() / interned.as_secs_f64());
+}
+
+/// The lexer before identifiers were interned: identifiers hold a `String`,
+/// keywords are looked up in a map built by every lexer, and every token
+/// allocates its lexeme. It indexes the source by byte offset like the
+/// current one, so only the identifier handling differs.
+mod baseline {
+    use std::collections::HashMap;
+
+    #[allow(dead_code)]
+    pub enum Kind {
+        Identifier(String),
+        Keyword(&'static str),
+        Number(f64),
+        String(String),
+        Punctuation,
+        Eof,
+    }
+
+    #[allow(dead_code)]
+    pub struct Token {
+        pub kind: Kind,
+        pub lexeme: String,
+        pub line: usize,
+        pub start: usize,
+        pub end: usize,
+    }
+
+    pub struct Lexer {
+        input: String,
+        start: usize,
+        current: usize,
+        line: usize,
+        tokens: Vec<Token>,
+        keywords: HashMap<String, &'static str>,
+    }
+
+    impl Lexer {
+        pub fn new(input: String) -> Lexer {
+            let keywords = [
+                "true", "false", "if", "else", "while", "for", "in", "fn", "let", "return", "import", "number",
+                "string",
+            ];
+            Lexer {
+                input,
+                start: 0,
+                current: 0,
+                line: 1,
+                tokens: vec![],
+                keywords: keywords.into_iter().map(|keyword| (keyword.to_string(), keyword)).collect(),
+            }
+        }
+
+        pub fn scan_tokens(mut self) -> Vec<Token> {
+            while self.current < self.input.len() {
+                self.start = self.current;
+                self.scan_token();
+            }
+            self.start = self.current;
+            self.add_token(Kind::Eof);
+            self.tokens
+        }
+
+        fn scan_token(&mut self) {
+            let c = self.advance();
+            match c {
+                '(' | ')' | '{' | '}' | ',' | '.' | '+' | ';' | ':' | '*' => self.add_token(Kind::Punctuation),
+                '-' => {
+                    self.match_char('>');
+                    self.add_token(Kind::Punctuation);
+                }
+                '!' | '=' | '<' | '>' => {
+                    self.match_char('=');
+                    self.add_token(Kind::Punctuation);
+                }
+                '/' if self.match_char('/') => {
+                    while self.peek() != '\n' && self.current < self.input.len() {
+                        self.advance();
+                    }
+                }
+                '/' => self.add_token(Kind::Punctuation),
+                ' ' | '\r' | '\t' => {}
+                '\n' => self.line += 1,
+                '"' => {
+                    while self.peek() != '"' && self.current < self.input.len() {
+                        if self.peek() == '\n' {
+                            self.line += 1;
+                        }
+                        self.advance();
+                    }
+                    self.advance();
+                    let value = self.input[self.start + 1..self.current - 1].to_string();
+                    self.add_token(Kind::String(value));
+                }
+                c if c.is_ascii_digit() => {
+                    while self.peek().is_ascii_digit() || self.peek() == '.' {
+                        self.advance();
+                    }
+                    let value = self.input[self.start..self.current].parse().unwrap();
+                    self.add_token(Kind::Number(value));
+                }
+                c if c.is_ascii_alphabetic() || c == '_' => {
+                    while self.peek().is_ascii_alphanumeric() || self.peek() == '_' {
+                        self.advance();
+                    }
+                    let text = &self.input[self.start..self.current];
+                    let kind = match self.keywords.get(text) {
+                        Some(keyword) => Kind::Keyword(keyword),
+                        None => Kind::Identifier(text.to_string()),
+                    };
+                    self.add_token(kind);
+                }
+                c => panic!("unexpected character {:?}", c),
+            }
+        }
+
+        fn peek(&self) -> char {
+            self.input[self.current..].chars().next().unwrap_or('\0')
+        }
+
+        fn advance(&mut self) -> char {
+            let c = self.peek();
+            self.current += c.len_utf8();
+            c
+        }
+
+        fn match_char(&mut self, expected: char) -> bool {
+            let matched = self.peek() == expected;
+            if matched {
+                self.current += 1;
+            }
+            matched
+        }
+
+        fn add_token(&mut self, kind: Kind) {
+            let lexeme = self.input[self.start..self.current].to_string();
+            let (line, start, end) = (self.line, self.start, self.current);
+            self.tokens.push(Token { kind, lexeme, line, start, end });
+        }
+    }
+}
@@ -1,17 +1,19 @@
+pub mod symbol;
 pub mod ttype;
 pub mod token;
 
-use std::collections::HashMap;
+use std::borrow::Cow;
 
 use gecko_error::{Error, LineInfo};
 
+use symbol::Symbol;
+
 pub struct Lexer {
     pub input: String,
     start: usize,
     current: usize,
     line: usize,
     tokens: Vec<token::Token>,
-    keywords: HashMap<String, ttype::TType>,
 }
 
 impl Lexer {
@@ -22,39 +24,56 @@ impl Lexer {
             current: 0,
             line: 1,
             tokens: Vec::new(),
-            keywords: {
-                let mut keywords = HashMap::new();
-                keywords.insert(String::from("true"), ttype::TType::TRUE);
-                keywords.insert(String::from("false"), ttype::TType::FALSE);
-                keywords.insert(String::from("if"), ttype::TType::IF);
-                keywords.insert(String::from("else"), ttype::TType::ELSE);
-                keywords.insert(String::from("while"), ttype::TType::WHILE);
-                keywords.insert(String::from("for"), ttype::TType::FOR);
-                keywords.insert(String::from("in"), ttype::TType::IN);
-                keywords.insert(String::from("fn"), ttype::TType::FN);
-                keywords.insert(String::from("let"), ttype::TType::LET);
-                keywords.insert(String::from("return"), ttype::TType::RETURN);
-                keywords.insert(String::from("import"), ttype::TType::IMPORT);
-                keywords.insert(String::from("number"), ttype::TType::NUMBER);
-                keywords.insert(String::from("string"), ttype::TType::STRING);
-                keywords
-            },
+        }
+    }
+
+    /// A lexer that starts at byte `offset` of `input`, which is on `line`.
+    /// `offset` must not be inside a token, string or comment.
+    pub fn starting_at(input: String, offset: usize, line: usize) -> Lexer {
+        Lexer {
+            start: offset,
+            current: offset,
+            line,
+            ..Lexer::new(input)
         }
     }
 
     pub fn scan_tokens(&mut self) -> Result<Vec<token::Token>, Error> {
-        while !self.is_at_end() {
+        let mut tokens = self.scan_to(self.input.len())?;
+        tokens.push(self.eof());
+
+        Ok(tokens)
+    }
+
+    /// Scans tokens until the lexer reaches byte `stop` or the end of input,
+    /// returning the tokens scanned since the last call. A token, string or
+    /// comment that spans `stop` is scanned whole, so [`Lexer::offset`] may
+    /// end up past `stop`.
+    pub fn scan_to(&mut self, stop: usize) -> Result<Vec<token::Token>, Error> {
+        while self.current < stop && !self.is_at_end() {
             self.start = self.current;
             self.scan_token()?;
         }
 
-        self.tokens.push(token::Token::new(
+        Ok(std::mem::take(&mut self.tokens))
+    }
+
+    /// The end-of-file token for the current position.
+    pub fn eof(&self) -> token::Token {
+        token::Token::new(
             ttype::TType::EOF,
-            String::from(""),
-            LineInfo::new(self.line, self.start, self.current),
-        ));
+            "",
+            LineInfo::new(self.line, self.current, self.current),
+        )
+    }
+
+    /// The byte offset the lexer has reached.
+    pub fn offset(&self) -> usize {
+        self.current
+    }
 
-        Ok(self.tokens.clone())
+    pub fn line(&self) -> usize {
+        self.line
     }
 
     fn scan_token(&mut self) -> Result<(), Error> {
@@ -77,6 +96,7 @@ impl Lexer {
             },
             '+' => self.add_token(ttype::TType::PLUS),
             ';' => self.add_token(ttype::TType::SEMICOLON),
+            ':' => self.add_token(ttype::TType::COLON),
             '*' => self.add_token(ttype::TType::STAR),
             '!' => {
                 let ttype = if self.match_char('=') {
@@ -155,20 +175,19 @@ impl Lexer {
                         }
                     }
 
-                    let lexeme = self.input[self.start..self.current].to_string();
-                    let value = lexeme.parse::<f64>().unwrap();
+                    let value = self.input[self.start..self.current].parse::<f64>().unwrap();
                     self.add_token(ttype::TType::Number(value));
                 } else if Lexer::is_alpha(c) {
                     while Lexer::is_alphanumeric(self.peek()) {
                         self.advance();
                     }
 
-                    let lexeme = self.input[self.start..self.current].to_string();
-                    let ttype = match self.keywords.get(&lexeme) {
-                        Some(ttype) => ttype.clone(),
-                        None => ttype::TType::Identifier(lexeme),
-                    };
-                    self.add_token(ttype);
+                    let symbol = Symbol::try_intern(&self.input[self.start..self.current]).map_err(|err| {
+                        let span = gecko_error::LineInfo::new(self.line, self.start, self.current);
+                        Error::new(span, format!("Cannot intern this name: {}", err))
+                    })?;
+                    let ttype = symbol.keyword().unwrap_or(ttype::TType::Identifier(symbol));
+                    self.push_token(ttype, Cow::Borrowed(symbol.as_str()));
                 } else {
                     return Err(Error::new(
                         gecko_error::LineInfo::new(self.line, self.start, self.current),
@@ -182,15 +201,13 @@ impl Lexer {
     }
 
     fn peek_next(&self) -> char {
-        if self.current + 1 >= self.input.len() {
-            '\0'
-        } else {
-            self.input.chars().nth(self.current + 1).unwrap()
-        }
+        let mut chars = self.input[self.current..].chars();
+        chars.next();
+        chars.next().unwrap_or('\0')
     }
 
     fn is_alpha(c: char) -> bool {
-        (c >= 'a' && c <= 'z') || (c >= 'A' && c <= 'Z') || c == '_'
+        c.is_ascii_alphabetic() || c == '_'
     }
 
     fn is_alphanumeric(c: char) -> bool {
@@ -199,39 +216,36 @@ impl Lexer {
     }
 
     fn is_digit(c: char) -> bool {
-        c >= '0' && c <= '9'
+        c.is_ascii_digit()
     }
 
     fn peek(&self) -> char {
-        if self.is_at_end() {
-            '\0'
-        } else {
-            self.input.chars().nth(self.current).unwrap()
-        }
+        self.input[self.current..].chars().next().unwrap_or('\0')
     }
 
     fn match_char(&mut self, expected: char) -> bool {
-        if self.is_at_end() {
+        if self.peek() != expected {
             return false;
         }
 
-        if self.input.chars().nth(self.current).unwrap() != expected {
-            return false;
-        }
-
-        self.current += 1;
+        self.current += expected.len_utf8();
         true
     }
 
     fn add_token(&mut self, ttype: ttype::TType) {
         let text = self.input[self.start..self.current].to_string();
+        self.push_token(ttype, Cow::Owned(text));
+    }
+
+    fn push_token(&mut self, ttype: ttype::TType, lexeme: Cow<'static, str>) {
         self.tokens
-            .push(token::Token::new(ttype, text, LineInfo::new(self.line, self.start, self.current)));
+            .push(token::Token::new(ttype, lexeme, LineInfo::new(self.line, self.start, self.current)));
     }
 
     fn advance(&mut self) -> char {
-        self.current += 1;
-        self.input.chars().nth(self.current - 1).unwrap()
+        let c = self.peek();
+        self.current += c.len_utf8();
+        c
     }
 
     fn is_at_end(&self) -> bool {
@@ -0,0 +1,163 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fmt,
+    sync::{LazyLock, Mutex},
+};
+
+use crate::ttype::TType;
+
+/// Interned strings are never freed, so the interner caps how much text it
+/// holds. Anything read from outside the program (source files, REPL lines,
+/// JSON documents and `.gkc` files) goes through [`Symbol::try_intern`],
+/// which fails at the cap rather than letting such input grow the process
+/// without bound.
+pub const MAX_INTERNED_BYTES: usize = 64 * 1024 * 1024;
+
+/// Keywords are interned first, in this order, so a symbol is a keyword
+/// exactly when its index is below `KEYWORDS.len()`.
+const KEYWORDS: [(&str, TType); 13] = [
+    ("true", TType::TRUE),
+    ("false", TType::FALSE),
+    ("if", TType::IF),
+    ("else", TType::ELSE),
+    ("while", TType::WHILE),
+    ("for", TType::FOR),
+    ("in", TType::IN),
+    ("fn", TType::FN),
+    ("let", TType::LET),
+    ("return", TType::RETURN),
+    ("import", TType::IMPORT),
+    ("number", TType::NUMBER),
+    ("string", TType::STRING),
+];
+
+static INTERNER: LazyLock<Mutex<Interner>> = LazyLock::new(|| {
+    let mut interner = Interner::default();
+    for (keyword, _) in &KEYWORDS {
+        interner.intern(keyword);
+    }
+    Mutex::new(interner)
+});
+
+thread_local! {
+    /// This thread's copy of the shared interner, so interning a string the
+    /// thread has seen before, or reading one back, doesn't take the lock.
+    static LOCAL: RefCell<Interner> = RefCell::new(Interner::default());
+}
+
+/// An interned string. Symbols are shared by the whole process, so two
+/// symbols are equal exactly when their strings are, whichever file or pass
+/// produced them, and they compare and hash as integers.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol(u32);
+
+/// [`Symbol::try_intern`] was given a new string with the interner at
+/// [`MAX_INTERNED_BYTES`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InternerFull;
+
+impl fmt::Display for InternerFull {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "too many distinct names and strings (over {} MiB)", MAX_INTERNED_BYTES >> 20)
+    }
+}
+
+impl Symbol {
+    /// Interns a string the compiler itself supplies, such as `"main"`.
+    /// These don't count against [`MAX_INTERNED_BYTES`].
+    pub fn intern(string: &str) -> Symbol {
+        Symbol::lookup_or(string, |shared| Ok(shared.intern(string))).unwrap()
+    }
+
+    /// Interns a string that came from input, failing if it is new and the
+    /// interner already holds [`MAX_INTERNED_BYTES`].
+    pub fn try_intern(string: &str) -> Result<Symbol, InternerFull> {
+        Symbol::lookup_or(string, |shared| {
+            if shared.bytes + string.len() > MAX_INTERNED_BYTES && !shared.symbols.contains_key(string) {
+                return Err(InternerFull);
+            }
+            Ok(shared.intern(string))
+        })
+    }
+
+    /// Finds `string` in this thread's copy of the interner, or else calls
+    /// `intern` with the shared one locked.
+    fn lookup_or(
+        string: &str,
+        intern: impl FnOnce(&mut Interner) -> Result<Symbol, InternerFull>,
+    ) -> Result<Symbol, InternerFull> {
+        LOCAL.with_borrow_mut(|local| match local.symbols.get(string) {
+            Some(&symbol) => Ok(symbol),
+            None => {
+                let mut shared = INTERNER.lock().unwrap();
+                let symbol = intern(&mut shared);
+                local.update(&shared);
+                symbol
+            }
+        })
+    }
+
+    /// Interned strings are never freed, so they can be borrowed for as long
+    /// as the program runs.
+    pub fn as_str(self) -> &'static str {
+        LOCAL.with_borrow_mut(|local| {
+            if self.index() >= local.strings.len() {
+                local.update(&INTERNER.lock().unwrap());
+            }
+            local.strings[self.index()]
+        })
+    }
+
+    /// The keyword this symbol spells, if any.
+    pub fn keyword(self) -> Option<TType> {
+        KEYWORDS.get(self.index()).map(|(_, ttype)| ttype.clone())
+    }
+
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Symbol({:?})", self.as_str())
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Default)]
+struct Interner {
+    symbols: HashMap<&'static str, Symbol>,
+    strings: Vec<&'static str>,
+    /// Total length of `strings`.
+    bytes: usize,
+}
+
+impl Interner {
+    fn intern(&mut self, string: &str) -> Symbol {
+        if let Some(&symbol) = self.symbols.get(string) {
+            return symbol;
+        }
+
+        let string: &'static str = Box::leak(string.into());
+        let symbol = Symbol(self.strings.len() as u32);
+        self.strings.push(string);
+        self.bytes += string.len();
+        self.symbols.insert(string, symbol);
+        symbol
+    }
+
+    /// Copies the strings `shared` gained since the last update.
+    fn update(&mut self, shared: &Interner) {
+        for (index, &string) in shared.strings.iter().enumerate().skip(self.strings.len()) {
+            self.strings.push(string);
+            self.symbols.insert(string, Symbol(index as u32));
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use std::{borrow::Cow, fmt};
+
 use gecko_error::LineInfo;
 
 use crate::ttype::TType;
@@ -5,20 +7,25 @@ use crate::ttype::TType;
 #[derive(Debug, Clone, PartialEq)]
 pub struct Token {
     pub ttype: TType,
-    pub lexeme: String,
+    /// Identifiers and keywords borrow their interned string, so only other
+    /// tokens allocate one.
+    pub lexeme: Cow<'static, str>,
     pub lineinfo: LineInfo,
 }
 
 impl Token {
-    pub fn new(ttype: TType, lexeme: String, lineinfo: LineInfo) -> Token {
+    pub fn new(ttype: TType, lexeme: impl Into<Cow<'static, str>>, lineinfo: LineInfo) -> Token {
         Token {
             ttype,
-            lexeme,
+            lexeme: lexeme.into(),
             lineinfo,
         }
     }
 
-    pub fn to_string(&self) -> String {
-        format!("{:?} {}", self.ttype, self.lexeme)
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} {}", self.ttype, self.lexeme)
     }
 }
@@ -1,6 +1,8 @@
+use crate::symbol::Symbol;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum TType {
-    Identifier(String),
+    Identifier(Symbol),
     Number(f64),
     String(String),
 
@@ -41,3 +43,97 @@ pub enum TType {
     NUMBER, // number
     STRING, // string
 }
+
+impl TType {
+    /// The variant name without its payload, e.g. `Identifier` or `LPAREN`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            TType::Identifier(_) => "Identifier",
+            TType::Number(_) => "Number",
+            TType::String(_) => "String",
+            TType::EQ => "EQ",
+            TType::PLUS => "PLUS",
+            TType::MINUS => "MINUS",
+            TType::STAR => "STAR",
+            TType::SLASH => "SLASH",
+            TType::BANG => "BANG",
+            TType::EQEQ => "EQEQ",
+            TType::BANGEQ => "BANGEQ",
+            TType::LT => "LT",
+            TType::LTEQ => "LTEQ",
+            TType::GT => "GT",
+            TType::GTEQ => "GTEQ",
+            TType::LPAREN => "LPAREN",
+            TType::RPAREN => "RPAREN",
+            TType::LBRACE => "LBRACE",
+            TType::RBRACE => "RBRACE",
+            TType::COMMA => "COMMA",
+            TType::DOT => "DOT",
+            TType::SEMICOLON => "SEMICOLON",
+            TType::COLON => "COLON",
+            TType::ARROW => "ARROW",
+            TType::EOF => "EOF",
+            TType::TRUE => "TRUE",
+            TType::FALSE => "FALSE",
+            TType::IF => "IF",
+            TType::ELSE => "ELSE",
+            TType::WHILE => "WHILE",
+            TType::FOR => "FOR",
+            TType::IN => "IN",
+            TType::FN => "FN",
+            TType::LET => "LET",
+            TType::RETURN => "RETURN",
+            TType::IMPORT => "IMPORT",
+            TType::NUMBER => "NUMBER",
+            TType::STRING => "STRING",
+        }
+    }
+
+    /// The inverse of [`TType::name`]: rebuilds a token type from its name,
+    /// taking the payload of literal and identifier tokens from `lexeme`.
+    pub fn from_name(name: &str, lexeme: &str) -> Option<TType> {
+        let ttype = match name {
+            "Identifier" => TType::Identifier(Symbol::try_intern(lexeme).ok()?),
+            "Number" => TType::Number(lexeme.parse().ok()?),
+            "String" => TType::String(lexeme.strip_prefix('"')?.strip_suffix('"')?.to_string()),
+            "EQ" => TType::EQ,
+            "PLUS" => TType::PLUS,
+            "MINUS" => TType::MINUS,
+            "STAR" => TType::STAR,
+            "SLASH" => TType::SLASH,
+            "BANG" => TType::BANG,
+            "EQEQ" => TType::EQEQ,
+            "BANGEQ" => TType::BANGEQ,
+            "LT" => TType::LT,
+            "LTEQ" => TType::LTEQ,
+            "GT" => TType::GT,
+            "GTEQ" => TType::GTEQ,
+            "LPAREN" => TType::LPAREN,
+            "RPAREN" => TType::RPAREN,
+            "LBRACE" => TType::LBRACE,
+            "RBRACE" => TType::RBRACE,
+            "COMMA" => TType::COMMA,
+            "DOT" => TType::DOT,
+            "SEMICOLON" => TType::SEMICOLON,
+            "COLON" => TType::COLON,
+            "ARROW" => TType::ARROW,
+            "EOF" => TType::EOF,
+            "TRUE" => TType::TRUE,
+            "FALSE" => TType::FALSE,
+            "IF" => TType::IF,
+            "ELSE" => TType::ELSE,
+            "WHILE" => TType::WHILE,
+            "FOR" => TType::FOR,
+            "IN" => TType::IN,
+            "FN" => TType::FN,
+            "LET" => TType::LET,
+            "RETURN" => TType::RETURN,
+            "IMPORT" => TType::IMPORT,
+            "NUMBER" => TType::NUMBER,
+            "STRING" => TType::STRING,
+            _ => return None,
+        };
+
+        Some(ttype)
+    }
+}
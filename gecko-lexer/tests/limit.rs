@@ -0,0 +1,33 @@
+//! Fills the interner, so it runs in a test binary of its own.
+
+use gecko_lexer::{
+    symbol::{InternerFull, Symbol, MAX_INTERNED_BYTES},
+    Lexer,
+};
+
+#[test]
+fn input_stops_being_interned_at_the_limit() {
+    let known = Symbol::try_intern("known").unwrap();
+
+    let chunk = "x".repeat(1 << 20);
+    let mut filled = 0;
+    for i in 0.. {
+        if Symbol::try_intern(&format!("{}{}", chunk, i)).is_err() {
+            break;
+        }
+        filled += 1;
+    }
+    assert!(filled >= MAX_INTERNED_BYTES / (chunk.len() + 8) - 1, "{}", filled);
+
+    let fresh = format!("{}fresh", chunk);
+    assert_eq!(Symbol::try_intern(&fresh), Err(InternerFull));
+    assert_eq!(Symbol::try_intern("known"), Ok(known));
+    assert_eq!(Symbol::intern("compiler").as_str(), "compiler");
+
+    let err = Lexer::new(format!("let {} = known;", fresh)).scan_tokens().unwrap_err();
+    assert_eq!(
+        err.message,
+        "Cannot intern this name: too many distinct names and strings (over 64 MiB)"
+    );
+    assert!(Lexer::new(String::from("let known = known;")).scan_tokens().is_ok());
+}
@@ -0,0 +1,66 @@
+use gecko_lexer::{symbol::Symbol, ttype::TType, Lexer};
+
+fn ttypes(source: &str) -> Vec<TType> {
+    Lexer::new(source.to_string())
+        .scan_tokens()
+        .unwrap()
+        .into_iter()
+        .map(|token| token.ttype)
+        .collect()
+}
+
+#[test]
+fn keywords_are_recognised_from_their_symbol() {
+    assert_eq!(
+        ttypes("let fn return import number string true false if else while for in"),
+        [
+            TType::LET,
+            TType::FN,
+            TType::RETURN,
+            TType::IMPORT,
+            TType::NUMBER,
+            TType::STRING,
+            TType::TRUE,
+            TType::FALSE,
+            TType::IF,
+            TType::ELSE,
+            TType::WHILE,
+            TType::FOR,
+            TType::IN,
+            TType::EOF,
+        ]
+    );
+
+    assert_eq!(Symbol::intern("let").keyword(), Some(TType::LET));
+    assert_eq!(Symbol::intern("letter").keyword(), None);
+}
+
+#[test]
+fn identifiers_share_symbols_across_lexers() {
+    let first = ttypes("count");
+    let second = ttypes("total count");
+
+    assert_eq!(first[0], TType::Identifier(Symbol::intern("count")));
+    assert_eq!(first[0], second[1]);
+    assert_ne!(second[0], second[1]);
+    assert_eq!(Symbol::intern("count").as_str(), "count");
+}
+
+#[test]
+fn symbols_are_shared_between_threads() {
+    let symbol = std::thread::spawn(|| Symbol::intern("from_another_thread")).join().unwrap();
+
+    assert_eq!(symbol.as_str(), "from_another_thread");
+    assert_eq!(Symbol::intern("from_another_thread"), symbol);
+    let back = std::thread::spawn(move || Symbol::intern(symbol.as_str())).join().unwrap();
+    assert_eq!(back, symbol);
+}
+
+#[test]
+fn non_ascii_source_is_lexed_by_byte_offset() {
+    let tokens = Lexer::new(String::from("\"héllo 🦎\" x")).scan_tokens().unwrap();
+
+    assert_eq!(tokens[0].ttype, TType::String(String::from("héllo 🦎")));
+    assert_eq!(tokens[1].ttype, TType::Identifier(Symbol::intern("x")));
+    assert_eq!(tokens[1].lexeme, "x");
+}
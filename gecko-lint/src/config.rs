@@ -0,0 +1,115 @@
+use gecko_inspector::json::Json;
+
+use crate::rules::Rule;
+
+/// What a rule's findings are reported as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Off,
+    Warn,
+    Error,
+}
+
+/// Which rules run and how they are tuned. Every rule warns by default.
+///
+/// A project configures its rules in a [`Config::FILE_NAME`] file holding an
+/// object keyed by rule name. A rule's value is its level (`"off"`, `"warn"`
+/// or `"error"`), or an object of its options with an optional `"level"`:
+///
+/// ```json
+/// {
+///     "no-shadowing": "off",
+///     "max-function-length": { "level": "error", "max": 30 },
+///     "magic-numbers": { "allowed": [0, 1, 2] }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    levels: Vec<(Rule, Level)>,
+    /// The most lines a function may span, `fn` to `}`.
+    pub max_function_length: usize,
+    /// Numbers that may appear outside a `let` initializer.
+    pub allowed_numbers: Vec<f64>,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            levels: Rule::ALL.iter().map(|&rule| (rule, Level::Warn)).collect(),
+            max_function_length: 50,
+            allowed_numbers: vec![0.0, 1.0],
+        }
+    }
+}
+
+impl Config {
+    pub const FILE_NAME: &'static str = "gecko-lint.json";
+
+    pub fn level(&self, rule: Rule) -> Level {
+        self.levels.iter().find(|(r, _)| *r == rule).map_or(Level::Warn, |&(_, level)| level)
+    }
+
+    pub fn set_level(&mut self, rule: Rule, level: Level) {
+        match self.levels.iter_mut().find(|(r, _)| *r == rule) {
+            Some((_, current)) => *current = level,
+            None => self.levels.push((rule, level)),
+        }
+    }
+
+    /// Reads a config file, starting from the defaults.
+    pub fn parse(input: &str) -> Result<Config, String> {
+        let Json::Object(rules) = Json::parse(input)? else {
+            return Err(String::from("expected an object of rules"));
+        };
+
+        let mut config = Config::default();
+        for (name, value) in &rules {
+            let rule = Rule::from_name(name).ok_or_else(|| format!("unknown rule '{}'", name))?;
+
+            let options = match value {
+                Json::Object(options) => options,
+                level => {
+                    config.set_level(rule, parse_level(level, name)?);
+                    continue;
+                }
+            };
+
+            for (option, value) in options {
+                match (rule, option.as_str()) {
+                    (_, "level") => config.set_level(rule, parse_level(value, name)?),
+                    (Rule::MaxFunctionLength, "max") => {
+                        config.max_function_length = match value {
+                            Json::Number(max) if max.fract() == 0.0 && *max >= 1.0 => *max as usize,
+                            _ => return Err(format!("expected a positive whole number for 'max' of '{}'", name)),
+                        };
+                    }
+                    (Rule::MagicNumbers, "allowed") => {
+                        let numbers = match value {
+                            Json::Array(numbers) => numbers,
+                            _ => return Err(format!("expected an array of numbers for 'allowed' of '{}'", name)),
+                        };
+                        config.allowed_numbers = numbers
+                            .iter()
+                            .map(|number| match number {
+                                Json::Number(n) => Ok(*n),
+                                _ => Err(format!("expected an array of numbers for 'allowed' of '{}'", name)),
+                            })
+                            .collect::<Result<_, _>>()?;
+                    }
+                    _ => return Err(format!("unknown option '{}' for '{}'", option, name)),
+                }
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+fn parse_level(level: &Json, rule: &str) -> Result<Level, String> {
+    match level {
+        Json::String(level) if level == "off" => Ok(Level::Off),
+        Json::String(level) if level == "warn" => Ok(Level::Warn),
+        Json::String(level) if level == "error" => Ok(Level::Error),
+        _ => Err(format!("expected \"off\", \"warn\" or \"error\" as the level of '{}'", rule)),
+    }
+}
@@ -0,0 +1,13 @@
+//! Lints: warnings for code that runs but is probably a mistake.
+//!
+//! [`lint`] reports unused names, which `gecko check` shows alongside the
+//! compiler's errors. [`run_rules`] applies the style rules of `gecko lint`,
+//! each of which can be turned off or tuned per project with a [`Config`].
+
+mod config;
+mod rules;
+mod unused;
+
+pub use config::{Config, Level};
+pub use rules::{run_rules, Rule};
+pub use unused::lint;
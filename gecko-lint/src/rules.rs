@@ -0,0 +1,99 @@
+//! The rules of `gecko lint`. Each rule walks the AST and returns its
+//! findings, which [`run_rules`] reports at the level the config gives it.
+
+mod length;
+mod magic;
+mod naming;
+mod negation;
+mod shadowing;
+
+use gecko_error::{Error, LineInfo, Note};
+use gecko_parser::ast::Ast;
+
+use crate::config::{Config, Level};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rule {
+    /// Functions, variables and parameters are named in snake_case.
+    Naming,
+    /// Functions span at most [`Config::max_function_length`] lines.
+    MaxFunctionLength,
+    /// No name is declared again in a nested scope.
+    NoShadowing,
+    /// No `!!x`, `!(a != b)` or `!(a == b)`.
+    DoubleNegation,
+    /// Numbers other than [`Config::allowed_numbers`] are named with a `let`.
+    MagicNumbers,
+}
+
+impl Rule {
+    pub const ALL: [Rule; 5] = [
+        Rule::Naming,
+        Rule::MaxFunctionLength,
+        Rule::NoShadowing,
+        Rule::DoubleNegation,
+        Rule::MagicNumbers,
+    ];
+
+    /// The name the rule goes by in config files and reports.
+    pub fn name(self) -> &'static str {
+        match self {
+            Rule::Naming => "naming",
+            Rule::MaxFunctionLength => "max-function-length",
+            Rule::NoShadowing => "no-shadowing",
+            Rule::DoubleNegation => "double-negation",
+            Rule::MagicNumbers => "magic-numbers",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Rule> {
+        Rule::ALL.into_iter().find(|rule| rule.name() == name)
+    }
+
+    fn check(self, ast: &Ast, source: &str, config: &Config) -> Vec<Finding> {
+        match self {
+            Rule::Naming => naming::check(ast),
+            Rule::MaxFunctionLength => length::check(ast, source, config.max_function_length),
+            Rule::NoShadowing => shadowing::check(ast),
+            Rule::DoubleNegation => negation::check(ast, source),
+            Rule::MagicNumbers => magic::check(ast, source, &config.allowed_numbers),
+        }
+    }
+}
+
+/// Something a rule found, before the rule's level is applied.
+struct Finding {
+    span: LineInfo,
+    message: String,
+    notes: Vec<Note>,
+}
+
+impl Finding {
+    fn new(span: LineInfo, message: String, note: String) -> Finding {
+        Finding { span, message, notes: vec![Note::Note(note)] }
+    }
+}
+
+/// Applies every rule that isn't off to `ast`, returning the findings in
+/// source order. `source` is the text `ast` was parsed from.
+pub fn run_rules(ast: &Ast, source: &str, config: &Config) -> Vec<Error> {
+    let mut errors = vec![];
+
+    for rule in Rule::ALL {
+        let level = config.level(rule);
+        if level == Level::Off {
+            continue;
+        }
+
+        for mut finding in rule.check(ast, source, config) {
+            finding.notes.push(Note::Note(format!("reported by the '{}' rule", rule.name())));
+            errors.push(match level {
+                Level::Error => Error::new_with_notes(finding.span, finding.message, finding.notes),
+                _ => Error::warning(finding.span, finding.message, finding.notes),
+            });
+        }
+    }
+
+    errors.sort_by_key(|err| err.line_info.start);
+    errors
+}
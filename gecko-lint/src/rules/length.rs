@@ -0,0 +1,38 @@
+use gecko_error::position;
+use gecko_parser::{
+    ast::{Ast, StmtId},
+    nodes::stmt::Fn,
+    visit::{walk_ast, walk_fn, Visitor},
+};
+
+use super::Finding;
+
+pub(super) fn check(ast: &Ast, source: &str, max: usize) -> Vec<Finding> {
+    let mut length = Length { source, max, findings: vec![] };
+    walk_ast(&mut length, ast);
+    length.findings
+}
+
+struct Length<'a> {
+    source: &'a str,
+    max: usize,
+    findings: Vec<Finding>,
+}
+
+impl Visitor for Length<'_> {
+    fn visit_fn(&mut self, ast: &Ast, _id: StmtId, func: &Fn) {
+        let (first, _) = position(self.source, func.span.start);
+        let (last, _) = position(self.source, func.span.end);
+        let lines = last - first + 1;
+
+        if lines > self.max {
+            self.findings.push(Finding::new(
+                func.name.span,
+                format!("Function '{}' is {} lines long", func.name.name, lines),
+                format!("functions may be at most {} lines long", self.max),
+            ));
+        }
+
+        walk_fn(self, ast, func);
+    }
+}
@@ -0,0 +1,76 @@
+use gecko_parser::{
+    ast::{Ast, ExprId, StmtId},
+    nodes::{
+        expr::{Expr, LiteralExpr, Type, UnaryExpr, UnaryOp},
+        stmt::Var,
+    },
+    visit::{walk_ast, walk_unary, walk_var, Visitor},
+};
+
+use super::Finding;
+
+/// A number is named when it is the whole initializer of a `let`, negated
+/// or not; anywhere else it has to be one of the allowed numbers.
+pub(super) fn check(ast: &Ast, source: &str, allowed: &[f64]) -> Vec<Finding> {
+    let mut magic = Magic { source, allowed, findings: vec![] };
+    walk_ast(&mut magic, ast);
+    magic.findings
+}
+
+struct Magic<'a> {
+    source: &'a str,
+    allowed: &'a [f64],
+    findings: Vec<Finding>,
+}
+
+impl Magic<'_> {
+    fn number(&mut self, ast: &Ast, id: ExprId, value: f64) {
+        if self.allowed.contains(&value) {
+            return;
+        }
+
+        let span = *ast[id].span();
+        self.findings.push(Finding::new(
+            span,
+            format!("Magic number {}", &self.source[span.start..span.end]),
+            String::from("name it with a 'let' to say what it means"),
+        ));
+    }
+}
+
+impl Visitor for Magic<'_> {
+    fn visit_var(&mut self, ast: &Ast, _id: StmtId, var: &Var) {
+        if var.initializer.is_none_or(|initializer| number(ast, initializer).is_none()) {
+            walk_var(self, ast, var);
+        }
+    }
+
+    fn visit_literal(&mut self, ast: &Ast, id: ExprId, _literal: &LiteralExpr) {
+        if let Some(value) = number(ast, id) {
+            self.number(ast, id, value);
+        }
+    }
+
+    fn visit_unary(&mut self, ast: &Ast, id: ExprId, unary: &UnaryExpr) {
+        match number(ast, id) {
+            Some(value) => self.number(ast, id, value),
+            None => walk_unary(self, ast, unary),
+        }
+    }
+}
+
+/// The value of a number literal, or of a negated one.
+fn number(ast: &Ast, id: ExprId) -> Option<f64> {
+    match &ast[id] {
+        Expr::Literal(literal) => match literal.value {
+            Type::Int(i) => Some(i as f64),
+            Type::Float(f) => Some(f),
+            _ => None,
+        },
+        Expr::Unary(unary) if unary.operator == UnaryOp::Neg => match &ast[unary.right] {
+            Expr::Literal(_) => number(ast, unary.right).map(|n| -n),
+            _ => None,
+        },
+        _ => None,
+    }
+}
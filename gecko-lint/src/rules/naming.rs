@@ -0,0 +1,76 @@
+use gecko_parser::{
+    ast::{Ast, StmtId},
+    nodes::{
+        stmt::{Fn, Var},
+        Ident, Param,
+    },
+    visit::{walk_ast, walk_fn, walk_var, Visitor},
+};
+
+use super::Finding;
+
+pub(super) fn check(ast: &Ast) -> Vec<Finding> {
+    let mut naming = Naming { findings: vec![] };
+    walk_ast(&mut naming, ast);
+    naming.findings
+}
+
+struct Naming {
+    findings: Vec<Finding>,
+}
+
+impl Naming {
+    fn name(&mut self, kind: &str, ident: Ident) {
+        let name = ident.name.as_str();
+        let snake_case = snake_case(name);
+        if name != snake_case {
+            self.findings.push(Finding::new(
+                ident.span,
+                format!("{} '{}' should be snake_case", kind, name),
+                format!("rename it to '{}'", snake_case),
+            ));
+        }
+    }
+}
+
+impl Visitor for Naming {
+    fn visit_var(&mut self, ast: &Ast, _id: StmtId, var: &Var) {
+        self.name("Variable", var.name);
+        walk_var(self, ast, var);
+    }
+
+    fn visit_fn(&mut self, ast: &Ast, _id: StmtId, func: &Fn) {
+        self.name("Function", func.name);
+        walk_fn(self, ast, func);
+    }
+
+    fn visit_param(&mut self, _ast: &Ast, param: &Param) {
+        self.name("Parameter", param.name);
+    }
+}
+
+/// `name` in snake_case: `parseHTTPRequest` becomes `parse_http_request`.
+fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut out = String::with_capacity(name.len());
+
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_ascii_uppercase() {
+            let previous = i.checked_sub(1).map(|i| chars[i]);
+            let next = chars.get(i + 1);
+            // A word starts at an uppercase letter after a lowercase one or
+            // a digit, or at the last capital of an acronym.
+            let starts_word = match previous {
+                Some(p) if p.is_ascii_lowercase() || p.is_ascii_digit() => true,
+                Some(p) if p.is_ascii_uppercase() => next.is_some_and(|n| n.is_ascii_lowercase()),
+                _ => false,
+            };
+            if starts_word {
+                out.push('_');
+            }
+        }
+        out.push(c.to_ascii_lowercase());
+    }
+
+    out
+}
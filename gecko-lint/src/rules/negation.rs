@@ -0,0 +1,65 @@
+use gecko_parser::{
+    ast::{Ast, ExprId},
+    nodes::expr::{BinaryOp, Expr, UnaryExpr, UnaryOp},
+    visit::{walk_ast, walk_unary, Visitor},
+};
+
+use super::Finding;
+
+pub(super) fn check(ast: &Ast, source: &str) -> Vec<Finding> {
+    let mut negation = Negation { source, findings: vec![] };
+    walk_ast(&mut negation, ast);
+    negation.findings
+}
+
+struct Negation<'a> {
+    source: &'a str,
+    findings: Vec<Finding>,
+}
+
+impl Negation<'_> {
+    fn text(&self, ast: &Ast, id: ExprId) -> &str {
+        let span = ast[id].span();
+        &self.source[span.start..span.end]
+    }
+}
+
+impl Visitor for Negation<'_> {
+    fn visit_unary(&mut self, ast: &Ast, id: ExprId, unary: &UnaryExpr) {
+        if unary.operator == UnaryOp::Not {
+            let mut negated = unary.right;
+            while let Expr::Grouping(grouping) = &ast[negated] {
+                negated = grouping.expression;
+            }
+
+            let finding = match &ast[negated] {
+                Expr::Unary(inner) if inner.operator == UnaryOp::Not => Some((
+                    String::from("Double negation"),
+                    format!("write '{}' instead", self.text(ast, inner.right)),
+                )),
+                Expr::Binary(binary) if matches!(binary.operator, BinaryOp::Eq | BinaryOp::NotEq) => {
+                    let (negated, operator) = match binary.operator {
+                        BinaryOp::NotEq => ("!=", "=="),
+                        _ => ("==", "!="),
+                    };
+                    Some((
+                        format!("Negated '{}'", negated),
+                        format!(
+                            "write '{} {} {}' instead",
+                            self.text(ast, binary.left),
+                            operator,
+                            self.text(ast, binary.right)
+                        ),
+                    ))
+                }
+                _ => None,
+            };
+
+            if let Some((message, note)) = finding {
+                self.findings.push(Finding::new(*ast[id].span(), message, note));
+            }
+        }
+
+        walk_unary(self, ast, unary);
+    }
+}
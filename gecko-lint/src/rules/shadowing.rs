@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+use gecko_error::LineInfo;
+use gecko_lexer::symbol::Symbol;
+use gecko_parser::{
+    ast::{Ast, StmtId},
+    nodes::{
+        stmt::{Fn, If, LangImport, Stmt, Var},
+        Param,
+    },
+    visit::{walk_stmts, Visitor},
+};
+
+use super::Finding;
+
+/// Scopes follow the resolver: function bodies are checked once the scope
+/// around them is complete, so they see names declared after them too.
+pub(super) fn check(ast: &Ast) -> Vec<Finding> {
+    let mut shadowing = Shadowing { scopes: vec![], findings: vec![] };
+    shadowing.block(ast, &ast.root, &[]);
+    shadowing.findings
+}
+
+#[derive(Default)]
+struct Scope {
+    names: HashMap<Symbol, LineInfo>,
+    functions: Vec<StmtId>,
+}
+
+struct Shadowing {
+    scopes: Vec<Scope>,
+    findings: Vec<Finding>,
+}
+
+impl Shadowing {
+    /// Checks `stmts` in a new scope that starts out with `params`.
+    fn block(&mut self, ast: &Ast, stmts: &[StmtId], params: &[Param]) {
+        self.scopes.push(Scope::default());
+        for param in params {
+            self.declare(param.name.name, param.name.span);
+        }
+
+        walk_stmts(self, ast, stmts);
+
+        let functions = std::mem::take(&mut self.scopes.last_mut().unwrap().functions);
+        for id in functions {
+            if let Stmt::FnDecl(func) = &ast[id] {
+                self.block(ast, &func.body, &func.params);
+            }
+        }
+
+        self.scopes.pop();
+    }
+
+    fn declare(&mut self, name: Symbol, span: LineInfo) {
+        let (scope, enclosing) = self.scopes.split_last_mut().unwrap();
+
+        if let Some(outer) = enclosing.iter().rev().find_map(|scope| scope.names.get(&name)) {
+            self.findings.push(Finding::new(
+                span,
+                format!("'{}' shadows a name from an enclosing scope", name),
+                format!("'{}' is declared on line {}", name, outer.line),
+            ));
+        }
+
+        // A second declaration in the same scope is the resolver's error.
+        scope.names.entry(name).or_insert(span);
+    }
+}
+
+impl Visitor for Shadowing {
+    fn visit_var(&mut self, _ast: &Ast, _id: StmtId, var: &Var) {
+        self.declare(var.name.name, var.name.span);
+    }
+
+    fn visit_fn(&mut self, _ast: &Ast, id: StmtId, func: &Fn) {
+        self.declare(func.name.name, func.name.span);
+        self.scopes.last_mut().unwrap().functions.push(id);
+    }
+
+    fn visit_if(&mut self, ast: &Ast, _id: StmtId, stmt: &If) {
+        self.block(ast, &stmt.then_branch, &[]);
+        if let Some(else_branch) = &stmt.else_branch {
+            self.block(ast, else_branch, &[]);
+        }
+    }
+
+    fn visit_lang_import(&mut self, _ast: &Ast, _id: StmtId, import: &LangImport) {
+        if let Some(&alias) = import.path.last() {
+            self.declare(alias, import.span);
+        }
+    }
+}
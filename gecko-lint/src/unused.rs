@@ -0,0 +1,126 @@
+//! Unused names. A name that starts with `_` is taken to be unused on
+//! purpose and isn't reported.
+//!
+//! Gecko has no visibility modifiers, so a name is private when nothing
+//! outside the file can use it: a name declared in a function or in an
+//! `if`, or any top-level name but `main` in a file that declares `main`.
+//! The other top-level functions, variables and imports of a file without
+//! `main` are what an `import "file.gk";` of it provides, and are never
+//! reported.
+
+use std::collections::HashMap;
+
+use gecko_error::{Error, LineInfo, Note};
+use gecko_parser::{
+    ast::{Ast, ExprId, StmtId},
+    nodes::{
+        expr::{LiteralExpr, Type},
+        stmt::{FileImport, Fn, LangImport, Stmt, Var},
+    },
+    visit::{walk_ast, walk_fn, walk_var, Visitor},
+};
+use gecko_resolver::{Decl, Resolution};
+
+/// Reports the unused declarations in `ast`, in source order.
+pub fn lint(ast: &Ast, resolution: &Resolution) -> Vec<Error> {
+    let mut declarations = Declarations {
+        resolution,
+        decls: vec![],
+        file_imports: vec![],
+        unresolved: false,
+    };
+    walk_ast(&mut declarations, ast);
+
+    let mut uses: HashMap<Decl, Vec<LineInfo>> = HashMap::new();
+    for (id, decl) in resolution.bindings() {
+        uses.entry(decl).or_default().push(*ast[id].span());
+    }
+
+    let program = ast
+        .root
+        .iter()
+        .any(|&id| matches!(&ast[id], Stmt::FnDecl(func) if func.name.name.as_str() == "main"));
+
+    let mut warnings = vec![];
+
+    for decl in declarations.decls {
+        let name = decl.name(ast);
+        let spans = uses.get(&decl).map(Vec::as_slice).unwrap_or_default();
+
+        let public = |id: StmtId| ast.root.contains(&id) && (!program || name.as_str() == "main");
+        let (kind, used) = match decl {
+            Decl::Var(id) => ("variable", public(id) || !spans.is_empty()),
+            Decl::Param { .. } => ("parameter", !spans.is_empty()),
+            Decl::Module(id) => ("import", public(id) || !spans.is_empty()),
+            Decl::Fn(id) => {
+                // Calls from its own body don't make a function used.
+                let body = *ast[id].span();
+                let called = spans.iter().any(|span| span.start < body.start || span.end > body.end);
+                ("function", public(id) || called)
+            }
+        };
+        if used || name.as_str().starts_with('_') {
+            continue;
+        }
+
+        let message = format!("Unused {} '{}'", kind, name);
+        let notes = match decl {
+            Decl::Module(_) => vec![],
+            _ => vec![Note::Note(format!("if this is intentional, name it '_{}'", name))],
+        };
+        warnings.push(Error::warning(decl.span(ast), message, notes));
+    }
+
+    // What a file import declares isn't known, but every such name is left
+    // unresolved, so a file where every name resolves uses none of them.
+    if !declarations.unresolved {
+        for id in declarations.file_imports {
+            if let Stmt::FileImport(import) = &ast[id] {
+                warnings.push(Error::warning(
+                    import.span,
+                    format!("Unused import \"{}\"", import.path),
+                    vec![Note::Note(String::from("every name in this file is declared in it"))],
+                ));
+            }
+        }
+    }
+
+    warnings.sort_by_key(|warning| warning.line_info.start);
+    warnings
+}
+
+/// Collects every declaration in the AST, and whether any name is left
+/// unresolved.
+struct Declarations<'a> {
+    resolution: &'a Resolution,
+    decls: Vec<Decl>,
+    file_imports: Vec<StmtId>,
+    unresolved: bool,
+}
+
+impl Visitor for Declarations<'_> {
+    fn visit_var(&mut self, ast: &Ast, id: StmtId, var: &Var) {
+        self.decls.push(Decl::Var(id));
+        walk_var(self, ast, var);
+    }
+
+    fn visit_fn(&mut self, ast: &Ast, id: StmtId, func: &Fn) {
+        self.decls.push(Decl::Fn(id));
+        self.decls.extend((0..func.params.len()).map(|index| Decl::Param { func: id, index }));
+        walk_fn(self, ast, func);
+    }
+
+    fn visit_lang_import(&mut self, _ast: &Ast, id: StmtId, _import: &LangImport) {
+        self.decls.push(Decl::Module(id));
+    }
+
+    fn visit_file_import(&mut self, _ast: &Ast, id: StmtId, _import: &FileImport) {
+        self.file_imports.push(id);
+    }
+
+    fn visit_literal(&mut self, _ast: &Ast, id: ExprId, literal: &LiteralExpr) {
+        if matches!(literal.value, Type::Iden(_)) && self.resolution.get(id).is_none() {
+            self.unresolved = true;
+        }
+    }
+}
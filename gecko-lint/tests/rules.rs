@@ -0,0 +1,169 @@
+use gecko_lexer::Lexer;
+use gecko_lint::{run_rules, Config, Level, Rule};
+use gecko_parser::Parser;
+
+/// Each finding's rule, message and the source text it points at, with
+/// only `rule` turned on.
+fn findings(rule: Rule, config: &Config, source: &str) -> Vec<(String, String)> {
+    let mut config = config.clone();
+    for other in Rule::ALL.into_iter().filter(|&other| other != rule) {
+        config.set_level(other, Level::Off);
+    }
+
+    let tokens = Lexer::new(source.to_string()).scan_tokens().unwrap();
+    let ast = Parser::new(tokens).parse().unwrap();
+    run_rules(&ast, source, &config)
+        .into_iter()
+        .inspect(|err| assert_eq!(err.notes.last().unwrap().to_string(), format!("note: reported by the '{}' rule", rule.name())))
+        .map(|err| (err.message, source[err.line_info.start..err.line_info.end].to_string()))
+        .collect()
+}
+
+fn finding(message: &str, text: &str) -> (String, String) {
+    (message.to_string(), text.to_string())
+}
+
+#[test]
+fn names_are_snake_case() {
+    let source = "fn parseHTTPRequest(rawText: string) {
+    let _private_value = 1;
+    let Total = 2;
+}
+fn main() {}
+";
+
+    assert_eq!(
+        findings(Rule::Naming, &Config::default(), source),
+        [
+            finding("Function 'parseHTTPRequest' should be snake_case", "parseHTTPRequest"),
+            finding("Parameter 'rawText' should be snake_case", "rawText"),
+            finding("Variable 'Total' should be snake_case", "Total"),
+        ]
+    );
+}
+
+#[test]
+fn functions_longer_than_the_limit() {
+    let source = "fn short() {}
+fn long() {
+    let a = 1;
+    let b = 2;
+}
+";
+    let mut config = Config::default();
+    config.max_function_length = 3;
+
+    assert_eq!(
+        findings(Rule::MaxFunctionLength, &config, source),
+        [finding("Function 'long' is 4 lines long", "long")]
+    );
+}
+
+#[test]
+fn shadowing_follows_the_scopes() {
+    let source = "let count = 1;
+fn f(count: number) {
+    if true {
+        let value = count;
+    }
+    let value = 2;
+}
+fn g() {
+    let later = 1;
+}
+let later = 2;
+";
+
+    let shadows = findings(Rule::NoShadowing, &Config::default(), source);
+    assert_eq!(
+        shadows,
+        [
+            finding("'count' shadows a name from an enclosing scope", "count"),
+            finding("'later' shadows a name from an enclosing scope", "later"),
+        ]
+    );
+}
+
+#[test]
+fn double_negations() {
+    let source = "let a = !!true;
+let b = !(1 != 2);
+let c = !(1 == 2);
+let d = !(1 < 2);
+";
+
+    assert_eq!(
+        findings(Rule::DoubleNegation, &Config::default(), source),
+        [
+            finding("Double negation", "!!true"),
+            finding("Negated '!='", "!(1 != 2)"),
+            finding("Negated '=='", "!(1 == 2)"),
+        ]
+    );
+}
+
+#[test]
+fn numbers_outside_a_let_are_magic() {
+    let source = "let limit = 100;
+let below = -5;
+let area = limit * 3.5;
+fn f() -> number {
+    return -1 + 0;
+}
+";
+
+    assert_eq!(findings(Rule::MagicNumbers, &Config::default(), source), [finding("Magic number 3.5", "3.5"), finding("Magic number -1", "-1")]);
+
+    let mut config = Config::default();
+    config.allowed_numbers = vec![-1.0, 0.0, 3.5];
+    assert!(findings(Rule::MagicNumbers, &config, source).is_empty());
+}
+
+#[test]
+fn levels_decide_the_severity() {
+    let source = "let badName = 1;";
+    let tokens = Lexer::new(source.to_string()).scan_tokens().unwrap();
+    let ast = Parser::new(tokens).parse().unwrap();
+
+    let mut config = Config::default();
+    assert!(run_rules(&ast, source, &config)[0].is_warning());
+
+    config.set_level(Rule::Naming, Level::Error);
+    assert!(!run_rules(&ast, source, &config)[0].is_warning());
+
+    config.set_level(Rule::Naming, Level::Off);
+    assert!(run_rules(&ast, source, &config).is_empty());
+}
+
+#[test]
+fn config_files_set_levels_and_options() {
+    let config = Config::parse(
+        r#"{
+            "naming": "off",
+            "max-function-length": { "level": "error", "max": 30 },
+            "magic-numbers": { "allowed": [0, 1, 2] }
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(config.level(Rule::Naming), Level::Off);
+    assert_eq!(config.level(Rule::MaxFunctionLength), Level::Error);
+    assert_eq!(config.level(Rule::MagicNumbers), Level::Warn);
+    assert_eq!(config.level(Rule::NoShadowing), Level::Warn);
+    assert_eq!(config.max_function_length, 30);
+    assert_eq!(config.allowed_numbers, [0.0, 1.0, 2.0]);
+}
+
+#[test]
+fn bad_config_files_are_rejected() {
+    let error = |input: &str| Config::parse(input).unwrap_err();
+
+    assert_eq!(error("[]"), "expected an object of rules");
+    assert_eq!(error(r#"{"tabs": "off"}"#), "unknown rule 'tabs'");
+    assert_eq!(error(r#"{"naming": "loud"}"#), "expected \"off\", \"warn\" or \"error\" as the level of 'naming'");
+    assert_eq!(error(r#"{"naming": {"max": 3}}"#), "unknown option 'max' for 'naming'");
+    assert_eq!(
+        error(r#"{"max-function-length": {"max": 2.5}}"#),
+        "expected a positive whole number for 'max' of 'max-function-length'"
+    );
+}
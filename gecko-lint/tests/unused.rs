@@ -0,0 +1,105 @@
+use gecko_lexer::Lexer;
+use gecko_lint::lint;
+use gecko_parser::Parser;
+use gecko_resolver::resolve;
+
+/// Each warning's message and the source text it points at.
+fn warnings(source: &str) -> Vec<(String, String)> {
+    let tokens = Lexer::new(source.to_string()).scan_tokens().unwrap();
+    let ast = Parser::new(tokens).parse().unwrap();
+    let (resolution, _) = resolve(&ast);
+    lint(&ast, &resolution)
+        .into_iter()
+        .inspect(|warning| assert!(warning.is_warning()))
+        .map(|warning| (warning.message, source[warning.line_info.start..warning.line_info.end].to_string()))
+        .collect()
+}
+
+fn warning(message: &str, text: &str) -> (String, String) {
+    (message.to_string(), text.to_string())
+}
+
+#[test]
+fn unused_variables_and_parameters() {
+    let source = "fn add(a: number, b: number) -> number {
+    let sum = a;
+    let unused = 1;
+    return sum;
+}
+add(1, 2);
+";
+
+    assert_eq!(
+        warnings(source),
+        [warning("Unused parameter 'b'", "b"), warning("Unused variable 'unused'", "unused")]
+    );
+}
+
+#[test]
+fn unused_imports() {
+    assert_eq!(
+        warnings("import std.io; import std.math; fn main() { io.println(1); }"),
+        [warning("Unused import 'math'", "import std.math;")]
+    );
+    assert_eq!(
+        warnings("import \"lib.gk\"; let a = 1; a;"),
+        [warning("Unused import \"lib.gk\"", "import \"lib.gk\";")]
+    );
+    // `helper` can only come from the file.
+    assert!(warnings("import \"lib.gk\"; helper();").is_empty());
+}
+
+#[test]
+fn private_functions_must_be_called() {
+    let source = "fn main() {
+    fn nested() {}
+    fn called() {}
+    called();
+}
+fn helper() {}
+fn recursive() {
+    recursive();
+}
+";
+
+    assert_eq!(
+        warnings(source),
+        [
+            warning("Unused function 'nested'", "nested"),
+            warning("Unused function 'helper'", "helper"),
+            warning("Unused function 'recursive'", "recursive"),
+        ]
+    );
+}
+
+#[test]
+fn top_level_names_of_a_library_are_public() {
+    assert!(warnings("fn helper() {} fn other() {}").is_empty());
+    assert!(warnings("import std.io; let limit = 10;").is_empty());
+    assert_eq!(warnings("if true { fn local() {} }"), [warning("Unused function 'local'", "local")]);
+    assert_eq!(
+        warnings("import std.io; let limit = 10; fn main() {}"),
+        [warning("Unused import 'io'", "import std.io;"), warning("Unused variable 'limit'", "limit")]
+    );
+}
+
+#[test]
+fn underscore_names_are_unused_on_purpose() {
+    let source = "fn main() {
+    fn _later(_unused: number) {}
+    let _x = 1;
+}
+";
+
+    assert!(warnings(source).is_empty());
+}
+
+#[test]
+fn warnings_suggest_the_underscore_name() {
+    let tokens = Lexer::new(String::from("fn f() { let x = 1; } f();")).scan_tokens().unwrap();
+    let ast = Parser::new(tokens).parse().unwrap();
+    let (resolution, _) = resolve(&ast);
+    let warnings = lint(&ast, &resolution);
+
+    assert_eq!(warnings[0].notes[0].to_string(), "note: if this is intentional, name it '_x'");
+}
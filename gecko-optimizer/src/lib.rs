@@ -0,0 +1,231 @@
+//! Constant folding: operators whose operands are literals are evaluated
+//! ahead of time and replaced by a literal of the result, and the branch of
+//! an `if` whose condition folds to a constant that can never run is
+//! dropped. Folding follows the interpreter, so a folded program prints the
+//! same as the original; operations the interpreter would reject (`1 + "a"`)
+//! are left for it to report. Division by a constant zero in code that can
+//! run is also warned about here.
+
+use gecko_error::Error;
+use gecko_lexer::symbol::Symbol;
+use gecko_parser::{
+    ast::{Ast, ExprId, StmtId},
+    nodes::{
+        expr::{BinaryOp, Expr, LiteralExpr, Type, UnaryOp},
+        stmt::Stmt,
+    },
+    visit::{walk_binary_mut, walk_grouping_mut, walk_unary_mut, VisitorMut},
+};
+
+/// Folds the constants in `ast` in place, returning warnings for any
+/// divisions by zero.
+pub fn optimize(ast: &mut Ast) -> Vec<Error> {
+    let mut folder = Folder { errors: vec![] };
+    let root = std::mem::take(&mut ast.root);
+    ast.root = folder.block(ast, root);
+    folder.errors
+}
+
+struct Folder {
+    errors: Vec<Error>,
+}
+
+impl Folder {
+    /// Folds `stmts`, replacing each `if` with a constant condition by the
+    /// branch that runs.
+    fn block(&mut self, ast: &mut Ast, stmts: Vec<StmtId>) -> Vec<StmtId> {
+        let mut folded = Vec::with_capacity(stmts.len());
+
+        for id in stmts {
+            self.visit_stmt_mut(ast, id);
+
+            let Stmt::If(stmt) = &ast[id] else {
+                folded.push(id);
+                continue;
+            };
+            let Some(Type::Bool(condition)) = constant(ast, stmt.condition) else {
+                folded.push(id);
+                continue;
+            };
+
+            let live = match condition {
+                true => stmt.then_branch.clone(),
+                false => stmt.else_branch.clone().unwrap_or_default(),
+            };
+
+            // A branch is a scope of its own, so one that declares names
+            // stays in its `if`; only the other branch is emptied.
+            if declares_names(ast, &live) {
+                if let Stmt::If(stmt) = &mut ast[id] {
+                    match condition {
+                        true => stmt.else_branch = None,
+                        false => stmt.then_branch.clear(),
+                    }
+                }
+                folded.push(id);
+            } else {
+                folded.extend(live);
+            }
+        }
+
+        folded
+    }
+
+    /// Replaces `id` with a literal of `value`, keeping its span.
+    fn replace(&mut self, ast: &mut Ast, id: ExprId, value: Option<Type>) {
+        if let Some(value) = value {
+            let span = *ast[id].span();
+            ast[id] = Expr::Literal(LiteralExpr::new(value, span));
+        }
+    }
+}
+
+impl VisitorMut for Folder {
+    fn visit_fn_mut(&mut self, ast: &mut Ast, id: StmtId) {
+        let Stmt::FnDecl(func) = &mut ast[id] else {
+            return;
+        };
+        let body = std::mem::take(&mut func.body);
+        let body = self.block(ast, body);
+        if let Stmt::FnDecl(func) = &mut ast[id] {
+            func.body = body;
+        }
+    }
+
+    fn visit_if_mut(&mut self, ast: &mut Ast, id: StmtId) {
+        let Stmt::If(stmt) = &mut ast[id] else {
+            return;
+        };
+        let condition = stmt.condition;
+        let then_branch = std::mem::take(&mut stmt.then_branch);
+        let else_branch = stmt.else_branch.take();
+
+        self.visit_expr_mut(ast, condition);
+
+        // `block` drops the branch that can't run, so it isn't folded.
+        let (then_branch, else_branch) = match constant(ast, condition) {
+            Some(Type::Bool(true)) => (self.block(ast, then_branch), None),
+            Some(Type::Bool(false)) => (vec![], else_branch.map(|branch| self.block(ast, branch))),
+            _ => (self.block(ast, then_branch), else_branch.map(|branch| self.block(ast, branch))),
+        };
+
+        if let Stmt::If(stmt) = &mut ast[id] {
+            stmt.then_branch = then_branch;
+            stmt.else_branch = else_branch;
+        }
+    }
+
+    fn visit_grouping_mut(&mut self, ast: &mut Ast, id: ExprId) {
+        walk_grouping_mut(self, ast, id);
+
+        let Expr::Grouping(grouping) = &ast[id] else {
+            return;
+        };
+        let value = constant(ast, grouping.expression);
+        self.replace(ast, id, value);
+    }
+
+    fn visit_binary_mut(&mut self, ast: &mut Ast, id: ExprId) {
+        walk_binary_mut(self, ast, id);
+
+        let Expr::Binary(binary) = &ast[id] else {
+            return;
+        };
+        let right = constant(ast, binary.right);
+
+        if binary.operator == BinaryOp::Div && right.and_then(number) == Some(0.0) {
+            let span = binary.operator_span.to(ast[binary.right].span());
+            self.errors.push(Error::warning(span, String::from("Division by zero"), vec![]));
+            return;
+        }
+
+        let value = match (constant(ast, binary.left), right) {
+            (Some(left), Some(right)) => fold_binary(binary.operator, left, right),
+            _ => None,
+        };
+        self.replace(ast, id, value);
+    }
+
+    fn visit_unary_mut(&mut self, ast: &mut Ast, id: ExprId) {
+        walk_unary_mut(self, ast, id);
+
+        let Expr::Unary(unary) = &ast[id] else {
+            return;
+        };
+        let value = match (unary.operator, constant(ast, unary.right)) {
+            (UnaryOp::Neg, Some(Type::Int(i))) => i.checked_neg().map(Type::Int),
+            (UnaryOp::Neg, Some(Type::Float(f))) => Some(Type::Float(-f)),
+            (UnaryOp::Not, Some(Type::Bool(b))) => Some(Type::Bool(!b)),
+            _ => None,
+        };
+        self.replace(ast, id, value);
+    }
+}
+
+/// The value of `id` if it is a literal number, string or bool.
+fn constant(ast: &Ast, id: ExprId) -> Option<Type> {
+    match &ast[id] {
+        Expr::Literal(literal) => match literal.value {
+            value @ (Type::Int(_) | Type::Float(_) | Type::String(_) | Type::Bool(_)) => Some(value),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn number(value: Type) -> Option<f64> {
+    match value {
+        Type::Int(i) => Some(i as f64),
+        Type::Float(f) => Some(f),
+        _ => None,
+    }
+}
+
+fn fold_binary(operator: BinaryOp, left: Type, right: Type) -> Option<Type> {
+    match (operator, left, right) {
+        (BinaryOp::Eq, left, right) => Some(Type::Bool(equals(left, right))),
+        (BinaryOp::NotEq, left, right) => Some(Type::Bool(!equals(left, right))),
+        (BinaryOp::Add, Type::String(a), Type::String(b)) => {
+            Some(Type::String(Symbol::intern(&format!("{}{}", a, b))))
+        }
+        // Integers stay integers unless they overflow.
+        (operator, Type::Int(a), Type::Int(b)) => match operator {
+            BinaryOp::Add => a.checked_add(b).map(Type::Int),
+            BinaryOp::Sub => a.checked_sub(b).map(Type::Int),
+            BinaryOp::Mul => a.checked_mul(b).map(Type::Int),
+            _ => None,
+        }
+        .or_else(|| fold_numbers(operator, a as f64, b as f64)),
+        (operator, left, right) => fold_numbers(operator, number(left)?, number(right)?),
+    }
+}
+
+fn fold_numbers(operator: BinaryOp, a: f64, b: f64) -> Option<Type> {
+    match operator {
+        BinaryOp::Add => Some(Type::Float(a + b)),
+        BinaryOp::Sub => Some(Type::Float(a - b)),
+        BinaryOp::Mul => Some(Type::Float(a * b)),
+        BinaryOp::Div => Some(Type::Float(a / b)),
+        BinaryOp::Less => Some(Type::Bool(a < b)),
+        BinaryOp::LessEq => Some(Type::Bool(a <= b)),
+        BinaryOp::Greater => Some(Type::Bool(a > b)),
+        BinaryOp::GreaterEq => Some(Type::Bool(a >= b)),
+        BinaryOp::Eq | BinaryOp::NotEq => None,
+    }
+}
+
+/// Equality as the interpreter sees it: values of different types are never
+/// equal.
+fn equals(left: Type, right: Type) -> bool {
+    match (left, right) {
+        (Type::String(a), Type::String(b)) => a == b,
+        (Type::Bool(a), Type::Bool(b)) => a == b,
+        (left, right) => matches!((number(left), number(right)), (Some(a), Some(b)) if a == b),
+    }
+}
+
+fn declares_names(ast: &Ast, stmts: &[StmtId]) -> bool {
+    stmts
+        .iter()
+        .any(|&id| matches!(ast[id], Stmt::VarDecl(_) | Stmt::FnDecl(_) | Stmt::LangImport(_)))
+}
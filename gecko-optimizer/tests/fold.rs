@@ -0,0 +1,124 @@
+use gecko_error::LineInfo;
+use gecko_inspector::inspect_sexpr;
+use gecko_lexer::Lexer;
+use gecko_optimizer::optimize;
+use gecko_parser::{
+    ast::Ast,
+    nodes::{
+        expr::{BinaryExpr, BinaryOp, Expr, LiteralExpr, Type},
+        stmt::{ExprStmt, Stmt},
+    },
+    Parser,
+};
+
+fn parse(source: &str) -> Ast {
+    let tokens = Lexer::new(source.to_string()).scan_tokens().unwrap();
+    Parser::new(tokens).parse().unwrap()
+}
+
+/// The folded program as S-expressions.
+fn fold(source: &str) -> String {
+    let mut ast = parse(source);
+    let errors = optimize(&mut ast);
+    assert!(errors.is_empty(), "{:?}", errors.iter().map(|e| &e.message).collect::<Vec<_>>());
+    inspect_sexpr(&ast)
+}
+
+#[test]
+fn arithmetic_is_folded() {
+    assert_eq!(fold("2 * 3 + 4;"), "10");
+    assert_eq!(fold("(1 + 2) * -(3 - 5);"), "6");
+    assert_eq!(fold("7 / 2;"), "3.5");
+}
+
+#[test]
+fn strings_are_concatenated() {
+    assert_eq!(fold("\"gec\" + \"ko\" + \"!\";"), "\"gecko!\"");
+}
+
+#[test]
+fn negation_and_comparisons_are_folded() {
+    assert_eq!(fold("!true;"), "false");
+    assert_eq!(fold("!(1 < 2);"), "false");
+    assert_eq!(fold("2 >= 2 == true;"), "true");
+    assert_eq!(fold("\"a\" != \"b\";"), "true");
+    assert_eq!(fold("1 == \"1\";"), "false");
+}
+
+#[test]
+fn names_and_calls_are_left_alone() {
+    assert_eq!(fold("x + 1 * 2;"), "(+ x 2)");
+    assert_eq!(fold("f(1 + 1) - 3;"), "(- (call f 2) 3)");
+}
+
+#[test]
+fn mistyped_operands_are_left_for_the_interpreter() {
+    assert_eq!(fold("1 + \"a\";"), "(+ 1 \"a\")");
+    assert_eq!(fold("-true;"), "(- true)");
+}
+
+#[test]
+fn integers_stay_integers() {
+    let mut ast = Ast::new();
+    let span = LineInfo::new(1, 0, 1);
+    let left = ast.add_expr(Expr::Literal(LiteralExpr::new(Type::Int(6), span)));
+    let right = ast.add_expr(Expr::Literal(LiteralExpr::new(Type::Int(7), span)));
+    let product = ast.add_expr(Expr::Binary(BinaryExpr::new(left, BinaryOp::Mul, span, right, span)));
+    let stmt = ast.add_stmt(Stmt::ExprStmt(ExprStmt::new(product, span)));
+    ast.root.push(stmt);
+
+    optimize(&mut ast);
+    assert!(matches!(&ast[product], Expr::Literal(LiteralExpr { value: Type::Int(42), .. })));
+}
+
+#[test]
+fn dead_branches_are_dropped() {
+    assert_eq!(fold("if 1 < 2 { f(); } else { g(); } h();"), "(call f)\n(call h)");
+    assert_eq!(fold("if 1 > 2 { f(); } else { g(); }"), "(call g)");
+    assert_eq!(fold("if false { f(); }"), "");
+    assert_eq!(fold("if false { f(); } else if true { g(); } else { h(); }"), "(call g)");
+    assert_eq!(fold("fn main() { if !false { return 1; } return 2; }"), "(fn main () void (return 1) (return 2))");
+    assert_eq!(fold("if x { f(1 + 1); }"), "(if x (do (call f 2)))");
+}
+
+#[test]
+fn branches_that_declare_names_keep_their_scope() {
+    assert_eq!(fold("if true { let x = 1; } else { g(); }"), "(if true (do (let x 1)))");
+    assert_eq!(fold("if false { f(); } else { fn g() {} }"), "(if false (do) (do (fn g () void)))");
+}
+
+#[test]
+fn division_by_a_constant_zero_is_a_warning() {
+    let source = "let a = 1 / 0;\nlet b = x / (2 - 2);\nlet c = x / 0.5;";
+    let mut ast = parse(source);
+    let errors = optimize(&mut ast);
+
+    assert!(errors.iter().all(|err| err.is_warning()));
+    let errors: Vec<_> = errors
+        .iter()
+        .map(|err| (err.message.as_str(), &source[err.line_info.start..err.line_info.end]))
+        .collect();
+    assert_eq!(errors, [("Division by zero", "/ 0"), ("Division by zero", "/ (2 - 2)")]);
+}
+
+#[test]
+fn division_by_zero_in_a_branch_that_never_runs_is_not_reported() {
+    let source = "if false { let a = 1 / 0; } else { b / 0; }\nif 1 > 2 { c / 0; }\nif true { d / 1; } else { d / 0; }";
+    let mut ast = parse(source);
+    let errors = optimize(&mut ast);
+
+    let errors: Vec<_> = errors.iter().map(|err| &source[err.line_info.start..err.line_info.end]).collect();
+    assert_eq!(errors, ["/ 0"]);
+    assert_eq!(ast.root.len(), 2);
+}
+
+#[test]
+fn folded_literals_keep_the_span_of_the_expression() {
+    let source = "let a = 1 + 2 * 3;";
+    let mut ast = parse(source);
+    optimize(&mut ast);
+
+    let Stmt::VarDecl(var) = &ast[ast.root[0]] else { panic!("expected a let") };
+    let span = *ast[var.initializer.unwrap()].span();
+    assert_eq!(&source[span.start..span.end], "1 + 2 * 3");
+}
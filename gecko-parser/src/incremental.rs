@@ -0,0 +1,196 @@
+//! Reparsing after a text edit.
+//!
+//! Top-level statements that end before the edit or start after it are
+//! copied from the previous tree (the latter with their spans shifted), and
+//! only the text between them is lexed and parsed again. The last statement
+//! before the edit is parsed again too, since the new text can continue it,
+//! like an `else` typed after an `if`. If a token, string
+//! or comment runs into a kept statement, or the new text doesn't parse on
+//! its own, the reparsed window grows to the next statement.
+
+use std::ops::Range;
+
+use gecko_error::{Error, LineInfo};
+use gecko_lexer::Lexer;
+
+use crate::{
+    ast::{Ast, ExprId, StmtId},
+    nodes::{
+        expr::{BinaryExpr, CallExpr, Expr, GetExpr, GroupingExpr, LiteralExpr, UnaryExpr},
+        stmt::{ExprStmt, FileImport, Fn, If, LangImport, Return, Stmt, Var},
+        Ident, Param,
+    },
+    Parser,
+};
+
+/// Replaces the bytes in `range` of the old source with `text`.
+#[derive(Debug, Clone)]
+pub struct Edit {
+    pub range: Range<usize>,
+    pub text: String,
+}
+
+impl Edit {
+    pub fn new(range: Range<usize>, text: impl Into<String>) -> Edit {
+        Edit { range, text: text.into() }
+    }
+
+    /// The source after the edit.
+    pub fn apply(&self, source: &str) -> String {
+        let mut edited = source.to_string();
+        edited.replace_range(self.range.clone(), &self.text);
+        edited
+    }
+}
+
+/// Parses `source`, the text after `edit` was applied to the text
+/// `previous` was parsed from. The result is the same as parsing `source`
+/// from scratch.
+pub fn reparse(previous: &Ast, source: &str, edit: &Edit) -> Result<Ast, Error> {
+    let root = &previous.root;
+    let span = |index: usize| *previous[root[index]].span();
+
+    let prefix = root.iter().take_while(|&&id| previous[id].span().end < edit.range.start).count().saturating_sub(1);
+    let suffix = (prefix..root.len()).find(|&index| span(index).start > edit.range.end).unwrap_or(root.len());
+    let bytes = edit.text.len() as isize - edit.range.len() as isize;
+
+    let mut ast = Ast::new();
+    for &id in &root[..prefix] {
+        let stmt = Copier::new(previous, &mut ast, 0, 0).stmt(id);
+        ast.root.push(stmt);
+    }
+
+    let window_start = if prefix > 0 { span(prefix - 1).end } else { 0 };
+    let line = 1 + source.as_bytes()[..window_start].iter().filter(|&&b| b == b'\n').count();
+    let mut lexer = Lexer::starting_at(source.to_string(), window_start, line);
+    let mut tokens = vec![];
+
+    for stop_index in suffix..=root.len() {
+        let stop = match stop_index < root.len() {
+            true => span(stop_index).start.wrapping_add_signed(bytes),
+            false => source.len(),
+        };
+        if lexer.offset() > stop {
+            continue;
+        }
+
+        tokens.extend(lexer.scan_to(stop)?);
+        if lexer.offset() != stop {
+            continue;
+        }
+
+        let mut window = tokens.clone();
+        window.push(lexer.eof());
+        let parsed = match Parser::new(window).parse() {
+            Ok(parsed) => parsed,
+            Err(err) if stop_index == root.len() => return Err(err),
+            Err(_) => continue,
+        };
+
+        for &id in &parsed.root {
+            let stmt = Copier::new(&parsed, &mut ast, 0, 0).stmt(id);
+            ast.root.push(stmt);
+        }
+
+        if stop_index < root.len() {
+            let lines = lexer.line() as isize - span(stop_index).line as isize;
+            for &id in &root[stop_index..] {
+                let stmt = Copier::new(previous, &mut ast, bytes, lines).stmt(id);
+                ast.root.push(stmt);
+            }
+        }
+
+        return Ok(ast);
+    }
+
+    unreachable!("the last window reaches the end of the source")
+}
+
+/// Copies statements from one tree into another, moving their spans by
+/// `bytes` and `lines`.
+struct Copier<'a> {
+    from: &'a Ast,
+    to: &'a mut Ast,
+    bytes: isize,
+    lines: isize,
+}
+
+impl<'a> Copier<'a> {
+    fn new(from: &'a Ast, to: &'a mut Ast, bytes: isize, lines: isize) -> Copier<'a> {
+        Copier { from, to, bytes, lines }
+    }
+
+    fn span(&self, span: LineInfo) -> LineInfo {
+        LineInfo::new(
+            span.line.wrapping_add_signed(self.lines),
+            span.start.wrapping_add_signed(self.bytes),
+            span.end.wrapping_add_signed(self.bytes),
+        )
+    }
+
+    fn ident(&self, ident: Ident) -> Ident {
+        Ident::new(ident.name, self.span(ident.span))
+    }
+
+    fn stmt(&mut self, id: StmtId) -> StmtId {
+        let from = self.from;
+        let stmt = match &from[id] {
+            Stmt::ExprStmt(stmt) => Stmt::ExprStmt(ExprStmt::new(self.expr(stmt.expr), self.span(stmt.span))),
+            Stmt::VarDecl(var) => {
+                let initializer = var.initializer.map(|expr| self.expr(expr));
+                Stmt::VarDecl(Var::new(self.ident(var.name), initializer, self.span(var.span)))
+            }
+            Stmt::FnDecl(func) => {
+                let params = func
+                    .params
+                    .iter()
+                    .map(|param| Param::new(self.ident(param.name), self.ident(param.type_), self.span(param.span)))
+                    .collect();
+                let body = func.body.iter().map(|&stmt| self.stmt(stmt)).collect();
+                let return_type = func.return_type.map(|t| self.ident(t));
+                Stmt::FnDecl(Fn::new(self.ident(func.name), params, body, return_type, self.span(func.span)))
+            }
+            Stmt::Return(ret) => {
+                let value = ret.value.map(|expr| self.expr(expr));
+                Stmt::Return(Return::new(value, self.span(ret.span)))
+            }
+            Stmt::If(stmt) => {
+                let condition = self.expr(stmt.condition);
+                let then_branch = stmt.then_branch.iter().map(|&stmt| self.stmt(stmt)).collect();
+                let else_branch = stmt.else_branch.as_ref().map(|branch| branch.iter().map(|&stmt| self.stmt(stmt)).collect());
+                Stmt::If(If::new(condition, then_branch, else_branch, self.span(stmt.span)))
+            }
+            Stmt::LangImport(import) => Stmt::LangImport(LangImport::new(import.path.clone(), self.span(import.span))),
+            Stmt::FileImport(import) => Stmt::FileImport(FileImport::new(import.path, self.span(import.span))),
+        };
+        self.to.add_stmt(stmt)
+    }
+
+    fn expr(&mut self, id: ExprId) -> ExprId {
+        let from = self.from;
+        let expr = match &from[id] {
+            Expr::Literal(lit) => Expr::Literal(LiteralExpr::new(lit.value, self.span(lit.span))),
+            Expr::Grouping(group) => Expr::Grouping(GroupingExpr::new(self.expr(group.expression), self.span(group.span))),
+            Expr::Binary(binary) => Expr::Binary(BinaryExpr::new(
+                self.expr(binary.left),
+                binary.operator,
+                self.span(binary.operator_span),
+                self.expr(binary.right),
+                self.span(binary.span),
+            )),
+            Expr::Unary(unary) => Expr::Unary(UnaryExpr::new(
+                unary.operator,
+                self.span(unary.operator_span),
+                self.expr(unary.right),
+                self.span(unary.span),
+            )),
+            Expr::Call(call) => {
+                let callee = self.expr(call.callee);
+                let args = call.args.iter().map(|&arg| self.expr(arg)).collect();
+                Expr::Call(CallExpr::new(callee, self.span(call.paren), args, self.span(call.span)))
+            }
+            Expr::Get(get) => Expr::Get(GetExpr::new(self.expr(get.object), self.ident(get.name), self.span(get.span))),
+        };
+        self.to.add_expr(expr)
+    }
+}
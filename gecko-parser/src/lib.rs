@@ -1,296 +1,270 @@
+pub mod ast;
+pub mod incremental;
 pub mod nodes;
+pub mod visit;
 
-use std::{rc::Rc, vec};
+use ast::{Ast, ExprId, StmtId};
+use nodes::{expr::{Expr, Type, BinaryExpr, BinaryOp, UnaryExpr, UnaryOp, LiteralExpr, GroupingExpr, CallExpr, GetExpr}, stmt::{Stmt, Var, Fn, ExprStmt, Return, If, LangImport, FileImport}, Ident, Param};
 
-use nodes::{expr::{Expr, Type, BinaryExpr, UnaryExpr, LiteralExpr, GroupingExpr, CallExpr, GetExpr}, stmt::{Stmt, Var, Fn}, Param};
-
-use gecko_lexer::{token::Token, ttype::TType};
+use gecko_lexer::{symbol::Symbol, token::Token, ttype::TType};
 use gecko_error::{Error, LineInfo};
 
 pub struct Parser {
     tokens: Vec<Token>,
     current: usize,
-
+    ast: Ast,
 }
 
 impl Parser {
     pub fn new(tokens: Vec<Token>) -> Parser {
-        Parser { tokens, current: 0 }
+        Parser { tokens, current: 0, ast: Ast::new() }
     }
 
-    pub fn parse(&mut self) -> Result<Vec<Stmt>, Error> {
-        let mut stmts = Vec::new();
+    pub fn parse(&mut self) -> Result<Ast, Error> {
         while !self.is_at_end() {
-            stmts.push(self.stmt()?);
+            let stmt = self.stmt()?;
+            self.ast.root.push(stmt);
         }
 
-        Ok(stmts)
-
+        Ok(std::mem::take(&mut self.ast))
     }
 
-    fn stmt(&mut self) -> Result<Stmt, Error> {
-        if self.match_token(vec![TType::LET]) {
-            return self.var_decl();
-        } else if self.match_token(vec![TType::FN]) {
-            return self.fn_decl();
-        } else if self.match_token(vec![TType::RETURN]) {
-            return self.return_stmt();
-        } else if self.match_token(vec![TType::IMPORT]) {
-            return self.import_stmt();
-        }
+    fn stmt(&mut self) -> Result<StmtId, Error> {
+        let stmt = if self.match_token(&[TType::LET]) {
+            self.var_decl()?
+        } else if self.match_token(&[TType::FN]) {
+            self.fn_decl()?
+        } else if self.match_token(&[TType::RETURN]) {
+            self.return_stmt()?
+        } else if self.match_token(&[TType::IF]) {
+            self.if_stmt()?
+        } else if self.match_token(&[TType::IMPORT]) {
+            self.import_stmt()?
+        } else {
+            self.expr_stmt()?
+        };
 
-        self.expr_stmt()
+        Ok(self.ast.add_stmt(stmt))
     }
 
     fn import_stmt(&mut self) -> Result<Stmt, Error> {
-        if let TType::String(path) = self.peek().ttype {
-            self.advance();
-            self.consume(TType::SEMICOLON, "Expect ';' after import path.".to_string())?;
-            Ok(Stmt::FileImport(path))
-        } else if let TType::Identifier(name) = self.peek().ttype {
-            let mut paths = vec![name];
+        let start = self.previous().lineinfo;
+
+        if let TType::String(path) = &self.peek().ttype {
+            let path = self.intern_string(path)?;
             self.advance();
+            self.consume(TType::SEMICOLON, "Expect ';' after import path.")?;
+            Ok(Stmt::FileImport(FileImport::new(path, self.span_from(&start))))
+        } else if let TType::Identifier(_) = self.peek().ttype {
+            let mut paths = vec![self.consume_identifier("Expect import path.")?.name];
 
-            while self.match_token(vec![TType::DOT]) {
-                if let TType::Identifier(name) = self.peek().ttype {
-                    paths.push(name);
-                    self.advance();
-                } else {
-                    let tok = self.peek();
-                    return Err(Error::new_with_notes(
-                        LineInfo::new(tok.lineinfo.line, tok.lineinfo.start, tok.lineinfo.end),
-                        "Expect import path.".to_string(),
-                        vec![],
-                    ));
-                }
+            while self.match_token(&[TType::DOT]) {
+                paths.push(self.consume_identifier("Expect import path.")?.name);
             }
 
-            println!("{:?}", self.peek());
-
+            self.consume(TType::SEMICOLON, "Expect ';' after import path.")?;
 
-            self.consume(TType::SEMICOLON, "Expect ';' after import path.".to_string())?;
-
-            Ok(Stmt::LangImport(paths))
+            Ok(Stmt::LangImport(LangImport::new(paths, self.span_from(&start))))
         } else {
-            let tok = self.peek();
-            return Err(Error::new_with_notes(
-                LineInfo::new(tok.lineinfo.line, tok.lineinfo.start, tok.lineinfo.end),
-                "Expect import path.".to_string(),
-                vec![],
-            ));
+            Err(self.error_at_current("Expect import path."))
         }
     }
 
     fn fn_decl(&mut self) -> Result<Stmt, Error> {
-        if let TType::Identifier(name) = self.peek().ttype {
-            self.advance();
-            self.consume(TType::LPAREN, "Expect '(' after function name.".to_string())?;
-            let mut params: Vec<Param> = Vec::new();
-            if !self.check(TType::RPAREN) {
-                loop {
-                    if params.len() >= 255 {
-                        let tok = self.peek();
-                        return Err(Error::new_with_notes(
-                            LineInfo::new(tok.lineinfo.line, tok.lineinfo.start, tok.lineinfo.end),
-                            "Cannot have more than 255 parameters.".to_string(),
-                            vec![],
-                        ));
-                    }
-
-                    if let TType::Identifier(name) = self.peek().ttype {
-                        self.advance();
-                        self.consume(TType::COLON, "Expect type after param".to_string())?;
-                        let tok = self.advance();
-
-
-                        let param = Param::new(name, tok);
-                        params.push(param);
-                    } else {
-                        let tok = self.peek();
-                        return Err(Error::new_with_notes(
-                            LineInfo::new(tok.lineinfo.line, tok.lineinfo.start, tok.lineinfo.end),
-                            "Expect parameter name.".to_string(),
-                            vec![],
-                        ));
-                    }
-
-                    if !self.match_token(vec![TType::COMMA]) {
-                        break;
-                    }
+        let start = self.previous().lineinfo;
+        let name = self.consume_identifier("Expect function name.")?;
+
+        self.consume(TType::LPAREN, "Expect '(' after function name.")?;
+        let mut params: Vec<Param> = Vec::new();
+        if !self.check(TType::RPAREN) {
+            loop {
+                if params.len() >= 255 {
+                    return Err(self.error_at_current("Cannot have more than 255 parameters."));
                 }
-            }
-            self.consume(TType::RPAREN, "Expect ')' after parameters.".to_string())?;
 
-            let return_value = if self.match_token(vec![TType::ARROW]) {
-                Some(self.advance())
-            } else {
-                None
-            };
+                let name = self.consume_identifier("Expect parameter name.")?;
+                self.consume(TType::COLON, "Expect type after param")?;
+                let type_ = self.type_name("Expect parameter type.")?;
 
-            self.consume(TType::LBRACE, "Expect '{' before function body.".to_string())?;
+                params.push(Param::new(name, type_, self.span_from(&name.span)));
 
-            let body = self.block()?;
-            Ok(Stmt::FnDecl(
-                Fn::new(name, params, body, return_value)
-            ))
-        } else {
-            let tok = self.peek();
-            return Err(Error::new_with_notes(
-                LineInfo::new(tok.lineinfo.line, tok.lineinfo.start, tok.lineinfo.end),
-                "Expect function name.".to_string(),
-                vec![],
-            ));
+                if !self.match_token(&[TType::COMMA]) {
+                    break;
+                }
+            }
         }
+        self.consume(TType::RPAREN, "Expect ')' after parameters.")?;
+
+        let return_type = if self.match_token(&[TType::ARROW]) {
+            Some(self.type_name("Expect return type after '->'.")?)
+        } else {
+            None
+        };
+
+        self.consume(TType::LBRACE, "Expect '{' before function body.")?;
+
+        let body = self.block()?;
+        Ok(Stmt::FnDecl(
+            Fn::new(name, params, body, return_type, self.span_from(&start))
+        ))
     }
 
     fn return_stmt(&mut self) -> Result<Stmt, Error> {
-        let _keyword = self.previous();
+        let start = self.previous().lineinfo;
         let value = if !self.check(TType::SEMICOLON) {
             Some(self.expression()?)
         } else {
             None
         };
 
-        self.consume(TType::SEMICOLON, "Expect ';' after return value.".to_string())?;
+        self.consume(TType::SEMICOLON, "Expect ';' after return value.")?;
+
+        Ok(Stmt::Return(Return::new(value, self.span_from(&start))))
+    }
+
+    fn if_stmt(&mut self) -> Result<Stmt, Error> {
+        let start = self.previous().lineinfo;
+        let condition = self.expression()?;
+
+        self.consume(TType::LBRACE, "Expect '{' after if condition.")?;
+        let then_branch = self.block()?;
+
+        let else_branch = if !self.match_token(&[TType::ELSE]) {
+            None
+        } else if self.match_token(&[TType::IF]) {
+            let nested = self.if_stmt()?;
+            Some(vec![self.ast.add_stmt(nested)])
+        } else {
+            self.consume(TType::LBRACE, "Expect '{' after else.")?;
+            Some(self.block()?)
+        };
 
-        Ok(Stmt::Return(value))
+        Ok(Stmt::If(If::new(condition, then_branch, else_branch, self.span_from(&start))))
     }
 
-    fn block(&mut self) -> Result<Vec<Stmt>, Error> {
+    fn block(&mut self) -> Result<Vec<StmtId>, Error> {
         let mut stmts = Vec::new();
 
         while !self.check(TType::RBRACE) && !self.is_at_end() {
             stmts.push(self.stmt()?);
         }
 
-        self.consume(TType::RBRACE, "Expect '}' after block.".to_string())?;
+        self.consume(TType::RBRACE, "Expect '}' after block.")?;
 
         Ok(stmts)
     }
 
     fn var_decl(&mut self) -> Result<Stmt, Error> {
-        if let TType::Identifier(name) = self.peek().ttype {
-            self.advance();
-            let value = if self.match_token(vec![TType::EQ]) {
-                Some(self.expression()?)
-            } else {
-                None
-            };
-
-            self.consume(TType::SEMICOLON, "Expect ';' after variable declaration.".to_string())?;
-
-            return Ok(Stmt::VarDecl(Var::new(name, value)));
-
+        let start = self.previous().lineinfo;
+        let name = self.consume_identifier("Expect variable name.")?;
 
+        let value = if self.match_token(&[TType::EQ]) {
+            Some(self.expression()?)
         } else {
-            let tok = self.peek();
-            return Err(Error::new_with_notes(
-                LineInfo::new(tok.lineinfo.line, tok.lineinfo.start, tok.lineinfo.end),
-                "Expect variable name.".to_string(),
-                vec![],
-            ));
-        }
+            None
+        };
 
+        self.consume(TType::SEMICOLON, "Expect ';' after variable declaration.")?;
+
+        Ok(Stmt::VarDecl(Var::new(name, value, self.span_from(&start))))
     }
 
     fn expr_stmt(&mut self) -> Result<Stmt, Error> {
         let expr = self.expression()?;
 
-        if !self.match_token(vec![TType::SEMICOLON]) {
+        if !self.match_token(&[TType::SEMICOLON]) {
             let tok = self.previous();
             return Err(Error::new_with_notes(
-                LineInfo::new(tok.lineinfo.line, tok.lineinfo.start, tok.lineinfo.end),
+                tok.lineinfo,
                 "Expect ';' after expression.".to_string(),
                 vec![],
             ));
         }
 
-        Ok(Stmt::ExprStmt(expr))
+        let span = self.ast[expr].span().to(&self.previous().lineinfo);
+        Ok(Stmt::ExprStmt(ExprStmt::new(expr, span)))
     }
 
-    fn expression(&mut self) -> Result<Expr, Error> {
+    fn expression(&mut self) -> Result<ExprId, Error> {
         self.equality()
     }
 
-    fn equality(&mut self) -> Result<Expr, Error> {
+    fn equality(&mut self) -> Result<ExprId, Error> {
         let mut expr = self.comparison()?;
 
-        while self.match_token(vec![TType::EQEQ, TType::BANGEQ]) {
-            let operator = self.previous();
+        while self.match_token(&[TType::EQEQ, TType::BANGEQ]) {
+            let operator = self.previous_binary_op();
             let right = self.comparison()?;
-            expr = Expr::Binary(BinaryExpr::new(Rc::new(expr), operator, Rc::new(right)));
+            expr = self.binary(expr, operator, right);
         }
 
         Ok(expr)
     }
 
-    #[allow(unused_mut)]
-    fn comparison(&mut self) -> Result<Expr, Error> {
+    fn comparison(&mut self) -> Result<ExprId, Error> {
         let mut expr = self.term()?;
 
-        //while self.match_token(vec![TType::G, TType::GREATEREQ, TType::LESS, TType::LESSEQ]) {
-        //    let operator = self.previous();
-        //    let right = self.term()?;
-        //    expr = Expr::Binary(Rc::new(expr), operator, Rc::new(right));
-        //}
+        while self.match_token(&[TType::LT, TType::LTEQ, TType::GT, TType::GTEQ]) {
+            let operator = self.previous_binary_op();
+            let right = self.term()?;
+            expr = self.binary(expr, operator, right);
+        }
 
         Ok(expr)
     }
 
-    fn term(&mut self) -> Result<Expr, Error> {
-        let expr = self.factor()?;
-        while self.match_token(vec![TType::MINUS, TType::PLUS]) {
-            let operator = self.previous();
-            let right = self.term()?;
+    fn term(&mut self) -> Result<ExprId, Error> {
+        let mut expr = self.factor()?;
+        while self.match_token(&[TType::MINUS, TType::PLUS]) {
+            let operator = self.previous_binary_op();
+            let right = self.factor()?;
 
-            return Ok(Expr::Binary(
-                BinaryExpr::new(Rc::new(expr), operator, Rc::new(right)),
-            ));
+            expr = self.binary(expr, operator, right);
         }
 
         Ok(expr)
     }
 
-    fn factor(&mut self) -> Result<Expr, Error> {
-        let expr = self.unary()?;
-        while self.match_token(vec![TType::SLASH, TType::STAR]) {
-            let operator = self.previous();
-            let right = self.factor()?;
+    fn factor(&mut self) -> Result<ExprId, Error> {
+        let mut expr = self.unary()?;
+        while self.match_token(&[TType::SLASH, TType::STAR]) {
+            let operator = self.previous_binary_op();
+            let right = self.unary()?;
 
-            return Ok(Expr::Binary(
-                BinaryExpr::new(Rc::new(expr), operator, Rc::new(right)),
-            ));
+            expr = self.binary(expr, operator, right);
         }
 
         Ok(expr)
     }
 
-    fn unary(&mut self) -> Result<Expr, Error> {
-        if self.match_token(vec![TType::BANG, TType::MINUS]) {
-            let operator = self.previous();
+    fn unary(&mut self) -> Result<ExprId, Error> {
+        if self.match_token(&[TType::BANG, TType::MINUS]) {
+            let tok = self.previous();
+            let (operator, operator_span) = (UnaryOp::from_ttype(&tok.ttype).unwrap(), tok.lineinfo);
             let right = self.unary()?;
 
-            return Ok(Expr::Unary(
-                UnaryExpr::new(operator, Rc::new(right)),
-            ));
+            let span = operator_span.to(self.ast[right].span());
+            return Ok(self.ast.add_expr(Expr::Unary(
+                UnaryExpr::new(operator, operator_span, right, span),
+            )));
         }
 
         self.call()
     }
 
-    fn call(&mut self) -> Result<Expr, Error> {
+    fn call(&mut self) -> Result<ExprId, Error> {
         let mut expr = self.primary()?;
 
         loop {
-            if self.match_token(vec![TType::LPAREN]) {
+            if self.match_token(&[TType::LPAREN]) {
                 expr = self.finish_call(expr)?;
-            } else if self.match_token(vec![TType::DOT]) {
-                println!("{:?}", self.peek());
-                let name = self.consume_any_identifier("Expect property name after '.'.".to_string())?;
+            } else if self.match_token(&[TType::DOT]) {
+                let name = self.consume_identifier("Expect property name after '.'.")?;
 
-                expr = Expr::Get(
-                    GetExpr::new(Rc::new(expr), name),
-                );
+                let span = self.ast[expr].span().to(&name.span);
+                expr = self.ast.add_expr(Expr::Get(
+                    GetExpr::new(expr, name, span),
+                ));
             }
             else {
                 break;
@@ -300,121 +274,127 @@ impl Parser {
         Ok(expr)
     }
 
-    fn finish_call(&mut self, expr: Expr) -> Result<Expr, Error> {
+    fn finish_call(&mut self, callee: ExprId) -> Result<ExprId, Error> {
         let mut args = Vec::new();
 
         if !self.check(TType::RPAREN) {
             loop {
                 if args.len() >= 255 {
-                    let tok = self.peek();
-                    return Err(Error::new_with_notes(
-                        LineInfo::new(tok.lineinfo.line, tok.lineinfo.start, tok.lineinfo.end),
-                        "Cannot have more than 255 arguments.".to_string(),
-                        vec![],
-                    ));
+                    return Err(self.error_at_current("Cannot have more than 255 arguments."));
                 }
 
-                args.push(Rc::new(self.expression()?));
+                args.push(self.expression()?);
 
-                if !self.match_token(vec![TType::COMMA]) {
+                if !self.match_token(&[TType::COMMA]) {
                     break;
                 }
             }
         }
 
-        let paren = self.consume(TType::RPAREN, "Expect ')' after arguments.".to_string())?;
+        let paren = self.consume(TType::RPAREN, "Expect ')' after arguments.")?;
 
-        Ok(Expr::Call(
-            CallExpr::new(Rc::new(expr), paren, args),
-        ))
+        let span = self.ast[callee].span().to(&paren);
+        Ok(self.ast.add_expr(Expr::Call(
+            CallExpr::new(callee, paren, args, span),
+        )))
     }
 
-    fn primary(&mut self) -> Result<Expr, Error> {
-        if self.match_token(vec![TType::FALSE]) {
-            return Ok(Expr::Literal(
-                LiteralExpr::new(Type::Bool(false)),
-            ))
-        } else if self.match_token(vec![TType::TRUE]) {
-            return Ok(Expr::Literal(
-                LiteralExpr::new(Type::Bool(true)),
-            ))
-        } else if self.match_token(vec![TType::LPAREN]) {
+    fn primary(&mut self) -> Result<ExprId, Error> {
+        if self.match_token(&[TType::FALSE]) {
+            return Ok(self.literal(Type::Bool(false)));
+        } else if self.match_token(&[TType::TRUE]) {
+            return Ok(self.literal(Type::Bool(true)));
+        } else if self.match_token(&[TType::LPAREN]) {
+            let start = self.previous().lineinfo;
             let expr = self.expression()?;
 
-            if !self.match_token(vec![TType::RPAREN]) {
+            if !self.match_token(&[TType::RPAREN]) {
                 let tok = self.previous();
                 return Err(Error::new_with_notes(
-                    LineInfo::new(tok.lineinfo.line, tok.lineinfo.start, tok.lineinfo.end),
+                    tok.lineinfo,
                     "Expect ')' after expression.".to_string(),
                     vec![],
                 ));
             } else {
-                return Ok(Expr::Grouping(
-                    GroupingExpr::new(Rc::new(expr)),
-                ));
+                let span = self.span_from(&start);
+                return Ok(self.ast.add_expr(Expr::Grouping(
+                    GroupingExpr::new(expr, span),
+                )));
             }
         }
 
-        match self.peek().ttype {
-            TType::Number(num) => {
-                self.advance();
-                Ok(Expr::Literal(
-                    LiteralExpr::new(Type::Float(num)),
+        let value = match &self.peek().ttype {
+            TType::Number(num) => Type::Float(*num),
+            TType::String(string) => Type::String(self.intern_string(string)?),
+            TType::Identifier(name) => Type::Iden(*name),
+            _ => return Err(self.error_at_current("Expect expression.")),
+        };
 
-                ))
-            }
-            TType::String(string) => {
-                self.advance();
-                Ok(Expr::Literal(
-                    LiteralExpr::new(Type::String(string)),
-                ))
-            }
-            TType::Identifier(name) => {
-                self.advance();
-                Ok(Expr::Literal(
-                    LiteralExpr::new(Type::Iden(name)),
-                ))
-            }
-            _ => {
-                let tok = self.peek();
-                Err(Error::new_with_notes(
-                    LineInfo::new(tok.lineinfo.line, tok.lineinfo.start, tok.lineinfo.end),
-                    "Expect expression.".to_string(),
-                    vec![],
-                ))
-            }
-        }
+        self.advance();
+        Ok(self.literal(value))
+    }
+
+    /// A literal spanning the token just consumed.
+    fn literal(&mut self, value: Type) -> ExprId {
+        let span = self.previous().lineinfo;
+        self.ast.add_expr(Expr::Literal(LiteralExpr::new(value, span)))
+    }
+
+    fn binary(&mut self, left: ExprId, (operator, operator_span): (BinaryOp, LineInfo), right: ExprId) -> ExprId {
+        let span = self.ast[left].span().to(self.ast[right].span());
+        self.ast.add_expr(Expr::Binary(BinaryExpr::new(left, operator, operator_span, right, span)))
+    }
+
+    fn previous_binary_op(&self) -> (BinaryOp, LineInfo) {
+        let tok = self.previous();
+        (BinaryOp::from_ttype(&tok.ttype).unwrap(), tok.lineinfo)
+    }
+
+    /// The span from `start` to the end of the last consumed token.
+    fn span_from(&self, start: &LineInfo) -> LineInfo {
+        start.to(&self.previous().lineinfo)
     }
 
-    fn consume(&mut self, ttype: TType, message: String) -> Result<Token, Error> {
+    /// Interns the contents of the string literal at the current token.
+    fn intern_string(&self, string: &str) -> Result<Symbol, Error> {
+        Symbol::try_intern(string)
+            .map_err(|err| self.error_at_current(&format!("Cannot intern this string: {}", err)))
+    }
+
+    fn error_at_current(&self, message: &str) -> Error {
+        Error::new_with_notes(self.peek().lineinfo, message.to_string(), vec![])
+    }
+
+    /// Consumes a token of type `ttype`, returning its span.
+    fn consume(&mut self, ttype: TType, message: &str) -> Result<LineInfo, Error> {
         if self.check(ttype) {
-            Ok(self.advance())
+            Ok(self.advance().lineinfo)
         } else {
-            let tok = self.peek();
-            Err(Error::new_with_notes(
-                LineInfo::new(tok.lineinfo.line, tok.lineinfo.start, tok.lineinfo.end),
-                message,
-                vec![],
-            ))
+            Err(self.error_at_current(message))
         }
     }
 
-    fn consume_any_identifier(&mut self, message: String) -> Result<Token, Error> {
-        if let TType::Identifier(_) = self.peek().ttype {
-            Ok(self.advance())
+    fn consume_identifier(&mut self, message: &str) -> Result<Ident, Error> {
+        if let TType::Identifier(name) = self.peek().ttype {
+            Ok(Ident::new(name, self.advance().lineinfo))
         } else {
-            let tok = self.peek();
-            Err(Error::new_with_notes(
-                LineInfo::new(tok.lineinfo.line, tok.lineinfo.start, tok.lineinfo.end),
-                message,
-                vec![],
-            ))
+            Err(self.error_at_current(message))
         }
     }
 
-    fn match_token(&mut self, ttypes: Vec<TType>) -> bool {
+    /// A type annotation: `number`, `string` or a type name.
+    fn type_name(&mut self, message: &str) -> Result<Ident, Error> {
+        let name = match self.peek().ttype {
+            TType::Identifier(name) => name,
+            TType::NUMBER | TType::STRING => Symbol::intern(&self.peek().lexeme),
+            _ => return Err(self.error_at_current(message)),
+        };
+        Ok(Ident::new(name, self.advance().lineinfo))
+    }
+
+    fn match_token(&mut self, ttypes: &[TType]) -> bool {
         for ttype in ttypes {
-            if self.check(ttype) {
+            if self.check_ref(ttype) {
                 self.advance();
                 return true;
             }
@@ -424,14 +404,18 @@ impl Parser {
     }
 
     fn check(&self, ttype: TType) -> bool {
+        self.check_ref(&ttype)
+    }
+
+    fn check_ref(&self, ttype: &TType) -> bool {
         if self.is_at_end() {
             return false;
         }
 
-        self.peek().ttype == ttype
+        &self.peek().ttype == ttype
     }
 
-    fn advance(&mut self) -> Token {
+    fn advance(&mut self) -> &Token {
         if !self.is_at_end() {
             self.current += 1;
         }
@@ -443,12 +427,11 @@ impl Parser {
         self.peek().ttype == TType::EOF
     }
 
-    fn peek(&self) -> Token {
-        self.tokens[self.current].clone()
+    fn peek(&self) -> &Token {
+        &self.tokens[self.current]
     }
 
-
-    fn previous(&self) -> Token {
-        self.tokens[self.current - 1].clone()
+    fn previous(&self) -> &Token {
+        &self.tokens[self.current - 1]
     }
 }
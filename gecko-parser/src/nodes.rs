@@ -1,16 +1,32 @@
-use gecko_lexer::token::Token;
+use gecko_error::LineInfo;
+use gecko_lexer::symbol::Symbol;
 
 pub mod expr;
 pub mod stmt;
 
+/// A name as written in the source: variables, functions, parameters,
+/// properties and type annotations.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ident {
+    pub name: Symbol,
+    pub span: LineInfo,
+}
+
+impl Ident {
+    pub fn new(name: Symbol, span: LineInfo) -> Ident {
+        Ident { name, span }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Param {
-    pub name: String,
-    pub type_: Token,
+    pub name: Ident,
+    pub type_: Ident,
+    pub span: LineInfo,
 }
 
 impl Param {
-    pub fn new(name: String, type_: Token) -> Param {
-        Param { name, type_ }
+    pub fn new(name: Ident, type_: Ident, span: LineInfo) -> Param {
+        Param { name, type_, span }
     }
 }
@@ -1,109 +1,212 @@
-use std::rc::Rc;
+use gecko_error::LineInfo;
+use gecko_lexer::{symbol::Symbol, ttype::TType};
 
-use gecko_lexer::token::Token;
+use crate::ast::ExprId;
 
-#[derive(Debug, Clone, PartialEq)]
+use super::Ident;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Type {
     Int(i64),
     Float(f64),
-    String(String),
+    String(Symbol),
     Bool(bool),
-    Iden(String),
+    Iden(Symbol),
     Void,
     Unknown,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Eq,
+    NotEq,
+    Less,
+    LessEq,
+    Greater,
+    GreaterEq,
+}
+
+impl BinaryOp {
+    pub fn from_ttype(ttype: &TType) -> Option<BinaryOp> {
+        match ttype {
+            TType::PLUS => Some(BinaryOp::Add),
+            TType::MINUS => Some(BinaryOp::Sub),
+            TType::STAR => Some(BinaryOp::Mul),
+            TType::SLASH => Some(BinaryOp::Div),
+            TType::EQEQ => Some(BinaryOp::Eq),
+            TType::BANGEQ => Some(BinaryOp::NotEq),
+            TType::LT => Some(BinaryOp::Less),
+            TType::LTEQ => Some(BinaryOp::LessEq),
+            TType::GT => Some(BinaryOp::Greater),
+            TType::GTEQ => Some(BinaryOp::GreaterEq),
+            _ => None,
+        }
+    }
+
+    /// The token the operator was parsed from.
+    pub fn ttype(self) -> TType {
+        match self {
+            BinaryOp::Add => TType::PLUS,
+            BinaryOp::Sub => TType::MINUS,
+            BinaryOp::Mul => TType::STAR,
+            BinaryOp::Div => TType::SLASH,
+            BinaryOp::Eq => TType::EQEQ,
+            BinaryOp::NotEq => TType::BANGEQ,
+            BinaryOp::Less => TType::LT,
+            BinaryOp::LessEq => TType::LTEQ,
+            BinaryOp::Greater => TType::GT,
+            BinaryOp::GreaterEq => TType::GTEQ,
+        }
+    }
+
+    pub fn lexeme(self) -> &'static str {
+        match self {
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::Mul => "*",
+            BinaryOp::Div => "/",
+            BinaryOp::Eq => "==",
+            BinaryOp::NotEq => "!=",
+            BinaryOp::Less => "<",
+            BinaryOp::LessEq => "<=",
+            BinaryOp::Greater => ">",
+            BinaryOp::GreaterEq => ">=",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnaryOp {
+    Neg,
+    Not,
+}
+
+impl UnaryOp {
+    pub fn from_ttype(ttype: &TType) -> Option<UnaryOp> {
+        match ttype {
+            TType::MINUS => Some(UnaryOp::Neg),
+            TType::BANG => Some(UnaryOp::Not),
+            _ => None,
+        }
+    }
+
+    /// The token the operator was parsed from.
+    pub fn ttype(self) -> TType {
+        match self {
+            UnaryOp::Neg => TType::MINUS,
+            UnaryOp::Not => TType::BANG,
+        }
+    }
+
+    pub fn lexeme(self) -> &'static str {
+        match self {
+            UnaryOp::Neg => "-",
+            UnaryOp::Not => "!",
+        }
+    }
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct LiteralExpr {
     pub value: Type,
+    pub span: LineInfo,
 }
 
 impl LiteralExpr {
-    pub fn new(value: Type) -> Self {
-        Self { value }
+    pub fn new(value: Type, span: LineInfo) -> Self {
+        Self { value, span }
     }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct GroupingExpr {
-    pub expression: Rc<Expr>,
+    pub expression: ExprId,
+    pub span: LineInfo,
 }
 
 impl GroupingExpr {
-    pub fn new(expression: Rc<Expr>) -> Self {
-        Self { expression }
+    pub fn new(expression: ExprId, span: LineInfo) -> Self {
+        Self { expression, span }
     }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct BinaryExpr {
-    pub left: Rc<Expr>,
-    pub operator: Token,
-    pub right: Rc<Expr>,
+    pub left: ExprId,
+    pub operator: BinaryOp,
+    pub operator_span: LineInfo,
+    pub right: ExprId,
+    pub span: LineInfo,
 }
 
 impl BinaryExpr {
-    pub fn new(left: Rc<Expr>, operator: Token, right: Rc<Expr>) -> Self {
+    pub fn new(left: ExprId, operator: BinaryOp, operator_span: LineInfo, right: ExprId, span: LineInfo) -> Self {
         Self {
             left,
             operator,
+            operator_span,
             right,
+            span,
         }
     }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct UnaryExpr {
-    pub operator: Token,
-    pub right: Rc<Expr>,
+    pub operator: UnaryOp,
+    pub operator_span: LineInfo,
+    pub right: ExprId,
+    pub span: LineInfo,
 }
 
 impl UnaryExpr {
-    pub fn new(operator: Token, right: Rc<Expr>) -> Self {
-        Self { operator, right }
+    pub fn new(operator: UnaryOp, operator_span: LineInfo, right: ExprId, span: LineInfo) -> Self {
+        Self {
+            operator,
+            operator_span,
+            right,
+            span,
+        }
     }
 }
 
-/*
-Ok(Expr::Call(
-            CallExpr::new(Rc::new(expr), paren, args),
-        ))
- */
-
 #[derive(Debug, Clone, PartialEq)]
 pub struct CallExpr {
-    pub callee: Rc<Expr>,
-    pub paren: Token,
-    pub args: Vec<Rc<Expr>>,
+    pub callee: ExprId,
+    /// Span of the closing `)`.
+    pub paren: LineInfo,
+    pub args: Vec<ExprId>,
+    pub span: LineInfo,
 }
 
 impl CallExpr {
-    pub fn new(callee: Rc<Expr>, paren: Token, args: Vec<Rc<Expr>>) -> Self {
+    pub fn new(callee: ExprId, paren: LineInfo, args: Vec<ExprId>, span: LineInfo) -> Self {
         Self {
             callee,
             paren,
             args,
+            span,
         }
     }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct GetExpr {
-    pub object: Rc<Expr>,
-    pub name: Token,
+    pub object: ExprId,
+    pub name: Ident,
+    pub span: LineInfo,
 }
 
 impl GetExpr {
-    pub fn new(object: Rc<Expr>, name: Token) -> Self {
-        Self { object, name }
+    pub fn new(object: ExprId, name: Ident, span: LineInfo) -> Self {
+        Self { object, name, span }
     }
 }
 
-// Expr::Get(
-//    GetExpr::new(Rc::new(expr), name),
-// );
-
 #[derive(Debug, Clone, PartialEq)]
 pub enum Expr {
     Literal(LiteralExpr),
@@ -113,3 +216,17 @@ pub enum Expr {
     Call(CallExpr),
     Get(GetExpr)
 }
+
+impl Expr {
+    /// The source text the expression was parsed from.
+    pub fn span(&self) -> &LineInfo {
+        match self {
+            Expr::Literal(lit) => &lit.span,
+            Expr::Grouping(group) => &group.span,
+            Expr::Binary(binary) => &binary.span,
+            Expr::Unary(unary) => &unary.span,
+            Expr::Call(call) => &call.span,
+            Expr::Get(get) => &get.span,
+        }
+    }
+}
@@ -1,48 +1,140 @@
-use gecko_lexer::token::Token;
+use gecko_error::LineInfo;
+use gecko_lexer::symbol::Symbol;
 
-use super::{expr::Expr, Param};
+use crate::ast::{ExprId, StmtId};
+
+use super::{Ident, Param};
+
+#[derive(Clone, Debug)]
+pub struct ExprStmt {
+    pub expr: ExprId,
+    pub span: LineInfo,
+}
+
+impl ExprStmt {
+    pub fn new(expr: ExprId, span: LineInfo) -> ExprStmt {
+        ExprStmt { expr, span }
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct Var {
-    pub name: String,
-    pub initializer: Option<Expr>
+    pub name: Ident,
+    pub initializer: Option<ExprId>,
+    pub span: LineInfo,
 }
 
 impl Var {
-    pub fn new(name: String, initializer: Option<Expr>) -> Var {
+    pub fn new(name: Ident, initializer: Option<ExprId>, span: LineInfo) -> Var {
         Var {
             name,
-            initializer
+            initializer,
+            span,
         }
     }
 }
 
 #[derive(Clone, Debug)]
 pub struct Fn {
-    pub name: String,
+    pub name: Ident,
     pub params: Vec<Param>,
-    pub body: Vec<Stmt>,
-    pub return_type: Option<Token>,
+    pub body: Vec<StmtId>,
+    pub return_type: Option<Ident>,
+    pub span: LineInfo,
 }
 
 impl Fn {
-    pub fn new(name: String, params: Vec<Param>, body: Vec<Stmt>, return_type: Option<Token>) -> Fn {
+    pub fn new(name: Ident, params: Vec<Param>, body: Vec<StmtId>, return_type: Option<Ident>, span: LineInfo) -> Fn {
         Fn {
             name,
             params,
             body,
             return_type,
+            span,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Return {
+    pub value: Option<ExprId>,
+    pub span: LineInfo,
+}
+
+impl Return {
+    pub fn new(value: Option<ExprId>, span: LineInfo) -> Return {
+        Return { value, span }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct If {
+    pub condition: ExprId,
+    pub then_branch: Vec<StmtId>,
+    /// The `else` block. An `else if` is a block holding just the nested
+    /// `if`.
+    pub else_branch: Option<Vec<StmtId>>,
+    pub span: LineInfo,
+}
+
+impl If {
+    pub fn new(condition: ExprId, then_branch: Vec<StmtId>, else_branch: Option<Vec<StmtId>>, span: LineInfo) -> If {
+        If {
+            condition,
+            then_branch,
+            else_branch,
+            span,
         }
     }
 }
 
+#[derive(Clone, Debug)]
+pub struct LangImport {
+    pub path: Vec<Symbol>,
+    pub span: LineInfo,
+}
+
+impl LangImport {
+    pub fn new(path: Vec<Symbol>, span: LineInfo) -> LangImport {
+        LangImport { path, span }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct FileImport {
+    pub path: Symbol,
+    pub span: LineInfo,
+}
+
+impl FileImport {
+    pub fn new(path: Symbol, span: LineInfo) -> FileImport {
+        FileImport { path, span }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum Stmt {
-    ExprStmt(Expr),
+    ExprStmt(ExprStmt),
     VarDecl(Var),
-    /// name, params, body
     FnDecl(Fn),
-    Return(Option<Expr>),
-    LangImport(Vec<String>),
-    FileImport(String),
+    Return(Return),
+    If(If),
+    LangImport(LangImport),
+    FileImport(FileImport),
+}
+
+impl Stmt {
+    /// The source text the statement was parsed from, including its
+    /// terminating `;` or closing `}`.
+    pub fn span(&self) -> &LineInfo {
+        match self {
+            Stmt::ExprStmt(stmt) => &stmt.span,
+            Stmt::VarDecl(var) => &var.span,
+            Stmt::FnDecl(func) => &func.span,
+            Stmt::Return(ret) => &ret.span,
+            Stmt::If(stmt) => &stmt.span,
+            Stmt::LangImport(import) => &import.span,
+            Stmt::FileImport(import) => &import.span,
+        }
+    }
 }
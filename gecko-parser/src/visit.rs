@@ -0,0 +1,372 @@
+//! Traversal of the AST.
+//!
+//! [`Visitor`] walks the tree by reference and [`VisitorMut`] with mutable
+//! access to the [`Ast`]. Every method has a default that visits the node's
+//! children through the matching `walk_*` (or `walk_*_mut`) function, so a
+//! pass only overrides the nodes it cares about and calls `walk_*` itself
+//! when it still wants to descend. Literals and imports have no children,
+//! so their methods default to doing nothing. Methods receive the node's ID
+//! so passes can key side tables by it.
+
+use crate::{
+    ast::{Ast, ExprId, StmtId},
+    nodes::{
+        expr::{BinaryExpr, CallExpr, Expr, GetExpr, GroupingExpr, LiteralExpr, UnaryExpr},
+        stmt::{ExprStmt, FileImport, Fn, If, LangImport, Return, Stmt, Var},
+        Param,
+    },
+};
+
+pub trait Visitor {
+    fn visit_stmt(&mut self, ast: &Ast, id: StmtId) {
+        walk_stmt(self, ast, id);
+    }
+
+    fn visit_expr_stmt(&mut self, ast: &Ast, _id: StmtId, stmt: &ExprStmt) {
+        walk_expr_stmt(self, ast, stmt);
+    }
+
+    fn visit_var(&mut self, ast: &Ast, _id: StmtId, var: &Var) {
+        walk_var(self, ast, var);
+    }
+
+    fn visit_fn(&mut self, ast: &Ast, _id: StmtId, func: &Fn) {
+        walk_fn(self, ast, func);
+    }
+
+    fn visit_param(&mut self, ast: &Ast, param: &Param) {
+        walk_param(self, ast, param);
+    }
+
+    fn visit_return(&mut self, ast: &Ast, _id: StmtId, ret: &Return) {
+        walk_return(self, ast, ret);
+    }
+
+    fn visit_if(&mut self, ast: &Ast, _id: StmtId, stmt: &If) {
+        walk_if(self, ast, stmt);
+    }
+
+    fn visit_lang_import(&mut self, _ast: &Ast, _id: StmtId, _import: &LangImport) {}
+
+    fn visit_file_import(&mut self, _ast: &Ast, _id: StmtId, _import: &FileImport) {}
+
+    fn visit_expr(&mut self, ast: &Ast, id: ExprId) {
+        walk_expr(self, ast, id);
+    }
+
+    fn visit_literal(&mut self, _ast: &Ast, _id: ExprId, _literal: &LiteralExpr) {}
+
+    fn visit_grouping(&mut self, ast: &Ast, _id: ExprId, grouping: &GroupingExpr) {
+        walk_grouping(self, ast, grouping);
+    }
+
+    fn visit_binary(&mut self, ast: &Ast, _id: ExprId, binary: &BinaryExpr) {
+        walk_binary(self, ast, binary);
+    }
+
+    fn visit_unary(&mut self, ast: &Ast, _id: ExprId, unary: &UnaryExpr) {
+        walk_unary(self, ast, unary);
+    }
+
+    fn visit_call(&mut self, ast: &Ast, _id: ExprId, call: &CallExpr) {
+        walk_call(self, ast, call);
+    }
+
+    fn visit_get(&mut self, ast: &Ast, _id: ExprId, get: &GetExpr) {
+        walk_get(self, ast, get);
+    }
+}
+
+/// Visits every top-level statement of `ast`.
+pub fn walk_ast<V: Visitor + ?Sized>(visitor: &mut V, ast: &Ast) {
+    walk_stmts(visitor, ast, &ast.root);
+}
+
+pub fn walk_stmts<V: Visitor + ?Sized>(visitor: &mut V, ast: &Ast, stmts: &[StmtId]) {
+    for &stmt in stmts {
+        visitor.visit_stmt(ast, stmt);
+    }
+}
+
+pub fn walk_stmt<V: Visitor + ?Sized>(visitor: &mut V, ast: &Ast, id: StmtId) {
+    match &ast[id] {
+        Stmt::ExprStmt(stmt) => visitor.visit_expr_stmt(ast, id, stmt),
+        Stmt::VarDecl(var) => visitor.visit_var(ast, id, var),
+        Stmt::FnDecl(func) => visitor.visit_fn(ast, id, func),
+        Stmt::Return(ret) => visitor.visit_return(ast, id, ret),
+        Stmt::If(stmt) => visitor.visit_if(ast, id, stmt),
+        Stmt::LangImport(import) => visitor.visit_lang_import(ast, id, import),
+        Stmt::FileImport(import) => visitor.visit_file_import(ast, id, import),
+    }
+}
+
+pub fn walk_expr_stmt<V: Visitor + ?Sized>(visitor: &mut V, ast: &Ast, stmt: &ExprStmt) {
+    visitor.visit_expr(ast, stmt.expr);
+}
+
+pub fn walk_var<V: Visitor + ?Sized>(visitor: &mut V, ast: &Ast, var: &Var) {
+    if let Some(initializer) = var.initializer {
+        visitor.visit_expr(ast, initializer);
+    }
+}
+
+pub fn walk_fn<V: Visitor + ?Sized>(visitor: &mut V, ast: &Ast, func: &Fn) {
+    for param in &func.params {
+        visitor.visit_param(ast, param);
+    }
+    walk_stmts(visitor, ast, &func.body);
+}
+
+/// A parameter's name and type are plain identifiers, so there is nothing
+/// below it to visit.
+pub fn walk_param<V: Visitor + ?Sized>(_visitor: &mut V, _ast: &Ast, _param: &Param) {}
+
+pub fn walk_return<V: Visitor + ?Sized>(visitor: &mut V, ast: &Ast, ret: &Return) {
+    if let Some(value) = ret.value {
+        visitor.visit_expr(ast, value);
+    }
+}
+
+pub fn walk_if<V: Visitor + ?Sized>(visitor: &mut V, ast: &Ast, stmt: &If) {
+    visitor.visit_expr(ast, stmt.condition);
+    walk_stmts(visitor, ast, &stmt.then_branch);
+    if let Some(else_branch) = &stmt.else_branch {
+        walk_stmts(visitor, ast, else_branch);
+    }
+}
+
+pub fn walk_expr<V: Visitor + ?Sized>(visitor: &mut V, ast: &Ast, id: ExprId) {
+    match &ast[id] {
+        Expr::Literal(literal) => visitor.visit_literal(ast, id, literal),
+        Expr::Grouping(grouping) => visitor.visit_grouping(ast, id, grouping),
+        Expr::Binary(binary) => visitor.visit_binary(ast, id, binary),
+        Expr::Unary(unary) => visitor.visit_unary(ast, id, unary),
+        Expr::Call(call) => visitor.visit_call(ast, id, call),
+        Expr::Get(get) => visitor.visit_get(ast, id, get),
+    }
+}
+
+pub fn walk_grouping<V: Visitor + ?Sized>(visitor: &mut V, ast: &Ast, grouping: &GroupingExpr) {
+    visitor.visit_expr(ast, grouping.expression);
+}
+
+pub fn walk_binary<V: Visitor + ?Sized>(visitor: &mut V, ast: &Ast, binary: &BinaryExpr) {
+    visitor.visit_expr(ast, binary.left);
+    visitor.visit_expr(ast, binary.right);
+}
+
+pub fn walk_unary<V: Visitor + ?Sized>(visitor: &mut V, ast: &Ast, unary: &UnaryExpr) {
+    visitor.visit_expr(ast, unary.right);
+}
+
+pub fn walk_call<V: Visitor + ?Sized>(visitor: &mut V, ast: &Ast, call: &CallExpr) {
+    visitor.visit_expr(ast, call.callee);
+    for &arg in &call.args {
+        visitor.visit_expr(ast, arg);
+    }
+}
+
+pub fn walk_get<V: Visitor + ?Sized>(visitor: &mut V, ast: &Ast, get: &GetExpr) {
+    visitor.visit_expr(ast, get.object);
+}
+
+/// Like [`Visitor`], but with mutable access to the whole [`Ast`], so a pass
+/// can rewrite nodes in place (`ast[id] = ...`) or add new ones. Nodes are
+/// reached by ID; a pass reads the node itself from `ast`.
+pub trait VisitorMut {
+    fn visit_stmt_mut(&mut self, ast: &mut Ast, id: StmtId) {
+        walk_stmt_mut(self, ast, id);
+    }
+
+    fn visit_expr_stmt_mut(&mut self, ast: &mut Ast, id: StmtId) {
+        walk_expr_stmt_mut(self, ast, id);
+    }
+
+    fn visit_var_mut(&mut self, ast: &mut Ast, id: StmtId) {
+        walk_var_mut(self, ast, id);
+    }
+
+    fn visit_fn_mut(&mut self, ast: &mut Ast, id: StmtId) {
+        walk_fn_mut(self, ast, id);
+    }
+
+    /// Visits parameter `index` of the function declared by `func`.
+    fn visit_param_mut(&mut self, _ast: &mut Ast, _func: StmtId, _index: usize) {}
+
+    fn visit_return_mut(&mut self, ast: &mut Ast, id: StmtId) {
+        walk_return_mut(self, ast, id);
+    }
+
+    fn visit_if_mut(&mut self, ast: &mut Ast, id: StmtId) {
+        walk_if_mut(self, ast, id);
+    }
+
+    fn visit_lang_import_mut(&mut self, _ast: &mut Ast, _id: StmtId) {}
+
+    fn visit_file_import_mut(&mut self, _ast: &mut Ast, _id: StmtId) {}
+
+    fn visit_expr_mut(&mut self, ast: &mut Ast, id: ExprId) {
+        walk_expr_mut(self, ast, id);
+    }
+
+    fn visit_literal_mut(&mut self, _ast: &mut Ast, _id: ExprId) {}
+
+    fn visit_grouping_mut(&mut self, ast: &mut Ast, id: ExprId) {
+        walk_grouping_mut(self, ast, id);
+    }
+
+    fn visit_binary_mut(&mut self, ast: &mut Ast, id: ExprId) {
+        walk_binary_mut(self, ast, id);
+    }
+
+    fn visit_unary_mut(&mut self, ast: &mut Ast, id: ExprId) {
+        walk_unary_mut(self, ast, id);
+    }
+
+    fn visit_call_mut(&mut self, ast: &mut Ast, id: ExprId) {
+        walk_call_mut(self, ast, id);
+    }
+
+    fn visit_get_mut(&mut self, ast: &mut Ast, id: ExprId) {
+        walk_get_mut(self, ast, id);
+    }
+}
+
+/// Visits every top-level statement of `ast`.
+pub fn walk_ast_mut<V: VisitorMut + ?Sized>(visitor: &mut V, ast: &mut Ast) {
+    for stmt in ast.root.clone() {
+        visitor.visit_stmt_mut(ast, stmt);
+    }
+}
+
+pub fn walk_stmt_mut<V: VisitorMut + ?Sized>(visitor: &mut V, ast: &mut Ast, id: StmtId) {
+    match &ast[id] {
+        Stmt::ExprStmt(_) => visitor.visit_expr_stmt_mut(ast, id),
+        Stmt::VarDecl(_) => visitor.visit_var_mut(ast, id),
+        Stmt::FnDecl(_) => visitor.visit_fn_mut(ast, id),
+        Stmt::Return(_) => visitor.visit_return_mut(ast, id),
+        Stmt::If(_) => visitor.visit_if_mut(ast, id),
+        Stmt::LangImport(_) => visitor.visit_lang_import_mut(ast, id),
+        Stmt::FileImport(_) => visitor.visit_file_import_mut(ast, id),
+    }
+}
+
+/// Visits the expressions, parameters and nested statements of `id`,
+/// whatever kind of statement it is.
+pub fn walk_stmt_children_mut<V: VisitorMut + ?Sized>(visitor: &mut V, ast: &mut Ast, id: StmtId) {
+    match &ast[id] {
+        Stmt::ExprStmt(_) => walk_expr_stmt_mut(visitor, ast, id),
+        Stmt::VarDecl(_) => walk_var_mut(visitor, ast, id),
+        Stmt::FnDecl(_) => walk_fn_mut(visitor, ast, id),
+        Stmt::Return(_) => walk_return_mut(visitor, ast, id),
+        Stmt::If(_) => walk_if_mut(visitor, ast, id),
+        Stmt::LangImport(_) | Stmt::FileImport(_) => {}
+    }
+}
+
+pub fn walk_expr_stmt_mut<V: VisitorMut + ?Sized>(visitor: &mut V, ast: &mut Ast, id: StmtId) {
+    if let Stmt::ExprStmt(stmt) = &ast[id] {
+        let expr = stmt.expr;
+        visitor.visit_expr_mut(ast, expr);
+    }
+}
+
+pub fn walk_var_mut<V: VisitorMut + ?Sized>(visitor: &mut V, ast: &mut Ast, id: StmtId) {
+    if let Stmt::VarDecl(Var { initializer: Some(expr), .. }) = &ast[id] {
+        let expr = *expr;
+        visitor.visit_expr_mut(ast, expr);
+    }
+}
+
+pub fn walk_fn_mut<V: VisitorMut + ?Sized>(visitor: &mut V, ast: &mut Ast, id: StmtId) {
+    if let Stmt::FnDecl(func) = &ast[id] {
+        let (params, body) = (func.params.len(), func.body.clone());
+        for index in 0..params {
+            visitor.visit_param_mut(ast, id, index);
+        }
+        for stmt in body {
+            visitor.visit_stmt_mut(ast, stmt);
+        }
+    }
+}
+
+pub fn walk_return_mut<V: VisitorMut + ?Sized>(visitor: &mut V, ast: &mut Ast, id: StmtId) {
+    if let Stmt::Return(Return { value: Some(expr), .. }) = &ast[id] {
+        let expr = *expr;
+        visitor.visit_expr_mut(ast, expr);
+    }
+}
+
+pub fn walk_if_mut<V: VisitorMut + ?Sized>(visitor: &mut V, ast: &mut Ast, id: StmtId) {
+    if let Stmt::If(stmt) = &ast[id] {
+        let condition = stmt.condition;
+        let branches = stmt.then_branch.iter().chain(stmt.else_branch.iter().flatten()).copied().collect::<Vec<_>>();
+        visitor.visit_expr_mut(ast, condition);
+        for stmt in branches {
+            visitor.visit_stmt_mut(ast, stmt);
+        }
+    }
+}
+
+pub fn walk_expr_mut<V: VisitorMut + ?Sized>(visitor: &mut V, ast: &mut Ast, id: ExprId) {
+    match &ast[id] {
+        Expr::Literal(_) => visitor.visit_literal_mut(ast, id),
+        Expr::Grouping(_) => visitor.visit_grouping_mut(ast, id),
+        Expr::Binary(_) => visitor.visit_binary_mut(ast, id),
+        Expr::Unary(_) => visitor.visit_unary_mut(ast, id),
+        Expr::Call(_) => visitor.visit_call_mut(ast, id),
+        Expr::Get(_) => visitor.visit_get_mut(ast, id),
+    }
+}
+
+/// Visits the operands of `id`, in source order, whatever kind of
+/// expression it is.
+pub fn walk_expr_children_mut<V: VisitorMut + ?Sized>(visitor: &mut V, ast: &mut Ast, id: ExprId) {
+    match &ast[id] {
+        Expr::Literal(_) => {}
+        Expr::Grouping(_) => walk_grouping_mut(visitor, ast, id),
+        Expr::Binary(_) => walk_binary_mut(visitor, ast, id),
+        Expr::Unary(_) => walk_unary_mut(visitor, ast, id),
+        Expr::Call(_) => walk_call_mut(visitor, ast, id),
+        Expr::Get(_) => walk_get_mut(visitor, ast, id),
+    }
+}
+
+pub fn walk_grouping_mut<V: VisitorMut + ?Sized>(visitor: &mut V, ast: &mut Ast, id: ExprId) {
+    if let Expr::Grouping(grouping) = &ast[id] {
+        let expression = grouping.expression;
+        visitor.visit_expr_mut(ast, expression);
+    }
+}
+
+pub fn walk_binary_mut<V: VisitorMut + ?Sized>(visitor: &mut V, ast: &mut Ast, id: ExprId) {
+    if let Expr::Binary(binary) = &ast[id] {
+        let (left, right) = (binary.left, binary.right);
+        visitor.visit_expr_mut(ast, left);
+        visitor.visit_expr_mut(ast, right);
+    }
+}
+
+pub fn walk_unary_mut<V: VisitorMut + ?Sized>(visitor: &mut V, ast: &mut Ast, id: ExprId) {
+    if let Expr::Unary(unary) = &ast[id] {
+        let right = unary.right;
+        visitor.visit_expr_mut(ast, right);
+    }
+}
+
+pub fn walk_call_mut<V: VisitorMut + ?Sized>(visitor: &mut V, ast: &mut Ast, id: ExprId) {
+    if let Expr::Call(call) = &ast[id] {
+        let (callee, args) = (call.callee, call.args.clone());
+        visitor.visit_expr_mut(ast, callee);
+        for arg in args {
+            visitor.visit_expr_mut(ast, arg);
+        }
+    }
+}
+
+pub fn walk_get_mut<V: VisitorMut + ?Sized>(visitor: &mut V, ast: &mut Ast, id: ExprId) {
+    if let Expr::Get(get) = &ast[id] {
+        let object = get.object;
+        visitor.visit_expr_mut(ast, object);
+    }
+}
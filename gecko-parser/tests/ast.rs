@@ -0,0 +1,80 @@
+use gecko_lexer::Lexer;
+use gecko_parser::{
+    ast::{Ast, ExprId},
+    nodes::{
+        expr::{BinaryOp, Expr, Type},
+        stmt::Stmt,
+    },
+    visit::{walk_ast, walk_expr, Visitor},
+    Parser,
+};
+
+fn parse(source: &str) -> Ast {
+    let tokens = Lexer::new(source.to_string()).scan_tokens().unwrap();
+    Parser::new(tokens).parse().unwrap()
+}
+
+#[derive(Default)]
+struct Exprs(Vec<ExprId>);
+
+impl Visitor for Exprs {
+    fn visit_expr(&mut self, ast: &Ast, id: ExprId) {
+        self.0.push(id);
+        walk_expr(self, ast, id);
+    }
+}
+
+#[test]
+fn every_expression_has_a_distinct_id() {
+    let ast = parse("let x = 1 + 2 * 3;\nfn f(a: number) -> number { return a; }\nf(x);");
+
+    let mut exprs = Exprs::default();
+    walk_ast(&mut exprs, &ast);
+
+    // Side tables can be plain vectors indexed by ID.
+    let mut seen = vec![false; ast.expr_count()];
+    for id in &exprs.0 {
+        assert!(!seen[id.index()], "{:?} visited twice", id);
+        seen[id.index()] = true;
+    }
+    assert!(seen.iter().all(|&s| s));
+    assert_eq!(ast.root.len(), 3);
+}
+
+#[test]
+fn repeated_names_share_a_symbol() {
+    let ast = parse("x + x;");
+
+    let mut exprs = Exprs::default();
+    walk_ast(&mut exprs, &ast);
+
+    let symbols: Vec<_> = exprs
+        .0
+        .iter()
+        .filter_map(|&id| match ast[id] {
+            Expr::Literal(ref lit) => match lit.value {
+                Type::Iden(name) => Some(name),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(symbols.len(), 2);
+    assert_eq!(symbols[0], symbols[1]);
+    assert_eq!((symbols[0]).as_str(), "x");
+}
+
+#[test]
+fn else_if_nests_an_if_in_the_else_branch() {
+    let ast = parse("if a < 1 { f(); } else if a >= 2 { g(); } else { h(); }");
+    let Stmt::If(outer) = &ast[ast.root[0]] else { panic!("expected an if") };
+    assert_eq!(outer.then_branch.len(), 1);
+
+    let else_branch = outer.else_branch.as_ref().unwrap();
+    let Stmt::If(inner) = &ast[else_branch[0]] else { panic!("expected a nested if") };
+    assert_eq!(inner.else_branch.as_ref().map(Vec::len), Some(1));
+
+    let Expr::Binary(condition) = &ast[inner.condition] else { panic!("expected a comparison") };
+    assert_eq!(condition.operator, BinaryOp::GreaterEq);
+}
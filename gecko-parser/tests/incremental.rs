@@ -0,0 +1,204 @@
+use gecko_error::Error;
+use gecko_lexer::Lexer;
+use gecko_parser::{
+    ast::{Ast, ExprId, StmtId},
+    incremental::{reparse, Edit},
+    visit::{walk_ast, walk_expr, walk_stmt, Visitor},
+    Parser,
+};
+
+fn parse(source: &str) -> Result<Ast, Error> {
+    let tokens = Lexer::new(source.to_string()).scan_tokens()?;
+    Parser::new(tokens).parse()
+}
+
+/// Every node in traversal order, with the arena IDs left out so trees built
+/// in a different order compare equal.
+#[derive(Default)]
+struct Dump(Vec<String>);
+
+impl Dump {
+    fn push(&mut self, node: String) {
+        let mut out = String::new();
+        let mut rest = node.as_str();
+        while let Some(at) = rest.find("Id(") {
+            out.push_str(&rest[..at]);
+            rest = rest[at + 3..].trim_start_matches(|c: char| c.is_ascii_digit());
+            out.push_str("Id(_");
+        }
+        out.push_str(rest);
+        self.0.push(out);
+    }
+}
+
+impl Visitor for Dump {
+    fn visit_stmt(&mut self, ast: &Ast, id: StmtId) {
+        self.push(format!("{:?}", ast[id]));
+        walk_stmt(self, ast, id);
+    }
+
+    fn visit_expr(&mut self, ast: &Ast, id: ExprId) {
+        self.push(format!("{:?}", ast[id]));
+        walk_expr(self, ast, id);
+    }
+}
+
+fn dump(ast: &Ast) -> Vec<String> {
+    let mut dump = Dump::default();
+    walk_ast(&mut dump, ast);
+    dump.0
+}
+
+const SOURCE: &str = r#"import std.io;
+
+fn add(a: number, b: number) -> number {
+    return a + b;
+}
+
+let x = add(1, 2); let y = x * 2;
+
+fn main() -> number {
+    if x < 3 {
+        io.println("x is", x);
+    } else if y >= x {
+        return 1;
+    }
+    return 0;
+}
+"#;
+
+/// Applies `edit` to `source` and checks the incremental result against a
+/// fresh parse of the edited text, returning the edited text.
+fn check(source: &str, edit: Edit) -> String {
+    let previous = parse(source).unwrap();
+    let edited = edit.apply(source);
+
+    match (reparse(&previous, &edited, &edit), parse(&edited)) {
+        (Ok(incremental), Ok(fresh)) => {
+            assert_eq!(dump(&incremental), dump(&fresh), "after {:?}:\n{}", edit, edited);
+            assert_eq!(incremental.root.len(), fresh.root.len());
+        }
+        (Err(incremental), Err(fresh)) => {
+            assert_eq!(incremental.message, fresh.message, "after {:?}:\n{}", edit, edited);
+            assert_eq!(format!("{:?}", incremental.line_info), format!("{:?}", fresh.line_info));
+        }
+        (incremental, fresh) => panic!(
+            "after {:?}:\n{}\nincremental: {:?}\nfresh: {:?}",
+            edit,
+            edited,
+            incremental.map(|_| ()).map_err(|e| e.message),
+            fresh.map(|_| ()).map_err(|e| e.message),
+        ),
+    }
+
+    edited
+}
+
+fn at(source: &str, needle: &str) -> usize {
+    source.find(needle).unwrap_or_else(|| panic!("{:?} not in source", needle))
+}
+
+#[test]
+fn edits_inside_one_statement() {
+    let body = at(SOURCE, "a + b");
+    check(SOURCE, Edit::new(body..body + 5, "(a - b) * 2"));
+    check(SOURCE, Edit::new(body..body + 1, "first"));
+
+    let call = at(SOURCE, "add(1, 2)");
+    check(SOURCE, Edit::new(call + 4..call + 5, "100"));
+}
+
+#[test]
+fn edits_that_add_or_remove_statements() {
+    let main = at(SOURCE, "fn main");
+    check(SOURCE, Edit::new(main..main, "let z = 3;\n\n"));
+    check(SOURCE, Edit::new(0..at(SOURCE, "fn add"), ""));
+    check(SOURCE, Edit::new(SOURCE.len()..SOURCE.len(), "main();\n"));
+
+    let y = at(SOURCE, " let y");
+    check(SOURCE, Edit::new(y..at(SOURCE, "\n\nfn main"), ""));
+}
+
+#[test]
+fn edits_between_statements_shift_the_rest() {
+    let gap = at(SOURCE, "\n\nlet x");
+    check(SOURCE, Edit::new(gap..gap, "\n\n\n// a comment\n"));
+    check(SOURCE, Edit::new(gap..gap + 1, ""));
+}
+
+#[test]
+fn edits_that_run_into_the_next_statement() {
+    // A comment that swallows the statement after it on the same line.
+    let y = at(SOURCE, "let y");
+    check(SOURCE, Edit::new(y..y, "// "));
+
+    // Removing a `;` joins two statements into one (or a parse error).
+    let semicolon = at(SOURCE, "2); let") + 2;
+    check(SOURCE, Edit::new(semicolon..semicolon + 1, ""));
+
+    // An identifier that grows into the keyword after it.
+    check("a;b;", Edit::new(1..2, ""));
+
+    // An unterminated string reaches the end of the file.
+    let gap = at(SOURCE, "\n\nlet x");
+    check(SOURCE, Edit::new(gap..gap, "\"open"));
+}
+
+#[test]
+fn edits_that_continue_the_statement_before() {
+    check("if true {}\n", Edit::new(11..11, "else {}"));
+    check("if true {} else {}\nlet a = 1;", Edit::new(18..18, " if false {}"));
+}
+
+#[test]
+fn typing_a_statement_one_character_at_a_time() {
+    let mut source = SOURCE.to_string();
+    // The tree for `source`, or `None` if it doesn't parse; an editor then
+    // parses the next version from scratch.
+    let mut ast = Some(parse(&source).unwrap());
+
+    for (i, c) in "let typed = x + 1;".chars().enumerate() {
+        let offset = at(SOURCE, "\nfn main") + i;
+        let edit = Edit::new(offset..offset, c.to_string());
+        let edited = edit.apply(&source);
+
+        let next = match &ast {
+            Some(ast) => reparse(ast, &edited, &edit),
+            None => parse(&edited),
+        };
+        ast = match (next, parse(&edited)) {
+            (Ok(next), Ok(fresh)) => {
+                assert_eq!(dump(&next), dump(&fresh), "{}", edited);
+                Some(next)
+            }
+            (Err(next), Err(fresh)) => {
+                assert_eq!(next.message, fresh.message, "{}", edited);
+                None
+            }
+            (next, fresh) => panic!("{}\nincremental ok: {}, fresh ok: {}", edited, next.is_ok(), fresh.is_ok()),
+        };
+        source = edited;
+    }
+
+    assert!(ast.is_some());
+}
+
+
+#[test]
+fn random_edits_match_a_fresh_parse() {
+    let snippets = ["", " ", "\n", ";", "{", "}", "(", ")", "\"", "//", "x", "1", "fn", "let q = 1;", "return 2;", "if", "else", "<", "é"];
+    let mut state = 0x9E37_79B9_7F4A_7C15u64;
+    let mut next = move |n: usize| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state % n as u64) as usize
+    };
+
+    for _ in 0..500 {
+        let start = next(SOURCE.len() + 1);
+        let end = (start + next(12)).min(SOURCE.len());
+        let text = snippets[next(snippets.len())];
+        check(SOURCE, Edit::new(start..end, text));
+    }
+}
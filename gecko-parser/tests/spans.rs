@@ -0,0 +1,121 @@
+use gecko_error::LineInfo;
+use gecko_lexer::Lexer;
+use gecko_parser::{
+    ast::{Ast, ExprId, StmtId},
+    nodes::{
+        stmt::{ExprStmt, FileImport, Fn, LangImport, Return, Var},
+        Param,
+    },
+    visit::{walk_ast, walk_expr, walk_fn, walk_var, Visitor},
+    Parser,
+};
+
+/// Records the source text under every node's span.
+struct Spans<'a> {
+    source: &'a str,
+    texts: Vec<&'a str>,
+}
+
+impl<'a> Spans<'a> {
+    fn push(&mut self, span: &LineInfo) {
+        self.texts.push(&self.source[span.start..span.end]);
+    }
+}
+
+impl Visitor for Spans<'_> {
+    fn visit_expr_stmt(&mut self, ast: &Ast, _id: StmtId, stmt: &ExprStmt) {
+        self.push(&stmt.span);
+        self.visit_expr(ast, stmt.expr);
+    }
+
+    fn visit_var(&mut self, ast: &Ast, _id: StmtId, var: &Var) {
+        self.push(&var.span);
+        self.push(&var.name.span);
+        walk_var(self, ast, var);
+    }
+
+    fn visit_fn(&mut self, ast: &Ast, _id: StmtId, func: &Fn) {
+        self.push(&func.span);
+        self.push(&func.name.span);
+        walk_fn(self, ast, func);
+        if let Some(return_type) = &func.return_type {
+            self.push(&return_type.span);
+        }
+    }
+
+    fn visit_param(&mut self, _ast: &Ast, param: &Param) {
+        self.push(&param.span);
+        self.push(&param.type_.span);
+    }
+
+    fn visit_return(&mut self, ast: &Ast, _id: StmtId, ret: &Return) {
+        self.push(&ret.span);
+        if let Some(value) = ret.value {
+            self.visit_expr(ast, value);
+        }
+    }
+
+    fn visit_lang_import(&mut self, _ast: &Ast, _id: StmtId, import: &LangImport) {
+        self.push(&import.span);
+    }
+
+    fn visit_file_import(&mut self, _ast: &Ast, _id: StmtId, import: &FileImport) {
+        self.push(&import.span);
+    }
+
+    fn visit_expr(&mut self, ast: &Ast, id: ExprId) {
+        self.push(ast[id].span());
+        walk_expr(self, ast, id);
+    }
+}
+
+fn spans(source: &str) -> Vec<&str> {
+    let tokens = Lexer::new(source.to_string()).scan_tokens().unwrap();
+    let ast = Parser::new(tokens).parse().unwrap();
+
+    let mut spans = Spans { source, texts: vec![] };
+    walk_ast(&mut spans, &ast);
+    spans.texts
+}
+
+#[test]
+fn statements_span_their_full_source() {
+    assert_eq!(spans("import std.io;"), ["import std.io;"]);
+    assert_eq!(spans("import \"lib.gk\";"), ["import \"lib.gk\";"]);
+    assert_eq!(spans("let x;"), ["let x;", "x"]);
+    assert_eq!(spans("let x = 1;"), ["let x = 1;", "x", "1"]);
+    assert_eq!(
+        spans("fn f(a: number, b: string) -> number {\n    return a;\n}"),
+        [
+            "fn f(a: number, b: string) -> number {\n    return a;\n}",
+            "f",
+            "a: number",
+            "number",
+            "b: string",
+            "string",
+            "return a;",
+            "a",
+            "number",
+        ]
+    );
+}
+
+#[test]
+fn expressions_span_their_full_source() {
+    assert_eq!(
+        spans("-(1 + 2) * x;"),
+        ["-(1 + 2) * x;", "-(1 + 2) * x", "-(1 + 2)", "(1 + 2)", "1 + 2", "1", "2", "x"]
+    );
+    assert_eq!(
+        spans("io.println(\"hi\", !true);"),
+        [
+            "io.println(\"hi\", !true);",
+            "io.println(\"hi\", !true)",
+            "io.println",
+            "io",
+            "\"hi\"",
+            "!true",
+            "true",
+        ]
+    );
+}
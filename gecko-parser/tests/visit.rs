@@ -0,0 +1,173 @@
+use gecko_lexer::{symbol::Symbol, Lexer};
+use gecko_parser::{
+    ast::{Ast, ExprId, StmtId},
+    nodes::{
+        expr::{Expr, GetExpr, GroupingExpr, LiteralExpr, Type, UnaryExpr},
+        stmt::{ExprStmt, FileImport, LangImport, Return},
+        Param,
+    },
+    visit::{
+        walk_ast, walk_ast_mut, walk_expr, walk_expr_mut, walk_expr_stmt, walk_get, walk_grouping, walk_param,
+        walk_return, walk_unary, walk_unary_mut, Visitor, VisitorMut,
+    },
+    Parser,
+};
+
+fn parse(source: &str) -> Ast {
+    let tokens = Lexer::new(source.to_string()).scan_tokens().unwrap();
+    Parser::new(tokens).parse().unwrap()
+}
+
+const SOURCE: &str = r#"
+import std.io;
+import "other.gk";
+
+fn add(a: number, b: number) -> number {
+    return (a + b) * -a;
+}
+
+fn main() -> number {
+    let x = add(1, 2);
+    io.println(x);
+    return x;
+}
+"#;
+
+#[derive(Default)]
+struct Counter {
+    idents: Vec<String>,
+    ids: Vec<ExprId>,
+    params: usize,
+    literals: usize,
+    imports: usize,
+}
+
+impl Visitor for Counter {
+    fn visit_literal(&mut self, _ast: &Ast, id: ExprId, literal: &LiteralExpr) {
+        match literal.value {
+            Type::Iden(name) => {
+                self.idents.push(name.as_str().to_string());
+                self.ids.push(id);
+            }
+            _ => self.literals += 1,
+        }
+    }
+
+    fn visit_param(&mut self, _ast: &Ast, _param: &Param) {
+        self.params += 1;
+    }
+
+    fn visit_lang_import(&mut self, _ast: &Ast, _id: StmtId, _import: &LangImport) {
+        self.imports += 1;
+    }
+
+    fn visit_file_import(&mut self, _ast: &Ast, _id: StmtId, _import: &FileImport) {
+        self.imports += 1;
+    }
+}
+
+fn idents(ast: &Ast) -> Vec<String> {
+    let mut counter = Counter::default();
+    walk_ast(&mut counter, ast);
+    counter.idents
+}
+
+#[test]
+fn visitor_reaches_every_node() {
+    let ast = parse(SOURCE);
+    let mut counter = Counter::default();
+    walk_ast(&mut counter, &ast);
+
+    assert_eq!(counter.idents, ["a", "b", "a", "add", "io", "x", "x"]);
+    assert_eq!(counter.params, 2);
+    assert_eq!(counter.literals, 2);
+    assert_eq!(counter.imports, 2);
+
+    for id in counter.ids {
+        assert!(matches!(ast[id], Expr::Literal(_)));
+    }
+}
+
+struct Rename;
+
+impl VisitorMut for Rename {
+    fn visit_literal_mut(&mut self, ast: &mut Ast, id: ExprId) {
+        let x = Symbol::intern("x");
+        let y = Symbol::intern("y");
+        if let Expr::Literal(literal) = &mut ast[id] {
+            if literal.value == Type::Iden(x) {
+                literal.value = Type::Iden(y);
+            }
+        }
+    }
+}
+
+#[test]
+fn visitor_mut_rewrites_in_place() {
+    let mut ast = parse(SOURCE);
+    let before = ast.clone();
+    walk_ast_mut(&mut Rename, &mut ast);
+
+    assert_eq!(idents(&ast), ["a", "b", "a", "add", "io", "y", "y"]);
+    assert_eq!(idents(&before), ["a", "b", "a", "add", "io", "x", "x"]);
+}
+
+/// Counts expressions in `visit_expr` while overriding the nodes above them
+/// and descending with the matching `walk_*`.
+#[derive(Default)]
+struct Exprs(usize);
+
+impl Visitor for Exprs {
+    fn visit_expr(&mut self, ast: &Ast, id: ExprId) {
+        self.0 += 1;
+        walk_expr(self, ast, id);
+    }
+
+    fn visit_expr_stmt(&mut self, ast: &Ast, _id: StmtId, stmt: &ExprStmt) {
+        walk_expr_stmt(self, ast, stmt);
+    }
+
+    fn visit_param(&mut self, ast: &Ast, param: &Param) {
+        walk_param(self, ast, param);
+    }
+
+    fn visit_return(&mut self, ast: &Ast, _id: StmtId, ret: &Return) {
+        walk_return(self, ast, ret);
+    }
+
+    fn visit_grouping(&mut self, ast: &Ast, _id: ExprId, grouping: &GroupingExpr) {
+        walk_grouping(self, ast, grouping);
+    }
+
+    fn visit_unary(&mut self, ast: &Ast, _id: ExprId, unary: &UnaryExpr) {
+        walk_unary(self, ast, unary);
+    }
+
+    fn visit_get(&mut self, ast: &Ast, _id: ExprId, get: &GetExpr) {
+        walk_get(self, ast, get);
+    }
+}
+
+impl VisitorMut for Exprs {
+    fn visit_expr_mut(&mut self, ast: &mut Ast, id: ExprId) {
+        self.0 += 1;
+        walk_expr_mut(self, ast, id);
+    }
+
+    fn visit_unary_mut(&mut self, ast: &mut Ast, id: ExprId) {
+        walk_unary_mut(self, ast, id);
+    }
+}
+
+#[test]
+fn walk_functions_descend_through_visit_expr() {
+    let mut ast = parse(SOURCE);
+
+    let mut exprs = Exprs::default();
+    walk_ast(&mut exprs, &ast);
+    assert_eq!(exprs.0, ast.expr_count());
+
+    let mut exprs = Exprs::default();
+    walk_ast_mut(&mut exprs, &mut ast);
+    assert_eq!(exprs.0, ast.expr_count());
+}
@@ -0,0 +1,251 @@
+//! Name resolution: binds every identifier use to the declaration it refers
+//! to and reports undefined and duplicate names.
+//!
+//! Scopes follow the interpreter. A function body can use any name declared
+//! in an enclosing scope, even after the function itself, because names are
+//! looked up when the function is called. Other statements only see names
+//! declared before them. Each function body and each branch of an `if` is
+//! a scope of its own.
+//!
+//! `import "file.gk";` runs the file in the global scope. What it declares
+//! is passed in with [`resolve_with_imports`]; the resolver doesn't read
+//! files itself.
+
+use std::collections::{HashMap, HashSet};
+
+use gecko_error::{Error, LineInfo, Note};
+use gecko_lexer::symbol::Symbol;
+use gecko_parser::{
+    ast::{Ast, ExprId, StmtId},
+    nodes::{
+        expr::{LiteralExpr, Type},
+        stmt::{FileImport, Fn, If, LangImport, Stmt, Var},
+    },
+    visit::{walk_stmts, walk_var, Visitor},
+};
+
+/// What a name refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Decl {
+    /// Parameter `index` of the function declared by the statement.
+    Param { func: StmtId, index: usize },
+    Var(StmtId),
+    Fn(StmtId),
+    /// The module alias introduced by `import std.io;` (`io`).
+    Module(StmtId),
+}
+
+impl Decl {
+    /// The span of the declared name.
+    pub fn span(self, ast: &Ast) -> LineInfo {
+        match (self, &ast[self.stmt()]) {
+            (Decl::Param { index, .. }, Stmt::FnDecl(func)) => func.params[index].name.span,
+            (_, Stmt::VarDecl(var)) => var.name.span,
+            (_, Stmt::FnDecl(func)) => func.name.span,
+            (_, stmt) => *stmt.span(),
+        }
+    }
+
+    /// The declared name.
+    pub fn name(self, ast: &Ast) -> Symbol {
+        match (self, &ast[self.stmt()]) {
+            (Decl::Param { index, .. }, Stmt::FnDecl(func)) => func.params[index].name.name,
+            (_, Stmt::VarDecl(var)) => var.name.name,
+            (_, Stmt::FnDecl(func)) => func.name.name,
+            (_, Stmt::LangImport(import)) => *import.path.last().unwrap(),
+            (_, stmt) => unreachable!("{:?} declares no name", stmt),
+        }
+    }
+
+    /// The statement that declares the name.
+    pub fn stmt(self) -> StmtId {
+        match self {
+            Decl::Param { func, .. } => func,
+            Decl::Var(id) | Decl::Fn(id) | Decl::Module(id) => id,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Resolution {
+    bindings: HashMap<ExprId, Decl>,
+}
+
+impl Resolution {
+    /// The declaration an identifier expression refers to.
+    pub fn get(&self, id: ExprId) -> Option<Decl> {
+        self.bindings.get(&id).copied()
+    }
+
+    /// Every resolved identifier use and its declaration.
+    pub fn bindings(&self) -> impl Iterator<Item = (ExprId, Decl)> + '_ {
+        self.bindings.iter().map(|(&id, &decl)| (id, decl))
+    }
+}
+
+/// The global names each `import "file.gk";` declares, keyed by the path
+/// as written in the import.
+pub type Imports = HashMap<Symbol, Vec<Symbol>>;
+
+/// Resolves every name in `ast`. Names that can't be resolved are left
+/// out of the [`Resolution`] and reported in the returned errors.
+pub fn resolve(ast: &Ast) -> (Resolution, Vec<Error>) {
+    resolve_with_imports(ast, &Imports::new())
+}
+
+/// Like [`resolve`], but uses of the names `imports` lists for a file are
+/// allowed once the file is imported. They are left out of the
+/// [`Resolution`], as they aren't declared in `ast`. After an import that
+/// `imports` doesn't cover, undefined names are only warnings.
+pub fn resolve_with_imports(ast: &Ast, imports: &Imports) -> (Resolution, Vec<Error>) {
+    let mut resolver = Resolver {
+        scopes: vec![],
+        resolution: Resolution::default(),
+        errors: vec![],
+        imports,
+        imported: HashSet::new(),
+        unknown_imports: vec![],
+    };
+    resolver.block(ast, &ast.root, |_| {});
+
+    // Function bodies are resolved after their scope, so sort the errors
+    // back into source order.
+    resolver.errors.sort_by_key(|err| err.line_info.start);
+    (resolver.resolution, resolver.errors)
+}
+
+/// The names a file declares in the global scope when it runs: its
+/// top-level lets, functions and module aliases.
+pub fn top_level_names(ast: &Ast) -> Vec<Symbol> {
+    ast.root
+        .iter()
+        .filter_map(|&id| match &ast[id] {
+            Stmt::VarDecl(var) => Some(var.name.name),
+            Stmt::FnDecl(func) => Some(func.name.name),
+            Stmt::LangImport(import) => import.path.last().copied(),
+            _ => None,
+        })
+        .collect()
+}
+
+#[derive(Default)]
+struct Scope {
+    names: HashMap<Symbol, Decl>,
+    /// Functions declared in this scope, resolved once the rest of the
+    /// scope has been declared.
+    functions: Vec<StmtId>,
+}
+
+struct Resolver<'a> {
+    scopes: Vec<Scope>,
+    resolution: Resolution,
+    errors: Vec<Error>,
+    imports: &'a Imports,
+    /// Names declared by the file imports that have run so far.
+    imported: HashSet<Symbol>,
+    /// Paths of the file imports that have run but aren't in `imports`.
+    unknown_imports: Vec<Symbol>,
+}
+
+impl Resolver<'_> {
+    /// Resolves `stmts` in a new scope, after `declare` has added any names
+    /// that belong to it (a function's parameters).
+    fn block(&mut self, ast: &Ast, stmts: &[StmtId], declare: impl FnOnce(&mut Self)) {
+        self.scopes.push(Scope::default());
+        declare(self);
+
+        walk_stmts(self, ast, stmts);
+
+        let functions = std::mem::take(&mut self.scopes.last_mut().unwrap().functions);
+        for id in functions {
+            if let Stmt::FnDecl(func) = &ast[id] {
+                self.function(ast, id, func);
+            }
+        }
+
+        self.scopes.pop();
+    }
+
+    fn function(&mut self, ast: &Ast, id: StmtId, func: &Fn) {
+        self.block(ast, &func.body, |resolver| {
+            for (index, param) in func.params.iter().enumerate() {
+                resolver.declare(ast, param.name.name, param.name.span, Decl::Param { func: id, index });
+            }
+        });
+    }
+
+    fn declare(&mut self, ast: &Ast, name: Symbol, span: LineInfo, decl: Decl) {
+        let scope = self.scopes.last_mut().unwrap();
+
+        if let Some(&previous) = scope.names.get(&name) {
+            let line = previous.span(ast).line;
+            self.errors.push(Error::new_with_notes(
+                span,
+                format!("'{}' is already declared in this scope", name),
+                vec![Note::Note(format!("'{}' was first declared on line {}", name, line))],
+            ));
+            return;
+        }
+
+        scope.names.insert(name, decl);
+    }
+
+    fn lookup(&self, name: Symbol) -> Option<Decl> {
+        self.scopes.iter().rev().find_map(|scope| scope.names.get(&name).copied())
+    }
+}
+
+impl Visitor for Resolver<'_> {
+    fn visit_var(&mut self, ast: &Ast, id: StmtId, var: &Var) {
+        walk_var(self, ast, var);
+        self.declare(ast, var.name.name, var.name.span, Decl::Var(id));
+    }
+
+    fn visit_fn(&mut self, ast: &Ast, id: StmtId, func: &Fn) {
+        self.declare(ast, func.name.name, func.name.span, Decl::Fn(id));
+        self.scopes.last_mut().unwrap().functions.push(id);
+    }
+
+    fn visit_if(&mut self, ast: &Ast, _id: StmtId, stmt: &If) {
+        self.visit_expr(ast, stmt.condition);
+        self.block(ast, &stmt.then_branch, |_| {});
+        if let Some(else_branch) = &stmt.else_branch {
+            self.block(ast, else_branch, |_| {});
+        }
+    }
+
+    fn visit_lang_import(&mut self, ast: &Ast, id: StmtId, import: &LangImport) {
+        if let Some(&alias) = import.path.last() {
+            self.declare(ast, alias, import.span, Decl::Module(id));
+        }
+    }
+
+    fn visit_file_import(&mut self, _ast: &Ast, _id: StmtId, import: &FileImport) {
+        match self.imports.get(&import.path) {
+            Some(names) => self.imported.extend(names),
+            None => self.unknown_imports.push(import.path),
+        }
+    }
+
+    fn visit_literal(&mut self, _ast: &Ast, id: ExprId, literal: &LiteralExpr) {
+        let Type::Iden(name) = literal.value else {
+            return;
+        };
+
+        match self.lookup(name) {
+            Some(decl) => {
+                self.resolution.bindings.insert(id, decl);
+            }
+            None if self.imported.contains(&name) => {}
+            None if !self.unknown_imports.is_empty() => {
+                let files: Vec<_> = self.unknown_imports.iter().map(|path| format!("\"{}\"", path)).collect();
+                self.errors.push(Error::warning(
+                    literal.span,
+                    format!("Undefined name '{}'", name),
+                    vec![Note::Note(format!("it may be declared by {}, which couldn't be checked", files.join(", ")))],
+                ));
+            }
+            None => self.errors.push(Error::new(literal.span, format!("Undefined name '{}'", name))),
+        }
+    }
+}
@@ -0,0 +1,221 @@
+use gecko_lexer::{symbol::Symbol, Lexer};
+use gecko_parser::{
+    ast::{Ast, ExprId},
+    nodes::{
+        expr::{LiteralExpr, Type},
+        stmt::Stmt,
+    },
+    visit::{walk_ast, Visitor},
+    Parser,
+};
+use gecko_resolver::{resolve, resolve_with_imports, top_level_names, Decl, Imports};
+
+fn parse(source: &str) -> Ast {
+    let tokens = Lexer::new(source.to_string()).scan_tokens().unwrap();
+    Parser::new(tokens).parse().unwrap()
+}
+
+/// The identifier uses in `ast`, in traversal order.
+#[derive(Default)]
+struct Uses(Vec<(ExprId, &'static str)>);
+
+impl Visitor for Uses {
+    fn visit_literal(&mut self, _ast: &Ast, id: ExprId, literal: &LiteralExpr) {
+        if let Type::Iden(name) = literal.value {
+            self.0.push((id, name.as_str()));
+        }
+    }
+}
+
+fn uses(ast: &Ast) -> Vec<(ExprId, &'static str)> {
+    let mut uses = Uses::default();
+    walk_ast(&mut uses, ast);
+    uses.0
+}
+
+/// Each identifier use with the source text of the declaration it binds to.
+fn bindings(source: &str) -> Vec<(&'static str, String)> {
+    let ast = parse(source);
+    let (resolution, errors) = resolve(&ast);
+    assert!(errors.is_empty(), "{:?}", errors.iter().map(|e| &e.message).collect::<Vec<_>>());
+
+    uses(&ast)
+        .into_iter()
+        .map(|(id, name)| {
+            let decl = resolution.get(id).unwrap_or_else(|| panic!("'{}' is unresolved", name));
+            let span = decl.span(&ast);
+            let kind = match decl {
+                Decl::Param { .. } => "param",
+                Decl::Var(_) => "let",
+                Decl::Fn(_) => "fn",
+                Decl::Module(_) => "module",
+            };
+            (name, format!("{} {}@{}", kind, &source[span.start..span.end], span.line))
+        })
+        .collect()
+}
+
+fn errors(source: &str) -> Vec<(String, usize)> {
+    let ast = parse(source);
+    resolve(&ast).1.into_iter().map(|err| (err.message, err.line_info.line)).collect()
+}
+
+#[test]
+fn uses_bind_to_their_declarations() {
+    let source = "import std.io;
+let x = 1;
+fn add(a: number, b: number) -> number {
+    let x = a;
+    return x + b;
+}
+io.println(add(x, 2));
+";
+
+    assert_eq!(
+        bindings(source),
+        [
+            ("a", String::from("param a@3")),
+            ("x", String::from("let x@4")),
+            ("b", String::from("param b@3")),
+            ("io", String::from("module import std.io;@1")),
+            ("add", String::from("fn add@3")),
+            ("x", String::from("let x@2")),
+        ]
+    );
+}
+
+#[test]
+fn function_bodies_see_later_declarations() {
+    let source = "fn main() {
+    helper(limit);
+}
+fn helper(n: number) {
+    return helper(n - 1);
+}
+let limit = 3;
+";
+
+    let names: Vec<_> = bindings(source).into_iter().map(|(name, decl)| format!("{} -> {}", name, decl)).collect();
+    assert_eq!(
+        names,
+        ["helper -> fn helper@4", "limit -> let limit@7", "helper -> fn helper@4", "n -> param n@4"]
+    );
+}
+
+#[test]
+fn undefined_names_are_reported() {
+    let source = "import std.io;
+let a = b;
+let b = 1;
+fn f() {
+    return missing + a;
+}
+fs.read(a);
+";
+
+    assert_eq!(
+        errors(source),
+        [
+            (String::from("Undefined name 'b'"), 2),
+            (String::from("Undefined name 'missing'"), 5),
+            (String::from("Undefined name 'fs'"), 7),
+        ]
+    );
+}
+
+#[test]
+fn a_variable_is_not_in_scope_in_its_own_initializer() {
+    assert_eq!(errors("let x = x;"), [(String::from("Undefined name 'x'"), 1)]);
+}
+
+#[test]
+fn duplicate_names_in_one_scope_are_reported() {
+    let source = "let x = 1;
+let x = 2;
+fn f(a: number, a: number) {
+    let x = a;
+}
+fn f() {}
+import std.io;
+import std.io;
+";
+
+    let ast = parse(source);
+    let (_, errors) = resolve(&ast);
+    let messages: Vec<_> = errors.iter().map(|err| (err.message.as_str(), err.line_info.line)).collect();
+    assert_eq!(
+        messages,
+        [
+            ("'x' is already declared in this scope", 2),
+            ("'a' is already declared in this scope", 3),
+            ("'f' is already declared in this scope", 6),
+            ("'io' is already declared in this scope", 8),
+        ]
+    );
+    assert_eq!(errors[0].notes[0].to_string(), "note: 'x' was first declared on line 1");
+}
+
+#[test]
+fn file_imports_declare_the_names_they_are_given() {
+    let ast = parse("helper();\nimport \"lib.gk\";\nhelper(limit);\nmissing();");
+    let imports = Imports::from([(Symbol::intern("lib.gk"), vec![Symbol::intern("helper")])]);
+    let (_, errors) = resolve_with_imports(&ast, &imports);
+
+    let errors: Vec<_> = errors.iter().map(|err| (err.message.as_str(), err.line_info.line)).collect();
+    assert_eq!(
+        errors,
+        [
+            ("Undefined name 'helper'", 1),
+            ("Undefined name 'limit'", 3),
+            ("Undefined name 'missing'", 4),
+        ]
+    );
+}
+
+#[test]
+fn names_after_an_unknown_file_import_are_only_warnings() {
+    let ast = parse("helper();\nimport \"lib.gk\";\nhelper();");
+    let (_, errors) = resolve(&ast);
+
+    assert_eq!(errors.len(), 2);
+    assert!(!errors[0].is_warning());
+    assert!(errors[1].is_warning());
+    assert_eq!(errors[1].message, "Undefined name 'helper'");
+    assert_eq!(
+        errors[1].notes[0].to_string(),
+        "note: it may be declared by \"lib.gk\", which couldn't be checked"
+    );
+}
+
+#[test]
+fn declarations_point_at_their_statement() {
+    let ast = parse("fn f(a: number) { return a; }");
+    let (resolution, _) = resolve(&ast);
+    let (_, decl) = resolution.bindings().next().unwrap();
+
+    assert!(matches!(ast[decl.stmt()], Stmt::FnDecl(_)));
+    assert!(matches!(decl, Decl::Param { index: 0, .. }));
+}
+
+#[test]
+fn if_branches_are_scopes_of_their_own() {
+    let source = "let x = 1;
+if x == 1 {
+    let x = 2;
+    let y = x;
+} else {
+    let y = 3;
+}
+y;
+";
+
+    assert_eq!(errors(source), [(String::from("Undefined name 'y'"), 8)]);
+}
+
+#[test]
+fn top_level_names_are_what_a_file_declares() {
+    let ast = parse("import std.io;\nlet x = 1;\nfn f() { let y = 2; }\nimport \"lib.gk\";\nif true { let z = 3; }");
+    let names: Vec<_> = top_level_names(&ast).into_iter().map(Symbol::as_str).collect();
+
+    assert_eq!(names, ["io", "x", "f"]);
+}
@@ -0,0 +1,131 @@
+//! Control flow: reports functions that can reach the end of their body
+//! without returning the value they declare, and statements that can never
+//! run because every path before them returns.
+
+use gecko_error::{Error, LineInfo, Note};
+use gecko_lexer::symbol::Symbol;
+use gecko_parser::{
+    ast::{Ast, StmtId},
+    nodes::{
+        stmt::{Fn, Stmt},
+        Ident,
+    },
+    visit::Visitor,
+};
+
+pub fn check(ast: &Ast) -> Vec<Error> {
+    let mut flow = Flow { errors: vec![], function: None };
+    flow.block(ast, &ast.root);
+    flow.errors
+}
+
+/// Whether every path through `stmts` returns, without reporting anything.
+pub fn always_returns(ast: &Ast, stmts: &[StmtId]) -> bool {
+    stmts.iter().any(|&id| match &ast[id] {
+        Stmt::Return(_) => true,
+        Stmt::If(stmt) => {
+            always_returns(ast, &stmt.then_branch)
+                && stmt.else_branch.as_ref().is_some_and(|branch| always_returns(ast, branch))
+        }
+        _ => false,
+    })
+}
+
+/// The function whose body is being checked.
+#[derive(Clone, Copy)]
+struct Function {
+    name: Symbol,
+    return_type: Option<Ident>,
+}
+
+impl Function {
+    /// The declared return type, unless the function returns nothing.
+    fn returns_value(self) -> Option<Ident> {
+        self.return_type.filter(|return_type| return_type.name.as_str() != "void")
+    }
+
+    fn return_type_note(self) -> Option<Note> {
+        let return_type = self.return_type?;
+        Some(Note::Note(format!(
+            "'{}' is declared to return {} on line {}",
+            self.name, return_type.name, return_type.span.line
+        )))
+    }
+}
+
+/// Why the statements after a point never run.
+enum Exit {
+    Return(LineInfo),
+    If(LineInfo),
+}
+
+impl Exit {
+    fn note(&self, function: Option<Function>) -> Note {
+        match (self, function) {
+            (Exit::Return(span), Some(_)) => Note::Note(format!("the function returns on line {}", span.line)),
+            (Exit::Return(span), None) => Note::Note(format!("the program returns on line {}", span.line)),
+            (Exit::If(span), _) => Note::Note(format!("both branches of the 'if' on line {} return", span.line)),
+        }
+    }
+}
+
+struct Flow {
+    errors: Vec<Error>,
+    function: Option<Function>,
+}
+
+impl Flow {
+    /// Checks `stmts`, returning how they exit if every path through them
+    /// returns.
+    fn block(&mut self, ast: &Ast, stmts: &[StmtId]) -> Option<Exit> {
+        let mut exit: Option<Exit> = None;
+        let mut warned = false;
+
+        for &id in stmts {
+            if let (Some(exit), false) = (&exit, warned) {
+                let mut notes = vec![exit.note(self.function)];
+                notes.extend(self.function.and_then(Function::return_type_note));
+                self.errors.push(Error::warning(*ast[id].span(), String::from("Unreachable statement"), notes));
+                warned = true;
+            }
+
+            let returns = self.stmt(ast, id);
+            exit = exit.or(returns);
+        }
+
+        exit
+    }
+
+    fn stmt(&mut self, ast: &Ast, id: StmtId) -> Option<Exit> {
+        match &ast[id] {
+            Stmt::Return(ret) => Some(Exit::Return(ret.span)),
+            Stmt::If(stmt) => {
+                let then_branch = self.block(ast, &stmt.then_branch);
+                let else_branch = stmt.else_branch.as_ref().and_then(|branch| self.block(ast, branch));
+                then_branch.and(else_branch).map(|_| Exit::If(stmt.span))
+            }
+            _ => {
+                self.visit_stmt(ast, id);
+                None
+            }
+        }
+    }
+}
+
+impl Visitor for Flow {
+    fn visit_fn(&mut self, ast: &Ast, _id: StmtId, func: &Fn) {
+        let function = Function { name: func.name.name, return_type: func.return_type };
+        let previous = self.function.replace(function);
+
+        let exit = self.block(ast, &func.body);
+        if let (None, Some(return_type)) = (exit, function.returns_value()) {
+            self.errors.push(Error::new_with_notes(
+                func.name.span,
+                format!("'{}' can reach the end of its body without returning a {}", func.name.name, return_type.name),
+                function.return_type_note().into_iter().collect(),
+            ));
+        }
+
+        self.function = previous;
+    }
+}
@@ -0,0 +1,402 @@
+//! Type checking: infers the type of every expression from its literals,
+//! operators and the declarations its names resolve to, and checks operands,
+//! call arguments and returned values against what is expected of them.
+//!
+//! A `let` takes the type of its initializer, and parameters the type they
+//! are annotated with. A function without a return type returns the type of
+//! the values its `return`s give, or void if they give none or it can end
+//! without returning; its body is checked as soon as a call needs that type.
+//!
+//! Function bodies that can end without returning a value, and statements
+//! after a `return`, are found by the [`flow`] pass run alongside.
+
+mod flow;
+mod ty;
+
+use std::collections::{HashMap, HashSet};
+
+use gecko_error::{Error, LineInfo, Note};
+use gecko_lexer::symbol::Symbol;
+use gecko_parser::{
+    ast::{Ast, ExprId, StmtId},
+    nodes::{
+        expr::{BinaryExpr, BinaryOp, CallExpr, Expr, GetExpr, LiteralExpr, Type, UnaryExpr, UnaryOp},
+        stmt::{Fn, Stmt},
+        Ident,
+    },
+};
+use gecko_resolver::{Decl, Resolution};
+
+pub use flow::always_returns;
+pub use ty::Ty;
+
+/// The types found by [`check`].
+#[derive(Debug, Default)]
+pub struct TypeTable {
+    exprs: HashMap<ExprId, Ty>,
+    decls: HashMap<Decl, Ty>,
+}
+
+impl TypeTable {
+    /// The type of an expression.
+    pub fn expr(&self, id: ExprId) -> &Ty {
+        self.exprs.get(&id).unwrap_or(&Ty::Unknown)
+    }
+
+    /// The type of a declared name.
+    pub fn decl(&self, decl: Decl) -> &Ty {
+        self.decls.get(&decl).unwrap_or(&Ty::Unknown)
+    }
+
+    /// Every declared name and its type.
+    pub fn bindings(&self) -> impl Iterator<Item = (Decl, &Ty)> + '_ {
+        self.decls.iter().map(|(&decl, ty)| (decl, ty))
+    }
+
+    /// The name under byte `offset` of the source and its type, for hovers:
+    /// a declared name or the innermost expression naming one.
+    pub fn type_at(&self, ast: &Ast, offset: usize) -> Option<(LineInfo, &Ty)> {
+        let contains = |span: &LineInfo| span.start <= offset && offset < span.end;
+
+        let decls = self.decls.iter().filter_map(|(&decl, ty)| match decl {
+            Decl::Module(_) => None,
+            decl => Some((decl.span(ast), ty)),
+        });
+        let names = self.exprs.iter().filter_map(|(&id, ty)| match &ast[id] {
+            Expr::Literal(LiteralExpr { value: Type::Iden(_), span }) => Some((*span, ty)),
+            _ => None,
+        });
+
+        decls.chain(names).filter(|(span, _)| contains(span)).min_by_key(|(span, _)| span.end - span.start)
+    }
+}
+
+/// Type checks `ast`, using `resolution` to find what each name refers to.
+pub fn check(ast: &Ast, resolution: &Resolution) -> (TypeTable, Vec<Error>) {
+    let mut checker = Checker {
+        ast,
+        resolution,
+        table: TypeTable::default(),
+        errors: vec![],
+        function: None,
+        unchecked: HashSet::new(),
+    };
+    checker.block(&ast.root);
+    checker.errors.extend(flow::check(ast));
+
+    // Function bodies are checked after their scope, as in the resolver.
+    checker.errors.sort_by_key(|err| err.line_info.start);
+    (checker.table, checker.errors)
+}
+
+/// The function whose body is being checked.
+struct Function {
+    name: Symbol,
+    /// The declared return type and the line of its annotation.
+    declared: Option<(Ty, usize)>,
+    /// Without a declared type, the type of the first `return` and its line.
+    inferred: Option<(Ty, usize)>,
+}
+
+struct Checker<'a> {
+    ast: &'a Ast,
+    resolution: &'a Resolution,
+    table: TypeTable,
+    errors: Vec<Error>,
+    function: Option<Function>,
+    /// Functions whose signature is known but whose body isn't checked yet.
+    unchecked: HashSet<StmtId>,
+}
+
+impl Checker<'_> {
+    /// Checks `stmts`, then the bodies of the functions declared among them,
+    /// so a body sees the types of everything declared around it.
+    fn block(&mut self, stmts: &[StmtId]) {
+        let mut functions = vec![];
+        for &id in stmts {
+            self.stmt(id, &mut functions);
+        }
+
+        for id in functions {
+            self.function(id);
+        }
+    }
+
+    fn stmt(&mut self, id: StmtId, functions: &mut Vec<StmtId>) {
+        let ast = self.ast;
+        match &ast[id] {
+            Stmt::ExprStmt(stmt) => {
+                self.expr(stmt.expr);
+            }
+            Stmt::VarDecl(var) => {
+                let ty = match var.initializer {
+                    Some(initializer) => self.expr(initializer),
+                    None => Ty::Unknown,
+                };
+                self.table.decls.insert(Decl::Var(id), ty);
+            }
+            Stmt::FnDecl(func) => {
+                self.signature(id, func);
+                self.unchecked.insert(id);
+                functions.push(id);
+            }
+            Stmt::Return(ret) => {
+                let found = match ret.value {
+                    Some(value) => self.expr(value),
+                    None => Ty::Void,
+                };
+                let span = match ret.value {
+                    Some(value) => *ast[value].span(),
+                    None => ret.span,
+                };
+                self.check_return(&found, span);
+            }
+            Stmt::If(stmt) => {
+                let condition = self.expr(stmt.condition);
+                self.expect(&Ty::Bool, &condition, *ast[stmt.condition].span());
+                self.block(&stmt.then_branch);
+                if let Some(else_branch) = &stmt.else_branch {
+                    self.block(else_branch);
+                }
+            }
+            Stmt::LangImport(_) => {
+                self.table.decls.insert(Decl::Module(id), Ty::Module);
+            }
+            Stmt::FileImport(_) => {}
+        }
+    }
+
+    /// Records the types of a function and its parameters from their
+    /// annotations.
+    fn signature(&mut self, id: StmtId, func: &Fn) {
+        let mut params = vec![];
+        for (index, param) in func.params.iter().enumerate() {
+            let ty = self.annotation(&param.type_);
+            self.table.decls.insert(Decl::Param { func: id, index }, ty.clone());
+            params.push(ty);
+        }
+
+        let ret = match &func.return_type {
+            Some(return_type) => self.annotation(return_type),
+            None => Ty::Unknown,
+        };
+        self.table.decls.insert(Decl::Fn(id), Ty::Fn { params, ret: Box::new(ret) });
+    }
+
+    /// Checks the body of the function declared by `id`, unless that has
+    /// already happened, and infers its return type if it has no annotation.
+    fn function(&mut self, id: StmtId) {
+        let Stmt::FnDecl(func) = &self.ast[id] else {
+            return;
+        };
+        if !self.unchecked.remove(&id) {
+            return;
+        }
+
+        let declared = func
+            .return_type
+            .map(|return_type| (self.annotation_quiet(&return_type), return_type.span.line));
+        let function = Function { name: func.name.name, declared, inferred: None };
+
+        let previous = self.function.replace(function);
+        self.block(&func.body);
+        let function = std::mem::replace(&mut self.function, previous).unwrap();
+
+        if function.declared.is_none() {
+            let (mut inferred, line) = function.inferred.unwrap_or((Ty::Void, 0));
+            // Falling off the end returns void, which must agree with the
+            // values the `return`s give.
+            if !matches!(inferred, Ty::Void | Ty::Unknown) && !always_returns(self.ast, &func.body) {
+                self.errors.push(Error::new_with_notes(
+                    func.name.span,
+                    format!("'{}' can reach the end of its body without returning a {}", func.name.name, inferred),
+                    vec![Note::Note(format!("'{}' returns {} on line {}", func.name.name, inferred, line))],
+                ));
+                inferred = Ty::Unknown;
+            }
+            if let Some(Ty::Fn { ret, .. }) = self.table.decls.get_mut(&Decl::Fn(id)) {
+                **ret = inferred;
+            }
+        }
+    }
+
+    /// The type a type annotation names, reporting names that aren't types.
+    fn annotation(&mut self, name: &Ident) -> Ty {
+        let ty = self.annotation_quiet(name);
+        if ty == Ty::Unknown {
+            self.errors.push(Error::new(name.span, format!("Unknown type '{}'", name.name)));
+        }
+        ty
+    }
+
+    fn annotation_quiet(&self, name: &Ident) -> Ty {
+        match name.name.as_str() {
+            "number" => Ty::Number,
+            "string" => Ty::String,
+            "bool" => Ty::Bool,
+            "void" => Ty::Void,
+            _ => Ty::Unknown,
+        }
+    }
+
+    fn check_return(&mut self, found: &Ty, span: LineInfo) {
+        let Some(function) = &mut self.function else {
+            return;
+        };
+
+        let (expected, note) = match (&function.declared, &mut function.inferred) {
+            (Some((expected, line)), _) => {
+                (expected, format!("'{}' is declared to return {} on line {}", function.name, expected, line))
+            }
+            (None, inferred @ None) => {
+                *inferred = Some((found.clone(), span.line));
+                return;
+            }
+            (None, Some((Ty::Unknown, _))) => {
+                function.inferred = Some((found.clone(), span.line));
+                return;
+            }
+            (None, Some((expected, line))) => {
+                (&*expected, format!("'{}' returns {} on line {}", function.name, expected, line))
+            }
+        };
+
+        if !expected.accepts(found) {
+            let error = Error::new_with_notes(span, mismatch(expected, found), vec![Note::Note(note)]);
+            self.errors.push(error);
+        }
+    }
+
+    fn expect(&mut self, expected: &Ty, found: &Ty, span: LineInfo) -> bool {
+        let ok = expected.accepts(found);
+        if !ok {
+            self.errors.push(Error::new(span, mismatch(expected, found)));
+        }
+        ok
+    }
+
+    fn expr(&mut self, id: ExprId) -> Ty {
+        let ast = self.ast;
+        let ty = match &ast[id] {
+            Expr::Literal(literal) => match literal.value {
+                Type::Int(_) | Type::Float(_) => Ty::Number,
+                Type::String(_) => Ty::String,
+                Type::Bool(_) => Ty::Bool,
+                Type::Void => Ty::Void,
+                Type::Iden(_) => match self.resolution.get(id) {
+                    Some(decl) => {
+                        // The return type of a function without an
+                        // annotation is only known once its body is checked.
+                        if let Decl::Fn(func) = decl {
+                            if matches!(&ast[func], Stmt::FnDecl(Fn { return_type: None, .. })) {
+                                self.function(func);
+                            }
+                        }
+                        self.table.decl(decl).clone()
+                    }
+                    None => Ty::Unknown,
+                },
+                Type::Unknown => Ty::Unknown,
+            },
+            Expr::Grouping(grouping) => self.expr(grouping.expression),
+            Expr::Binary(binary) => self.binary(binary),
+            Expr::Unary(unary) => self.unary(unary),
+            Expr::Call(call) => self.call(call),
+            Expr::Get(get) => self.get(get),
+        };
+        self.table.exprs.insert(id, ty.clone());
+        ty
+    }
+
+    fn binary(&mut self, binary: &BinaryExpr) -> Ty {
+        let left = self.expr(binary.left);
+        let right = self.expr(binary.right);
+        let left_span = *self.ast[binary.left].span();
+        let right_span = *self.ast[binary.right].span();
+
+        match binary.operator {
+            BinaryOp::Add => {
+                for (ty, span) in [(&left, left_span), (&right, right_span)] {
+                    if !matches!(ty, Ty::Number | Ty::String | Ty::Unknown) {
+                        self.errors.push(Error::new(span, format!("expected number or string, found {}", ty)));
+                        return Ty::Unknown;
+                    }
+                }
+                match (left, right) {
+                    (Ty::Unknown, ty) | (ty, Ty::Unknown) => ty,
+                    (left, right) if self.expect(&left, &right, right_span) => left,
+                    _ => Ty::Unknown,
+                }
+            }
+            BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div => {
+                self.expect(&Ty::Number, &left, left_span);
+                self.expect(&Ty::Number, &right, right_span);
+                Ty::Number
+            }
+            BinaryOp::Less | BinaryOp::LessEq | BinaryOp::Greater | BinaryOp::GreaterEq => {
+                self.expect(&Ty::Number, &left, left_span);
+                self.expect(&Ty::Number, &right, right_span);
+                Ty::Bool
+            }
+            BinaryOp::Eq | BinaryOp::NotEq => {
+                self.expect(&left, &right, right_span);
+                Ty::Bool
+            }
+        }
+    }
+
+    fn unary(&mut self, unary: &UnaryExpr) -> Ty {
+        let right = self.expr(unary.right);
+        let expected = match unary.operator {
+            UnaryOp::Neg => Ty::Number,
+            UnaryOp::Not => Ty::Bool,
+        };
+        self.expect(&expected, &right, *self.ast[unary.right].span());
+        expected
+    }
+
+    fn call(&mut self, call: &CallExpr) -> Ty {
+        let callee = self.expr(call.callee);
+        let args: Vec<Ty> = call.args.iter().map(|&arg| self.expr(arg)).collect();
+
+        match callee {
+            Ty::Fn { params, ret } => {
+                if params.len() != args.len() {
+                    self.errors.push(Error::new(
+                        call.paren,
+                        format!("Expected {} arguments but got {}", params.len(), args.len()),
+                    ));
+                } else {
+                    for ((param, arg), &id) in params.iter().zip(&args).zip(&call.args) {
+                        self.expect(param, arg, *self.ast[id].span());
+                    }
+                }
+                *ret
+            }
+            Ty::Unknown => Ty::Unknown,
+            other => {
+                self.errors.push(Error::new(call.paren, format!("Cannot call a {}", other)));
+                Ty::Unknown
+            }
+        }
+    }
+
+    fn get(&mut self, get: &GetExpr) -> Ty {
+        match self.expr(get.object) {
+            // Module members are defined by the interpreter's standard
+            // library and aren't known here.
+            Ty::Module | Ty::Unknown => Ty::Unknown,
+            other => {
+                self.errors.push(Error::new(
+                    get.name.span,
+                    format!("Cannot access '{}' on a {}", get.name.name, other),
+                ));
+                Ty::Unknown
+            }
+        }
+    }
+}
+
+fn mismatch(expected: &Ty, found: &Ty) -> String {
+    format!("expected {}, found {}", expected, found)
+}
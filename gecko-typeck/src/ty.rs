@@ -0,0 +1,46 @@
+use std::fmt;
+
+/// The static type of an expression or declaration.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Ty {
+    Number,
+    String,
+    Bool,
+    Void,
+    Fn { params: Vec<Ty>, ret: Box<Ty> },
+    Module,
+    /// Not known statically: an undefined name, a module member, or the
+    /// result of an expression that already failed to check. Compatible
+    /// with every type, so one mistake isn't reported again downstream.
+    Unknown,
+}
+
+impl Ty {
+    /// Whether a value of type `found` can be used where `self` is expected.
+    pub fn accepts(&self, found: &Ty) -> bool {
+        matches!(self, Ty::Unknown) || matches!(found, Ty::Unknown) || self == found
+    }
+}
+
+impl fmt::Display for Ty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Ty::Number => f.write_str("number"),
+            Ty::String => f.write_str("string"),
+            Ty::Bool => f.write_str("bool"),
+            Ty::Void => f.write_str("void"),
+            Ty::Fn { params, ret } => {
+                f.write_str("fn(")?;
+                for (i, param) in params.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}", param)?;
+                }
+                write!(f, ") -> {}", ret)
+            }
+            Ty::Module => f.write_str("module"),
+            Ty::Unknown => f.write_str("unknown"),
+        }
+    }
+}
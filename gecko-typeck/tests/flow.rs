@@ -0,0 +1,122 @@
+use gecko_lexer::Lexer;
+use gecko_parser::Parser;
+use gecko_resolver::resolve;
+use gecko_typeck::check;
+
+/// Each diagnostic as `severity: message @ line`, followed by its notes.
+fn diagnostics(source: &str) -> Vec<String> {
+    let tokens = Lexer::new(source.to_string()).scan_tokens().unwrap();
+    let ast = Parser::new(tokens).parse().unwrap();
+    let (resolution, _) = resolve(&ast);
+
+    check(&ast, &resolution)
+        .1
+        .iter()
+        .flat_map(|err| {
+            let head = format!("{}: {} @ {}", err.severity, err.message, err.line_info.line);
+            std::iter::once(head).chain(err.notes.iter().map(|note| note.to_string()))
+        })
+        .collect()
+}
+
+#[test]
+fn a_function_that_never_returns_a_value_is_an_error() {
+    let source = "import std.io;
+
+fn main() -> number {
+    io.println(\"Hello, world!\");
+}
+";
+
+    assert_eq!(
+        diagnostics(source),
+        [
+            "error: 'main' can reach the end of its body without returning a number @ 3",
+            "note: 'main' is declared to return number on line 3",
+        ]
+    );
+}
+
+#[test]
+fn every_branch_must_return() {
+    let source = "fn sign(n: number) -> number {
+    if n < 0 {
+        return -1;
+    } else if n > 0 {
+        return 1;
+    }
+}
+fn abs(n: number) -> number {
+    if n < 0 {
+        return -n;
+    } else {
+        return n;
+    }
+}
+fn half(n: number) -> number {
+    if n > 0 {
+        return n / 2;
+    }
+    return 0;
+}
+";
+
+    assert_eq!(
+        diagnostics(source),
+        [
+            "error: 'sign' can reach the end of its body without returning a number @ 1",
+            "note: 'sign' is declared to return number on line 1",
+        ]
+    );
+}
+
+#[test]
+fn functions_without_a_value_may_fall_off_the_end() {
+    assert!(diagnostics("fn f() {} fn g() -> void { let x = 1; } fn h(a: bool) { if a { return; } }").is_empty());
+}
+
+#[test]
+fn statements_after_a_return_are_unreachable() {
+    let source = "fn f() -> number {
+    return 1;
+    let x = 2;
+    let y = 3;
+}
+fn g(a: bool) {
+    if a {
+        return;
+    } else {
+        return;
+    }
+    g(a);
+}
+";
+
+    assert_eq!(
+        diagnostics(source),
+        [
+            "warning: Unreachable statement @ 3",
+            "note: the function returns on line 2",
+            "note: 'f' is declared to return number on line 1",
+            "warning: Unreachable statement @ 12",
+            "note: both branches of the 'if' on line 7 return",
+        ]
+    );
+}
+
+#[test]
+fn nested_functions_are_checked_on_their_own() {
+    let source = "fn outer() -> number {
+    fn inner() -> string {}
+    return 1;
+}
+";
+
+    assert_eq!(
+        diagnostics(source),
+        [
+            "error: 'inner' can reach the end of its body without returning a string @ 2",
+            "note: 'inner' is declared to return string on line 2",
+        ]
+    );
+}
@@ -0,0 +1,154 @@
+use gecko_lexer::Lexer;
+use gecko_parser::{ast::Ast, Parser};
+use gecko_resolver::resolve;
+use gecko_typeck::{check, TypeTable};
+
+fn parse(source: &str) -> Ast {
+    let tokens = Lexer::new(source.to_string()).scan_tokens().unwrap();
+    Parser::new(tokens).parse().unwrap()
+}
+
+fn infer(source: &str) -> (Ast, TypeTable, Vec<String>) {
+    let ast = parse(source);
+    let (resolution, _) = resolve(&ast);
+    let (table, errors) = check(&ast, &resolution);
+    let errors = errors.into_iter().map(|err| format!("{} @ {}", err.message, err.line_info.line)).collect();
+    (ast, table, errors)
+}
+
+/// Every declared name and its type, in source order.
+fn bindings(source: &str) -> Vec<String> {
+    let (ast, table, errors) = infer(source);
+    assert!(errors.is_empty(), "{:?}", errors);
+
+    let mut bindings: Vec<_> = table.bindings().map(|(decl, ty)| (decl.span(&ast).start, decl, ty)).collect();
+    bindings.sort_by_key(|(start, _, _)| *start);
+    bindings.into_iter().map(|(_, decl, ty)| format!("{}: {}", decl.name(&ast), ty)).collect()
+}
+
+#[test]
+fn lets_take_the_type_of_their_initializer() {
+    assert_eq!(
+        bindings("let a = 1; let b = a * 2 > 3; let c = \"x\" + \"y\"; let d;"),
+        ["a: number", "b: bool", "c: string", "d: unknown"]
+    );
+}
+
+#[test]
+fn return_types_are_inferred_from_returns() {
+    let source = "fn one() {
+    return 1;
+}
+fn nothing() {}
+fn early(a: bool) {
+    if a {
+        return;
+    }
+}
+fn twice(n: number) {
+    let doubled = n * 2;
+    return doubled;
+}
+let x = twice(one());
+";
+
+    assert_eq!(
+        bindings(source),
+        [
+            "one: fn() -> number",
+            "nothing: fn() -> void",
+            "early: fn(bool) -> void",
+            "a: bool",
+            "twice: fn(number) -> number",
+            "n: number",
+            "doubled: number",
+            "x: number",
+        ]
+    );
+}
+
+#[test]
+fn recursive_functions_infer_from_their_base_case() {
+    let source = "fn fact(n: number) {
+    if n < 2 {
+        return 1;
+    }
+    return n * fact(n - 1);
+}
+let f = fact(5);
+";
+
+    assert_eq!(bindings(source), ["fact: fn(number) -> number", "n: number", "f: number"]);
+}
+
+#[test]
+fn functions_are_inferred_before_the_calls_that_need_them() {
+    let source = "fn main() {
+    let greeting = greet(\"gecko\");
+    let bad = greeting * 2;
+}
+fn greet(name: string) {
+    return prefix + name;
+}
+let prefix = \"hello, \";
+";
+
+    let (_, _, errors) = infer(source);
+    assert_eq!(errors, ["expected number, found string @ 3"]);
+}
+
+#[test]
+fn returns_must_agree_without_an_annotation() {
+    let source = "fn f(a: bool) {
+    if a {
+        return 1;
+    }
+    return \"one\";
+}
+";
+
+    let ast = parse(source);
+    let (resolution, _) = resolve(&ast);
+    let (_, errors) = check(&ast, &resolution);
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].message, "expected number, found string");
+    assert_eq!(errors[0].notes[0].to_string(), "note: 'f' returns number on line 3");
+}
+
+#[test]
+fn falling_off_the_end_must_agree_with_the_returns() {
+    let source = "fn f(a: bool) {
+    if a {
+        return 1;
+    }
+}
+let x = f(false) + 1;
+";
+
+    let ast = parse(source);
+    let (resolution, _) = resolve(&ast);
+    let (table, errors) = check(&ast, &resolution);
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].message, "'f' can reach the end of its body without returning a number");
+    assert_eq!(errors[0].line_info.line, 1);
+    assert_eq!(errors[0].notes[0].to_string(), "note: 'f' returns number on line 3");
+    assert_eq!(table.decl(gecko_resolver::Decl::Fn(ast.root[0])).to_string(), "fn(bool) -> unknown");
+}
+
+#[test]
+fn hovers_find_the_innermost_name() {
+    let source = "fn add(a: number, b: number) { return a + b; }\nlet sum = add(1, 2);";
+    let (ast, table, _) = infer(source);
+    let hover = |needle: &str| {
+        let offset = source.rfind(needle).unwrap();
+        table.type_at(&ast, offset).map(|(span, ty)| (&source[span.start..span.end], ty.to_string()))
+    };
+
+    assert_eq!(hover("add("), Some(("add", String::from("fn(number, number) -> number"))));
+    assert_eq!(hover("b;"), Some(("b", String::from("number"))));
+    assert_eq!(hover("sum"), Some(("sum", String::from("number"))));
+    assert_eq!(hover("a: number"), Some(("a", String::from("number"))));
+    assert_eq!(hover("(1, 2)"), None);
+}
@@ -0,0 +1,189 @@
+use gecko_lexer::Lexer;
+use gecko_parser::{ast::Ast, nodes::stmt::Stmt, Parser};
+use gecko_resolver::resolve;
+use gecko_typeck::{check, Ty};
+
+fn parse(source: &str) -> Ast {
+    let tokens = Lexer::new(source.to_string()).scan_tokens().unwrap();
+    Parser::new(tokens).parse().unwrap()
+}
+
+/// Each type error's message and the source text it points at.
+fn errors(source: &str) -> Vec<(String, String)> {
+    let ast = parse(source);
+    let (resolution, _) = resolve(&ast);
+    check(&ast, &resolution)
+        .1
+        .into_iter()
+        .map(|err| (err.message, source[err.line_info.start..err.line_info.end].to_string()))
+        .collect()
+}
+
+fn error(message: &str, text: &str) -> (String, String) {
+    (message.to_string(), text.to_string())
+}
+
+/// The type of the initializer of each top-level `let`.
+fn let_types(source: &str) -> Vec<String> {
+    let ast = parse(source);
+    let (resolution, _) = resolve(&ast);
+    let (table, errors) = check(&ast, &resolution);
+    assert!(errors.is_empty(), "{:?}", errors.iter().map(|e| &e.message).collect::<Vec<_>>());
+
+    ast.root
+        .iter()
+        .filter_map(|&id| match &ast[id] {
+            Stmt::VarDecl(var) => Some(table.expr(var.initializer?).to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn literals_and_operators_have_types() {
+    let source = "let a = 1;
+let b = \"s\";
+let c = true;
+let d = 1 + 2 * 3;
+let e = \"a\" + \"b\";
+let f = 1 == 2;
+let g = !(a != 1);
+let h = -a;
+";
+
+    assert_eq!(let_types(source), ["number", "string", "bool", "number", "string", "bool", "bool", "number"]);
+}
+
+#[test]
+fn names_take_the_type_of_their_declaration() {
+    let source = "fn add(a: number, b: number) -> number {
+    return a + b;
+}
+fn name(flag: bool) -> string {
+    return \"gecko\";
+}
+let sum = add(1, 2);
+let copy = sum;
+let text = name(true);
+let func = add;
+import std.io;
+let println = io.println;
+";
+
+    assert_eq!(let_types(source), ["number", "number", "string", "fn(number, number) -> number", "unknown"]);
+}
+
+#[test]
+fn operands_must_have_the_operator_type() {
+    let source = "let a = 1 - \"one\";
+let b = \"two\" * 2;
+let c = 1 + \"one\";
+let d = true + 1;
+let e = -\"x\";
+let f = !1;
+let g = 1 == \"1\";
+";
+
+    assert_eq!(
+        errors(source),
+        [
+            error("expected number, found string", "\"one\""),
+            error("expected number, found string", "\"two\""),
+            error("expected number, found string", "\"one\""),
+            error("expected number or string, found bool", "true"),
+            error("expected number, found string", "\"x\""),
+            error("expected bool, found number", "1"),
+            error("expected number, found string", "\"1\""),
+        ]
+    );
+}
+
+#[test]
+fn calls_are_checked_against_the_parameters() {
+    let source = "fn f(a: number, b: string) {}
+f(1, \"x\");
+f(\"x\", 1);
+f(1);
+let n = 1;
+n(2);
+";
+
+    assert_eq!(
+        errors(source),
+        [
+            error("expected number, found string", "\"x\""),
+            error("expected string, found number", "1"),
+            error("Expected 2 arguments but got 1", ")"),
+            error("Cannot call a number", ")"),
+        ]
+    );
+}
+
+#[test]
+fn returns_are_checked_against_the_declared_type() {
+    let source = "fn f() -> number {
+    return \"x\";
+}
+fn g() -> string {
+    return;
+}
+fn h() {
+    return 1;
+}
+fn i() -> void {
+    return;
+}
+";
+
+    let ast = parse(source);
+    let (resolution, _) = resolve(&ast);
+    let (_, errors) = check(&ast, &resolution);
+    let messages: Vec<_> = errors.iter().map(|err| (err.message.as_str(), err.line_info.line)).collect();
+
+    assert_eq!(messages, [("expected number, found string", 2), ("expected string, found void", 5)]);
+    assert_eq!(errors[0].notes[0].to_string(), "note: 'f' is declared to return number on line 1");
+}
+
+#[test]
+fn unknown_types_are_reported_once() {
+    assert_eq!(
+        errors("fn f(a: int) -> float { return a; }"),
+        [error("Unknown type 'int'", "int"), error("Unknown type 'float'", "float")]
+    );
+}
+
+#[test]
+fn unknown_types_do_not_cascade() {
+    assert!(errors("let a = missing + 1; let b = missing(1, 2); let c = -missing.x;").is_empty());
+    assert!(errors("import std.io; io.println(1); io.print(\"x\", 2);").is_empty());
+}
+
+#[test]
+fn members_only_exist_on_modules() {
+    assert_eq!(errors("let a = 1; a.b;"), [error("Cannot access 'b' on a number", "b")]);
+}
+
+#[test]
+fn types_display_like_annotations() {
+    let ty = Ty::Fn { params: vec![Ty::Number, Ty::Bool], ret: Box::new(Ty::String) };
+    assert_eq!(ty.to_string(), "fn(number, bool) -> string");
+    assert!(Ty::Unknown.accepts(&Ty::Number) && Ty::Number.accepts(&Ty::Unknown));
+    assert!(!Ty::Number.accepts(&Ty::String));
+}
+
+#[test]
+fn conditions_must_be_bool() {
+    let source = "fn f(n: number) {
+    if n {
+        return;
+    } else if n < \"two\" {
+        return;
+    }
+}
+";
+
+    assert_eq!(
+        errors(source),
+        [error("expected bool, found number", "n"), error("expected number, found string", "\"two\"")]
+    );
+}
@@ -0,0 +1,146 @@
+//! Runs a few programs under the tree-walking interpreter and the VM and
+//! reports how long each takes. The VM's time includes compiling.
+//!
+//! Run with `cargo bench -p gecko-vm`.
+
+use std::{
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use gecko_interpreter::Interpreter;
+use gecko_lexer::Lexer;
+use gecko_parser::{ast::Ast, Parser};
+use gecko_vm::Vm;
+
+const RUNS: u32 = 5;
+
+/// Each program's `main` returns a number, so the two results can be
+/// compared. None recurses deeper than the call depth limit of 512, and all
+/// pass `gecko check`.
+const PROGRAMS: [(&str, &str); 3] = [
+    (
+        "fib",
+        "fn fib(n: number) -> number {
+    if n < 2 {
+        return n;
+    }
+    return fib(n - 1) + fib(n - 2);
+}
+fn main() -> number {
+    return fib(24);
+}",
+    ),
+    (
+        "closures",
+        "fn adder(n: number) {
+    fn add(x: number) -> number {
+        return x + n;
+    }
+    return add;
+}
+fn scale(k: number) {
+    fn by(n: number) {
+        fn apply(x: number) -> number {
+            return x * k + n;
+        }
+        return apply;
+    }
+    return by;
+}
+fn sum(n: number) -> number {
+    if n == 0 {
+        return 0;
+    }
+    let add = adder(n);
+    let apply = scale(2)(n);
+    return add(1) + apply(n) + sum(n - 1);
+}
+fn repeat(times: number) -> number {
+    if times == 0 {
+        return 0;
+    }
+    return sum(200) + repeat(times - 1);
+}
+fn main() -> number {
+    return repeat(250);
+}",
+    ),
+    (
+        "strings",
+        "fn build(n: number, s: string) -> string {
+    if n == 0 {
+        return s;
+    }
+    if n < 100 {
+        return build(n - 1, s + \"a\");
+    }
+    return build(n - 1, s + \"b\");
+}
+fn count(times: number) -> number {
+    if times == 0 {
+        return 0;
+    }
+    let s = build(200, \"\");
+    if s == \"\" {
+        return 0;
+    }
+    return 1 + count(times - 1);
+}
+fn main() -> number {
+    return count(250);
+}",
+    ),
+];
+
+fn parse(source: &str) -> Ast {
+    let tokens = Lexer::new(source.to_string()).scan_tokens().unwrap();
+    Parser::new(tokens).parse().unwrap()
+}
+
+/// The resolver's and type checker's diagnostics for `ast`.
+fn check(ast: &Ast) -> Vec<String> {
+    let (resolution, mut errors) = gecko_resolver::resolve(ast);
+    errors.extend(gecko_typeck::check(ast, &resolution).1);
+    errors.iter().map(|err| err.to_string()).collect()
+}
+
+/// The fastest of `RUNS` runs of `run`, and what it returned.
+fn best(mut run: impl FnMut() -> String) -> (Duration, String) {
+    let mut best = Duration::MAX;
+    let mut result = String::new();
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        result = run();
+        best = best.min(start.elapsed());
+    }
+    (best, result)
+}
+
+fn main() {
+    for (name, source) in PROGRAMS {
+        let ast = parse(source);
+        assert_eq!(check(&ast), Vec::<String>::new(), "{} doesn't check", name);
+
+        let (interpreted, expected) = best(|| {
+            let mut interpreter = Interpreter::new();
+            interpreter.run(Rc::new(ast.clone())).unwrap();
+            format!("{:?}", interpreter.call_main().unwrap())
+        });
+        let (vm, result) = best(|| {
+            let mut vm = Vm::new();
+            vm.run(&ast).unwrap();
+            format!("{:?}", vm.call_main().unwrap())
+        });
+        assert_eq!(result, expected, "{} returned different results", name);
+
+        println!(
+            "{:<10} interpreter {:>10.2?}   vm {:>10.2?}   {:.1}x faster (best of {})",
+            name,
+            interpreted,
+            vm,
+            interpreted.as_secs_f64() / vm.as_secs_f64(),
+            RUNS
+        );
+    }
+}
@@ -0,0 +1,184 @@
+use std::rc::Rc;
+
+use gecko_error::LineInfo;
+use gecko_lexer::symbol::Symbol;
+
+/// One instruction. Operands index the chunk's constants, the slots of the
+/// current call frame or the upvalues of the running closure; jumps hold
+/// the index of the instruction to continue at.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Op {
+    /// Pushes a number or string constant.
+    Constant(u32),
+    True,
+    False,
+    Void,
+    Pop,
+    /// Pushes `n` slots for the names of a block that aren't declared yet.
+    Reserve(u32),
+    /// Pops the `n` slots of a block, closing any upvalues over them.
+    EndScope(u32),
+    GetLocal(u32),
+    /// Pops a value into a slot.
+    SetLocal(u32),
+    GetUpvalue(u32),
+    /// Pushes the global named by a name constant.
+    GetGlobal(u32),
+    /// Pops a value into the global named by a name constant.
+    DefineGlobal(u32),
+    /// Replaces a module with its member named by a name constant.
+    GetMember(u32),
+    Neg,
+    Not,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Eq,
+    NotEq,
+    Less,
+    LessEq,
+    Greater,
+    GreaterEq,
+    Jump(u32),
+    /// Pops a bool and jumps if it is false.
+    JumpIfFalse(u32),
+    /// Calls the value below the `n` arguments on top of the stack.
+    Call(u32),
+    /// Pushes a closure of a function constant.
+    Closure(u32),
+    Return,
+    /// Pushes the built-in module whose path is a string constant.
+    Import(u32),
+    /// Runs the file whose path is a string constant.
+    ImportFile(u32),
+}
+
+impl Op {
+    pub fn name(self) -> &'static str {
+        match self {
+            Op::Constant(_) => "Constant",
+            Op::True => "True",
+            Op::False => "False",
+            Op::Void => "Void",
+            Op::Pop => "Pop",
+            Op::Reserve(_) => "Reserve",
+            Op::EndScope(_) => "EndScope",
+            Op::GetLocal(_) => "GetLocal",
+            Op::SetLocal(_) => "SetLocal",
+            Op::GetUpvalue(_) => "GetUpvalue",
+            Op::GetGlobal(_) => "GetGlobal",
+            Op::DefineGlobal(_) => "DefineGlobal",
+            Op::GetMember(_) => "GetMember",
+            Op::Neg => "Neg",
+            Op::Not => "Not",
+            Op::Add => "Add",
+            Op::Sub => "Sub",
+            Op::Mul => "Mul",
+            Op::Div => "Div",
+            Op::Eq => "Eq",
+            Op::NotEq => "NotEq",
+            Op::Less => "Less",
+            Op::LessEq => "LessEq",
+            Op::Greater => "Greater",
+            Op::GreaterEq => "GreaterEq",
+            Op::Jump(_) => "Jump",
+            Op::JumpIfFalse(_) => "JumpIfFalse",
+            Op::Call(_) => "Call",
+            Op::Closure(_) => "Closure",
+            Op::Return => "Return",
+            Op::Import(_) => "Import",
+            Op::ImportFile(_) => "ImportFile",
+        }
+    }
+
+    pub fn operand(self) -> Option<u32> {
+        match self {
+            Op::Constant(n)
+            | Op::Reserve(n)
+            | Op::EndScope(n)
+            | Op::GetLocal(n)
+            | Op::SetLocal(n)
+            | Op::GetUpvalue(n)
+            | Op::GetGlobal(n)
+            | Op::DefineGlobal(n)
+            | Op::GetMember(n)
+            | Op::Jump(n)
+            | Op::JumpIfFalse(n)
+            | Op::Call(n)
+            | Op::Closure(n)
+            | Op::Import(n)
+            | Op::ImportFile(n) => Some(n),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Constant {
+    Number(f64),
+    String(Rc<str>),
+    Name(Symbol),
+    Function(Rc<Function>),
+}
+
+/// Compiled code with a span for each instruction, which runtime errors
+/// point at.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Chunk {
+    pub code: Vec<Op>,
+    pub spans: Vec<LineInfo>,
+    pub constants: Vec<Constant>,
+}
+
+impl Chunk {
+    pub fn push(&mut self, op: Op, span: LineInfo) -> usize {
+        self.code.push(op);
+        self.spans.push(span);
+        self.code.len() - 1
+    }
+
+    /// Adds a constant, reusing an equal one already in the pool.
+    pub fn constant(&mut self, constant: Constant) -> u32 {
+        let existing = match &constant {
+            Constant::Function(_) => None,
+            _ => self.constants.iter().position(|c| same(c, &constant)),
+        };
+
+        existing.unwrap_or_else(|| {
+            self.constants.push(constant);
+            self.constants.len() - 1
+        }) as u32
+    }
+}
+
+/// Equality for deduplicating constants: `-0.0` and `0.0` stay apart.
+fn same(a: &Constant, b: &Constant) -> bool {
+    match (a, b) {
+        (Constant::Number(a), Constant::Number(b)) => a.to_bits() == b.to_bits(),
+        (Constant::String(a), Constant::String(b)) => a == b,
+        (Constant::Name(a), Constant::Name(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// Where a closure finds a name from an enclosing function when it is
+/// created.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Capture {
+    /// A slot of the enclosing function's frame.
+    Local(u32),
+    /// An upvalue of the enclosing closure.
+    Upvalue(u32),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Function {
+    pub name: Symbol,
+    pub arity: usize,
+    pub chunk: Chunk,
+    /// What each upvalue captures, and the name it is read by.
+    pub captures: Vec<(Capture, Symbol)>,
+    /// The span of the declaration.
+    pub span: LineInfo,
+}
@@ -0,0 +1,387 @@
+//! Compiles an [`Ast`] to bytecode.
+//!
+//! Names are resolved the way the resolver resolves them. Each name a block
+//! declares gets a stack slot when the block starts, so a function can
+//! capture a name declared after it in an enclosing scope, but code only
+//! reads a slot once the name's declaration has run. Function bodies are
+//! compiled at the end of the block that declares them, when every name in
+//! it is known. Names declared at the top level of a program are globals,
+//! looked up by name when the code runs, as are names declared nowhere.
+
+use std::rc::Rc;
+
+use gecko_error::{Error, LineInfo};
+use gecko_lexer::symbol::Symbol;
+use gecko_parser::{
+    ast::{Ast, ExprId, StmtId},
+    nodes::{
+        expr::{BinaryOp, Expr, Type, UnaryOp},
+        stmt::Stmt,
+        Param,
+    },
+};
+
+use crate::chunk::{Capture, Chunk, Constant, Function, Op};
+
+/// Compiles a program to the function that runs its top-level statements.
+pub fn compile(ast: &Ast) -> Result<Rc<Function>, Error> {
+    let span = LineInfo::new(0, 0, 0);
+    let mut compiler = Compiler {
+        ast,
+        states: vec![State::new(Symbol::intern("<script>"), 0, span)],
+    };
+
+    compiler.block(&ast.root, &[], true, span)?;
+    compiler.emit(Op::Void, span);
+    compiler.emit(Op::Return, span);
+
+    Ok(Rc::new(compiler.states.pop().unwrap().function))
+}
+
+struct Local {
+    name: Symbol,
+    slot: u32,
+    /// Whether the declaration has been compiled, so code after it can
+    /// read the slot.
+    declared: bool,
+}
+
+struct Scope {
+    /// The top level of a program, whose names are globals.
+    global: bool,
+    locals: Vec<Local>,
+    /// Functions declared in this scope: the constant each one's closure
+    /// is made from, and its declaration.
+    functions: Vec<(u32, StmtId)>,
+}
+
+/// A function being compiled.
+struct State {
+    function: Function,
+    scopes: Vec<Scope>,
+    /// The next free slot. Slot 0 holds the running closure.
+    slots: u32,
+}
+
+impl State {
+    fn new(name: Symbol, arity: usize, span: LineInfo) -> State {
+        State {
+            function: Function { name, arity, chunk: Chunk::default(), captures: vec![], span },
+            scopes: vec![],
+            slots: 1,
+        }
+    }
+
+    /// The slot of a declared name, innermost scope first.
+    fn local(&self, name: Symbol) -> Option<u32> {
+        self.scopes
+            .iter()
+            .rev()
+            .filter(|scope| !scope.global)
+            .find_map(|scope| scope.locals.iter().find(|local| local.name == name && local.declared))
+            .map(|local| local.slot)
+    }
+}
+
+struct Compiler<'a> {
+    ast: &'a Ast,
+    /// The function being compiled and the ones enclosing it, outermost first.
+    states: Vec<State>,
+}
+
+impl Compiler<'_> {
+    fn state(&mut self) -> &mut State {
+        self.states.last_mut().unwrap()
+    }
+
+    fn chunk(&mut self) -> &mut Chunk {
+        &mut self.state().function.chunk
+    }
+
+    fn emit(&mut self, op: Op, span: LineInfo) -> usize {
+        self.chunk().push(op, span)
+    }
+
+    fn constant(&mut self, constant: Constant) -> u32 {
+        self.chunk().constant(constant)
+    }
+
+    /// Points the jump at `index` to the next instruction.
+    fn patch(&mut self, index: usize) {
+        let target = self.chunk().code.len() as u32;
+        match &mut self.chunk().code[index] {
+            Op::Jump(to) | Op::JumpIfFalse(to) => *to = target,
+            op => unreachable!("{:?} is not a jump", op),
+        }
+    }
+
+    /// Compiles `stmts` in a new scope that starts out with `params`,
+    /// returning the number of slots the scope holds.
+    fn block(&mut self, stmts: &[StmtId], params: &[Param], global: bool, span: LineInfo) -> Result<u32, Error> {
+        let ast = self.ast;
+        let mut scope = Scope { global, locals: vec![], functions: vec![] };
+
+        let state = self.state();
+        for param in params {
+            scope.locals.push(Local { name: param.name.name, slot: state.slots, declared: true });
+            state.slots += 1;
+        }
+
+        if !global {
+            let mut reserved = 0;
+            for &id in stmts {
+                let Some(name) = declared_name(ast, id) else {
+                    continue;
+                };
+                if !scope.locals.iter().any(|local| local.name == name) {
+                    scope.locals.push(Local { name, slot: state.slots, declared: false });
+                    state.slots += 1;
+                    reserved += 1;
+                }
+            }
+            if reserved > 0 {
+                self.emit(Op::Reserve(reserved), span);
+            }
+        }
+
+        self.state().scopes.push(scope);
+
+        for &id in stmts {
+            self.stmt(id)?;
+        }
+
+        let functions = std::mem::take(&mut self.state().scopes.last_mut().unwrap().functions);
+        for (constant, id) in functions {
+            self.function(constant, id)?;
+        }
+
+        let state = self.state();
+        let slots = state.scopes.pop().unwrap().locals.len() as u32;
+        state.slots -= slots;
+        Ok(slots)
+    }
+
+    /// Compiles the body of the function declared by `id` into the
+    /// constant its closure is made from.
+    fn function(&mut self, constant: u32, id: StmtId) -> Result<(), Error> {
+        let ast = self.ast;
+        let Stmt::FnDecl(func) = &ast[id] else {
+            return Ok(());
+        };
+
+        self.states.push(State::new(func.name.name, func.params.len(), func.span));
+        self.block(&func.body, &func.params, false, func.span)?;
+        self.emit(Op::Void, func.span);
+        self.emit(Op::Return, func.span);
+
+        let function = self.states.pop().unwrap().function;
+        self.chunk().constants[constant as usize] = Constant::Function(Rc::new(function));
+        Ok(())
+    }
+
+    /// Pops the value on top of the stack into `name`.
+    fn define(&mut self, name: Symbol, span: LineInfo) {
+        let scope = self.state().scopes.last_mut().unwrap();
+
+        if scope.global {
+            let constant = self.constant(Constant::Name(name));
+            self.emit(Op::DefineGlobal(constant), span);
+            return;
+        }
+
+        let local = scope.locals.iter_mut().find(|local| local.name == name).unwrap();
+        local.declared = true;
+        let slot = local.slot;
+        self.emit(Op::SetLocal(slot), span);
+    }
+
+    fn stmt(&mut self, id: StmtId) -> Result<(), Error> {
+        let ast = self.ast;
+        match &ast[id] {
+            Stmt::ExprStmt(stmt) => {
+                self.expr(stmt.expr)?;
+                self.emit(Op::Pop, stmt.span);
+            }
+            Stmt::VarDecl(var) => {
+                match var.initializer {
+                    Some(initializer) => self.expr(initializer)?,
+                    None => {
+                        self.emit(Op::Void, var.span);
+                    }
+                }
+                self.define(var.name.name, var.span);
+            }
+            Stmt::FnDecl(func) => {
+                // Filled in with the compiled body at the end of the block.
+                let placeholder = Function {
+                    name: func.name.name,
+                    arity: func.params.len(),
+                    chunk: Chunk::default(),
+                    captures: vec![],
+                    span: func.span,
+                };
+                let constant = self.constant(Constant::Function(Rc::new(placeholder)));
+                self.emit(Op::Closure(constant), func.span);
+                self.define(func.name.name, func.span);
+                self.state().scopes.last_mut().unwrap().functions.push((constant, id));
+            }
+            Stmt::Return(ret) => {
+                match ret.value {
+                    Some(value) => self.expr(value)?,
+                    None => {
+                        self.emit(Op::Void, ret.span);
+                    }
+                }
+                self.emit(Op::Return, ret.span);
+            }
+            Stmt::If(stmt) => {
+                self.expr(stmt.condition)?;
+                let to_else = self.emit(Op::JumpIfFalse(0), *ast[stmt.condition].span());
+                self.branch(&stmt.then_branch, stmt.span)?;
+
+                match &stmt.else_branch {
+                    Some(else_branch) => {
+                        let to_end = self.emit(Op::Jump(0), stmt.span);
+                        self.patch(to_else);
+                        self.branch(else_branch, stmt.span)?;
+                        self.patch(to_end);
+                    }
+                    None => self.patch(to_else),
+                }
+            }
+            Stmt::LangImport(import) => {
+                let path: Vec<&str> = import.path.iter().map(|m| m.as_str()).collect();
+                let constant = self.constant(Constant::String(Rc::from(path.join("."))));
+                self.emit(Op::Import(constant), import.span);
+                if let Some(&alias) = import.path.last() {
+                    self.define(alias, import.span);
+                }
+            }
+            Stmt::FileImport(import) => {
+                let constant = self.constant(Constant::String(Rc::from(import.path.as_str())));
+                self.emit(Op::ImportFile(constant), import.span);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Compiles a branch of an `if` in a scope of its own.
+    fn branch(&mut self, stmts: &[StmtId], span: LineInfo) -> Result<(), Error> {
+        let slots = self.block(stmts, &[], false, span)?;
+        if slots > 0 {
+            self.emit(Op::EndScope(slots), span);
+        }
+        Ok(())
+    }
+
+    fn expr(&mut self, id: ExprId) -> Result<(), Error> {
+        let ast = self.ast;
+        match &ast[id] {
+            Expr::Literal(literal) => {
+                let op = match literal.value {
+                    Type::Int(i) => Op::Constant(self.constant(Constant::Number(i as f64))),
+                    Type::Float(f) => Op::Constant(self.constant(Constant::Number(f))),
+                    Type::String(s) => Op::Constant(self.constant(Constant::String(Rc::from(s.as_str())))),
+                    Type::Bool(true) => Op::True,
+                    Type::Bool(false) => Op::False,
+                    Type::Iden(name) => self.variable(name),
+                    Type::Void => Op::Void,
+                    Type::Unknown => {
+                        return Err(Error::new(
+                            literal.span,
+                            String::from("Cannot evaluate an expression of unknown type"),
+                        ))
+                    }
+                };
+                self.emit(op, literal.span);
+            }
+            Expr::Grouping(grouping) => self.expr(grouping.expression)?,
+            Expr::Unary(unary) => {
+                self.expr(unary.right)?;
+                let op = match unary.operator {
+                    UnaryOp::Neg => Op::Neg,
+                    UnaryOp::Not => Op::Not,
+                };
+                self.emit(op, unary.operator_span);
+            }
+            Expr::Binary(binary) => {
+                self.expr(binary.left)?;
+                self.expr(binary.right)?;
+                let op = match binary.operator {
+                    BinaryOp::Add => Op::Add,
+                    BinaryOp::Sub => Op::Sub,
+                    BinaryOp::Mul => Op::Mul,
+                    BinaryOp::Div => Op::Div,
+                    BinaryOp::Eq => Op::Eq,
+                    BinaryOp::NotEq => Op::NotEq,
+                    BinaryOp::Less => Op::Less,
+                    BinaryOp::LessEq => Op::LessEq,
+                    BinaryOp::Greater => Op::Greater,
+                    BinaryOp::GreaterEq => Op::GreaterEq,
+                };
+                self.emit(op, binary.operator_span);
+            }
+            Expr::Call(call) => {
+                self.expr(call.callee)?;
+                for &arg in &call.args {
+                    self.expr(arg)?;
+                }
+                self.emit(Op::Call(call.args.len() as u32), call.paren);
+            }
+            Expr::Get(get) => {
+                self.expr(get.object)?;
+                let constant = self.constant(Constant::Name(get.name.name));
+                self.emit(Op::GetMember(constant), get.name.span);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The instruction that reads `name` in the function being compiled.
+    fn variable(&mut self, name: Symbol) -> Op {
+        let depth = self.states.len() - 1;
+
+        if let Some(slot) = self.states[depth].local(name) {
+            return Op::GetLocal(slot);
+        }
+        if let Some(index) = self.upvalue(depth, name) {
+            return Op::GetUpvalue(index);
+        }
+        Op::GetGlobal(self.constant(Constant::Name(name)))
+    }
+
+    /// The upvalue of the function at `depth` that captures `name` from an
+    /// enclosing function, adding it if needed.
+    fn upvalue(&mut self, depth: usize, name: Symbol) -> Option<u32> {
+        if depth == 0 {
+            return None;
+        }
+
+        let capture = match self.states[depth - 1].local(name) {
+            Some(slot) => Capture::Local(slot),
+            None => Capture::Upvalue(self.upvalue(depth - 1, name)?),
+        };
+
+        let captures = &mut self.states[depth].function.captures;
+        let index = match captures.iter().position(|&(c, _)| c == capture) {
+            Some(index) => index,
+            None => {
+                captures.push((capture, name));
+                captures.len() - 1
+            }
+        };
+        Some(index as u32)
+    }
+}
+
+/// The name a statement declares in its block.
+fn declared_name(ast: &Ast, id: StmtId) -> Option<Symbol> {
+    match &ast[id] {
+        Stmt::VarDecl(var) => Some(var.name.name),
+        Stmt::FnDecl(func) => Some(func.name.name),
+        Stmt::LangImport(import) => import.path.last().copied(),
+        _ => None,
+    }
+}
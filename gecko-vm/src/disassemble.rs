@@ -0,0 +1,71 @@
+use std::fmt::Write;
+
+use crate::chunk::{Constant, Function, Op};
+
+/// Lists the instructions of `function` and of every function it declares,
+/// each with the source line it was compiled from. A line the instruction
+/// before already showed is printed as `|`, and code with no line, like the
+/// implicit return at the end of a program, as `-`.
+pub fn disassemble(function: &Function) -> String {
+    let mut out = String::new();
+    function_into(&mut out, function);
+    out
+}
+
+fn function_into(out: &mut String, function: &Function) {
+    let _ = writeln!(out, "== {} ==", function.name);
+
+    let chunk = &function.chunk;
+    let mut previous = None;
+    for (index, (op, span)) in chunk.code.iter().zip(&chunk.spans).enumerate() {
+        let line = match span.line {
+            0 => String::from("-"),
+            line if previous == Some(line) => String::from("|"),
+            line => line.to_string(),
+        };
+        previous = Some(span.line);
+
+        let instruction = match op.operand() {
+            Some(operand) => format!("{} {}", op.name(), operand),
+            None => op.name().to_string(),
+        };
+        match detail(function, *op) {
+            Some(detail) => {
+                let _ = writeln!(out, "{:04} {:>5}  {:<18} {}", index, line, instruction, detail);
+            }
+            None => {
+                let _ = writeln!(out, "{:04} {:>5}  {}", index, line, instruction);
+            }
+        }
+    }
+
+    for constant in &chunk.constants {
+        if let Constant::Function(function) = constant {
+            out.push('\n');
+            function_into(out, function);
+        }
+    }
+}
+
+/// What an operand refers to, where that isn't just a number.
+fn detail(function: &Function, op: Op) -> Option<String> {
+    let constant = |index: u32| &function.chunk.constants[index as usize];
+
+    match op {
+        Op::Constant(index)
+        | Op::GetGlobal(index)
+        | Op::DefineGlobal(index)
+        | Op::GetMember(index)
+        | Op::Closure(index)
+        | Op::Import(index)
+        | Op::ImportFile(index) => Some(match constant(index) {
+            Constant::Number(n) => n.to_string(),
+            Constant::String(s) => format!("{:?}", s),
+            Constant::Name(name) => name.to_string(),
+            Constant::Function(function) => format!("<fn {}>", function.name),
+        }),
+        Op::GetUpvalue(index) => Some(function.captures[index as usize].1.to_string()),
+        Op::Jump(target) | Op::JumpIfFalse(target) => Some(format!("-> {:04}", target)),
+        _ => None,
+    }
+}
@@ -0,0 +1,446 @@
+//! The `.gkc` file format: a compiled program, saved so it can be run
+//! without lexing, parsing or compiling its source again.
+//!
+//! A file starts with the magic bytes `GKC\0`, a little-endian `u16` format
+//! version and a `u64` hash of the source it was compiled from, followed by
+//! the program's script function. A function is its name, arity, span,
+//! captures, constants (function constants nested in place), and each
+//! instruction as a one-byte opcode, its `u32` operand if it has one and
+//! its span. Numbers are little-endian and strings are a `u32` length and
+//! UTF-8 bytes.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use gecko_error::LineInfo;
+use gecko_lexer::symbol::Symbol;
+
+use crate::chunk::{Capture, Chunk, Constant, Function, Op};
+
+pub const MAGIC: &[u8; 4] = b"GKC\0";
+/// Bumped whenever the layout or the instruction set changes, so files from
+/// another version of gecko are rejected instead of misread.
+pub const VERSION: u16 = 1;
+pub const EXTENSION: &str = "gkc";
+/// How deeply functions may be nested in a file, so a crafted one can't
+/// overflow the stack while it is read.
+pub const MAX_NESTING: usize = 256;
+
+/// A program read from a `.gkc` file.
+#[derive(Debug)]
+pub struct Compiled {
+    /// The [`source_hash`] of the source the program was compiled from.
+    pub source_hash: u64,
+    pub function: Rc<Function>,
+}
+
+/// A 64-bit FNV-1a hash, which unlike std's hashers is the same across Rust
+/// versions and so can be saved in a file.
+pub fn source_hash(source: &str) -> u64 {
+    source.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// Where the compiled form of the source at `path` is saved.
+pub fn path_for(path: &Path) -> PathBuf {
+    path.with_extension(EXTENSION)
+}
+
+/// The program saved next to the source at `path`, if there is one compiled
+/// from exactly `source` by this version of gecko.
+pub fn load_fresh(path: &Path, source: &str) -> Option<Rc<Function>> {
+    let bytes = fs::read(path_for(path)).ok()?;
+    let compiled = decode(&bytes).ok()?;
+    (compiled.source_hash == source_hash(source)).then_some(compiled.function)
+}
+
+pub fn encode(function: &Function, source: &str) -> Vec<u8> {
+    let mut out = Vec::from(&MAGIC[..]);
+    out.extend(VERSION.to_le_bytes());
+    out.extend(source_hash(source).to_le_bytes());
+    write_function(&mut out, function);
+    out
+}
+
+pub fn decode(bytes: &[u8]) -> Result<Compiled, String> {
+    if !bytes.starts_with(MAGIC) {
+        return Err(String::from("not a compiled gecko program"));
+    }
+
+    let mut reader = Reader { bytes, pos: MAGIC.len(), depth: 0 };
+    let version = u16::from_le_bytes(reader.array()?);
+    if version != VERSION {
+        return Err(format!(
+            "compiled by a different version of gecko (format {}, expected {})",
+            version, VERSION
+        ));
+    }
+
+    let source_hash = u64::from_le_bytes(reader.array()?);
+    let function = reader.function()?;
+    if reader.pos != bytes.len() {
+        return Err(String::from("unexpected bytes after the program"));
+    }
+    // The VM runs the program without arguments or upvalues.
+    if function.arity != 0 || !function.captures.is_empty() {
+        return Err(format!("'{}' can't be run as a program", function.name));
+    }
+
+    Ok(Compiled { source_hash, function })
+}
+
+fn write_u32(out: &mut Vec<u8>, n: usize) {
+    out.extend((n as u32).to_le_bytes());
+}
+
+fn write_str(out: &mut Vec<u8>, s: &str) {
+    write_u32(out, s.len());
+    out.extend(s.as_bytes());
+}
+
+fn write_span(out: &mut Vec<u8>, span: LineInfo) {
+    write_u32(out, span.line);
+    write_u32(out, span.start);
+    write_u32(out, span.end);
+}
+
+fn write_function(out: &mut Vec<u8>, function: &Function) {
+    write_str(out, function.name.as_str());
+    write_u32(out, function.arity);
+    write_span(out, function.span);
+
+    write_u32(out, function.captures.len());
+    for (capture, name) in &function.captures {
+        match capture {
+            Capture::Local(slot) => {
+                out.push(0);
+                write_u32(out, *slot as usize);
+            }
+            Capture::Upvalue(index) => {
+                out.push(1);
+                write_u32(out, *index as usize);
+            }
+        }
+        write_str(out, name.as_str());
+    }
+
+    let chunk = &function.chunk;
+    write_u32(out, chunk.constants.len());
+    for constant in &chunk.constants {
+        match constant {
+            Constant::Number(n) => {
+                out.push(0);
+                out.extend(n.to_le_bytes());
+            }
+            Constant::String(s) => {
+                out.push(1);
+                write_str(out, s);
+            }
+            Constant::Name(name) => {
+                out.push(2);
+                write_str(out, name.as_str());
+            }
+            Constant::Function(function) => {
+                out.push(3);
+                write_function(out, function);
+            }
+        }
+    }
+
+    write_u32(out, chunk.code.len());
+    for (op, span) in chunk.code.iter().zip(&chunk.spans) {
+        out.push(opcode(*op));
+        if let Some(operand) = op.operand() {
+            write_u32(out, operand as usize);
+        }
+        write_span(out, *span);
+    }
+}
+
+fn opcode(op: Op) -> u8 {
+    match op {
+        Op::Constant(_) => 0,
+        Op::True => 1,
+        Op::False => 2,
+        Op::Void => 3,
+        Op::Pop => 4,
+        Op::Reserve(_) => 5,
+        Op::EndScope(_) => 6,
+        Op::GetLocal(_) => 7,
+        Op::SetLocal(_) => 8,
+        Op::GetUpvalue(_) => 9,
+        Op::GetGlobal(_) => 10,
+        Op::DefineGlobal(_) => 11,
+        Op::GetMember(_) => 12,
+        Op::Neg => 13,
+        Op::Not => 14,
+        Op::Add => 15,
+        Op::Sub => 16,
+        Op::Mul => 17,
+        Op::Div => 18,
+        Op::Eq => 19,
+        Op::NotEq => 20,
+        Op::Less => 21,
+        Op::LessEq => 22,
+        Op::Greater => 23,
+        Op::GreaterEq => 24,
+        Op::Jump(_) => 25,
+        Op::JumpIfFalse(_) => 26,
+        Op::Call(_) => 27,
+        Op::Closure(_) => 28,
+        Op::Return => 29,
+        Op::Import(_) => 30,
+        Op::ImportFile(_) => 31,
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    /// How many functions enclose the one being read.
+    depth: usize,
+}
+
+impl Reader<'_> {
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        let bytes = self
+            .bytes
+            .get(self.pos..self.pos + N)
+            .ok_or_else(|| String::from("the file ends in the middle of the program"))?;
+        self.pos += N;
+        Ok(bytes.try_into().unwrap())
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    fn usize(&mut self) -> Result<usize, String> {
+        Ok(self.u32()? as usize)
+    }
+
+    fn str(&mut self) -> Result<&str, String> {
+        let len = self.usize()?;
+        let bytes = self
+            .bytes
+            .get(self.pos..self.pos + len)
+            .ok_or_else(|| String::from("the file ends in the middle of the program"))?;
+        self.pos += len;
+        std::str::from_utf8(bytes).map_err(|_| String::from("a string is not valid UTF-8"))
+    }
+
+    fn symbol(&mut self) -> Result<Symbol, String> {
+        Symbol::try_intern(self.str()?).map_err(|err| format!("cannot intern a name: {}", err))
+    }
+
+    fn span(&mut self) -> Result<LineInfo, String> {
+        Ok(LineInfo::new(self.usize()?, self.usize()?, self.usize()?))
+    }
+
+    fn function(&mut self) -> Result<Rc<Function>, String> {
+        let name = self.symbol()?;
+        let arity = self.usize()?;
+        let span = self.span()?;
+
+        let mut captures = vec![];
+        for _ in 0..self.u32()? {
+            let capture = match self.u8()? {
+                0 => Capture::Local(self.u32()?),
+                1 => Capture::Upvalue(self.u32()?),
+                tag => return Err(format!("unknown capture kind {}", tag)),
+            };
+            captures.push((capture, self.symbol()?));
+        }
+
+        let mut chunk = Chunk::default();
+        for _ in 0..self.u32()? {
+            let constant = match self.u8()? {
+                0 => Constant::Number(f64::from_le_bytes(self.array()?)),
+                1 => Constant::String(Rc::from(self.str()?)),
+                2 => Constant::Name(self.symbol()?),
+                3 => {
+                    if self.depth == MAX_NESTING {
+                        return Err(format!("functions are nested more than {} deep", MAX_NESTING));
+                    }
+                    self.depth += 1;
+                    let function = self.function()?;
+                    self.depth -= 1;
+                    Constant::Function(function)
+                }
+                tag => return Err(format!("unknown constant kind {}", tag)),
+            };
+            chunk.constants.push(constant);
+        }
+
+        for _ in 0..self.u32()? {
+            let op = self.op()?;
+            let span = self.span()?;
+            chunk.push(op, span);
+        }
+
+        let function = Function { name, arity, chunk, captures, span };
+        check(&function)?;
+        Ok(Rc::new(function))
+    }
+
+    fn op(&mut self) -> Result<Op, String> {
+        Ok(match self.u8()? {
+            0 => Op::Constant(self.u32()?),
+            1 => Op::True,
+            2 => Op::False,
+            3 => Op::Void,
+            4 => Op::Pop,
+            5 => Op::Reserve(self.u32()?),
+            6 => Op::EndScope(self.u32()?),
+            7 => Op::GetLocal(self.u32()?),
+            8 => Op::SetLocal(self.u32()?),
+            9 => Op::GetUpvalue(self.u32()?),
+            10 => Op::GetGlobal(self.u32()?),
+            11 => Op::DefineGlobal(self.u32()?),
+            12 => Op::GetMember(self.u32()?),
+            13 => Op::Neg,
+            14 => Op::Not,
+            15 => Op::Add,
+            16 => Op::Sub,
+            17 => Op::Mul,
+            18 => Op::Div,
+            19 => Op::Eq,
+            20 => Op::NotEq,
+            21 => Op::Less,
+            22 => Op::LessEq,
+            23 => Op::Greater,
+            24 => Op::GreaterEq,
+            25 => Op::Jump(self.u32()?),
+            26 => Op::JumpIfFalse(self.u32()?),
+            27 => Op::Call(self.u32()?),
+            28 => Op::Closure(self.u32()?),
+            29 => Op::Return,
+            30 => Op::Import(self.u32()?),
+            31 => Op::ImportFile(self.u32()?),
+            opcode => return Err(format!("unknown opcode {}", opcode)),
+        })
+    }
+}
+
+/// Rejects operands that don't refer to a constant of the right kind, an
+/// upvalue or an instruction, and code whose stack use doesn't add up. The
+/// VM trusts the compiler's operands and would panic on them.
+fn check(function: &Function) -> Result<(), String> {
+    let chunk = &function.chunk;
+    let constant = |index: u32| chunk.constants.get(index as usize);
+
+    let valid = |op: Op| match op {
+        Op::Constant(i) => matches!(constant(i), Some(Constant::Number(_) | Constant::String(_))),
+        Op::GetGlobal(i) | Op::DefineGlobal(i) | Op::GetMember(i) => matches!(constant(i), Some(Constant::Name(_))),
+        Op::Import(i) | Op::ImportFile(i) => matches!(constant(i), Some(Constant::String(_))),
+        Op::Closure(i) => matches!(constant(i), Some(Constant::Function(_))),
+        Op::GetUpvalue(i) => (i as usize) < function.captures.len(),
+        Op::Jump(target) | Op::JumpIfFalse(target) => (target as usize) < chunk.code.len(),
+        _ => true,
+    };
+
+    match chunk.code.iter().find(|op| !valid(**op)) {
+        Some(op) => Err(invalid(function, *op)),
+        None if chunk.code.last() != Some(&Op::Return) => {
+            Err(format!("'{}' does not end with a return", function.name))
+        }
+        None => check_stack(function),
+    }
+}
+
+/// Follows every path through `function`, counting the values in its call
+/// frame, so that no instruction pops more than the frame holds or reaches
+/// a slot above it, and paths that meet agree on the count.
+fn check_stack(function: &Function) -> Result<(), String> {
+    let code = &function.chunk.code;
+    let mut heights = vec![None; code.len()];
+    // The closure and its arguments.
+    let mut pending = vec![(0, function.arity + 1)];
+
+    while let Some((ip, height)) = pending.pop() {
+        match heights[ip] {
+            Some(seen) if seen == height => continue,
+            Some(_) => return Err(format!("paths to instruction {} in '{}' disagree on the stack", ip, function.name)),
+            None => heights[ip] = Some(height),
+        }
+
+        let op = code[ip];
+        let (pops, pushes) = stack_effect(op);
+        let fits = height >= pops
+            && match op {
+                Op::GetLocal(slot) => (slot as usize) < height,
+                Op::SetLocal(slot) => (slot as usize) < height - 1,
+                // Every reserved slot is set by an instruction of its own.
+                Op::Reserve(slots) => slots as usize <= code.len(),
+                Op::Closure(index) => {
+                    let Some(Constant::Function(closure)) = function.chunk.constants.get(index as usize) else {
+                        unreachable!("closures are made from function constants");
+                    };
+                    closure.captures.iter().all(|&(capture, _)| match capture {
+                        Capture::Local(slot) => (slot as usize) < height,
+                        Capture::Upvalue(index) => (index as usize) < function.captures.len(),
+                    })
+                }
+                _ => true,
+            };
+        if !fits {
+            return Err(invalid(function, op));
+        }
+
+        let height = height - pops + pushes;
+        match op {
+            Op::Return => {}
+            Op::Jump(target) => pending.push((target as usize, height)),
+            Op::JumpIfFalse(target) => pending.extend([(target as usize, height), (ip + 1, height)]),
+            _ => pending.push((ip + 1, height)),
+        }
+    }
+
+    Ok(())
+}
+
+/// How many values `op` pops off the stack and how many it pushes.
+fn stack_effect(op: Op) -> (usize, usize) {
+    match op {
+        Op::Constant(_)
+        | Op::True
+        | Op::False
+        | Op::Void
+        | Op::GetLocal(_)
+        | Op::GetUpvalue(_)
+        | Op::GetGlobal(_)
+        | Op::Closure(_)
+        | Op::Import(_) => (0, 1),
+        Op::Pop | Op::SetLocal(_) | Op::DefineGlobal(_) | Op::JumpIfFalse(_) | Op::Return => (1, 0),
+        Op::Reserve(slots) => (0, slots as usize),
+        Op::EndScope(slots) => (slots as usize, 0),
+        Op::GetMember(_) | Op::Neg | Op::Not => (1, 1),
+        Op::Add
+        | Op::Sub
+        | Op::Mul
+        | Op::Div
+        | Op::Eq
+        | Op::NotEq
+        | Op::Less
+        | Op::LessEq
+        | Op::Greater
+        | Op::GreaterEq => (2, 1),
+        Op::Jump(_) | Op::ImportFile(_) => (0, 0),
+        Op::Call(args) => (args as usize + 1, 1),
+    }
+}
+
+fn invalid(function: &Function, op: Op) -> String {
+    match op.operand() {
+        Some(operand) => format!("invalid instruction {} {} in '{}'", op.name(), operand, function.name),
+        None => format!("invalid instruction {} in '{}'", op.name(), function.name),
+    }
+}
@@ -0,0 +1,449 @@
+//! A bytecode compiler and stack-based virtual machine.
+//!
+//! [`compiler::compile`] turns a program into [`chunk::Function`]s and
+//! [`Vm`] runs them. A program prints and fails the same as under the
+//! tree-walking interpreter, with the same runtime errors.
+//! [`disassemble::disassemble`] lists the bytecode and [`gkc`] saves it to
+//! a file.
+
+pub mod chunk;
+pub mod compiler;
+pub mod disassemble;
+pub mod gkc;
+pub mod stdlib;
+pub mod value;
+
+use std::{cell::RefCell, collections::HashMap, fs, path::PathBuf, rc::Rc};
+
+use chunk::{Capture, Constant, Function, Op};
+use value::{Closure, Upvalue, Value};
+
+use gecko_error::{Error, LineInfo};
+use gecko_lexer::{symbol::Symbol, Lexer};
+use gecko_parser::{ast::Ast, Parser};
+
+/// The same limit as the interpreter's, so a runaway recursion fails the
+/// same way under both.
+const MAX_CALL_DEPTH: usize = 512;
+
+struct Frame {
+    closure: Rc<Closure>,
+    /// The next instruction, saved while the frame calls another.
+    ip: usize,
+    /// The stack index of slot 0, which holds the closure.
+    base: usize,
+}
+
+pub struct Vm {
+    stack: Vec<Value>,
+    frames: Vec<Frame>,
+    globals: HashMap<Symbol, Value>,
+    /// Upvalues still referring to a stack slot.
+    open_upvalues: Vec<Rc<RefCell<Upvalue>>>,
+    base_dir: PathBuf,
+}
+
+impl Default for Vm {
+    fn default() -> Self {
+        Vm::new()
+    }
+}
+
+impl Vm {
+    pub fn new() -> Vm {
+        Vm {
+            stack: Vec::with_capacity(256),
+            frames: Vec::with_capacity(64),
+            globals: HashMap::new(),
+            open_upvalues: vec![],
+            base_dir: PathBuf::from("."),
+        }
+    }
+
+    /// Sets the directory that `import "file.gk";` paths are resolved from.
+    pub fn with_base_dir(mut self, base_dir: PathBuf) -> Vm {
+        self.base_dir = base_dir;
+        self
+    }
+
+    /// Compiles `ast` and runs its top-level statements. Definitions stay in
+    /// the global scope, so `run` can be called repeatedly.
+    pub fn run(&mut self, ast: &Ast) -> Result<(), Error> {
+        let function = compiler::compile(ast)?;
+        self.run_function(function)
+    }
+
+    /// Runs a program compiled by [`compiler::compile`] or read from a
+    /// [`gkc`] file.
+    pub fn run_function(&mut self, function: Rc<Function>) -> Result<(), Error> {
+        let closure = Rc::new(Closure { function, upvalues: vec![] });
+        self.stack.push(Value::Closure(Rc::clone(&closure)));
+        self.call(closure, 0, None)?;
+        self.execute().map(|_| ())
+    }
+
+    /// Calls the program's `main` function, if it declared one.
+    pub fn call_main(&mut self) -> Result<Option<Value>, Error> {
+        let main = self.globals.get(&Symbol::intern("main")).cloned();
+
+        match main {
+            Some(Value::Closure(closure)) if closure.function.arity == 0 => {
+                self.stack.push(Value::Closure(Rc::clone(&closure)));
+                self.call(closure, 0, None)?;
+                Ok(Some(self.execute()?))
+            }
+            Some(Value::Closure(closure)) => Err(Error::new(
+                closure.function.span,
+                String::from("'main' cannot take parameters"),
+            )),
+            Some(_) => Err(Error::new_without_line_info(String::from("'main' is not a function"))),
+            None => Ok(None),
+        }
+    }
+
+    pub fn get_global(&self, name: &str) -> Option<Value> {
+        self.globals.get(&Symbol::intern(name)).cloned()
+    }
+
+    /// Pushes a frame for `closure`, whose arguments are on top of the
+    /// stack above the closure itself.
+    fn call(&mut self, closure: Rc<Closure>, args: usize, paren: Option<LineInfo>) -> Result<(), Error> {
+        if let Some(paren) = paren {
+            check_arity(closure.function.arity, args, paren)?;
+        }
+
+        if self.frames.len() > MAX_CALL_DEPTH {
+            let message = format!("Stack overflow in '{}'", closure.function.name);
+            return Err(match paren {
+                Some(paren) => Error::new(paren, message),
+                None => Error::new_without_line_info(message),
+            });
+        }
+
+        let base = self.stack.len() - args - 1;
+        self.frames.push(Frame { closure, ip: 0, base });
+        Ok(())
+    }
+
+    /// Runs the top frame until it returns, unwinding it on an error.
+    fn execute(&mut self) -> Result<Value, Error> {
+        let entry = self.frames.len() - 1;
+        let base = self.frames[entry].base;
+
+        let result = self.dispatch(entry);
+        if result.is_err() {
+            self.close_upvalues(base);
+            self.frames.truncate(entry);
+            self.stack.truncate(base);
+        }
+        result
+    }
+
+    fn dispatch(&mut self, entry: usize) -> Result<Value, Error> {
+        'frames: loop {
+            let frame = self.frames.last().unwrap();
+            let closure = Rc::clone(&frame.closure);
+            let function = &*closure.function;
+            let code = &function.chunk.code[..];
+            let base = frame.base;
+            let mut ip = frame.ip;
+
+            loop {
+                let op = code[ip];
+                ip += 1;
+
+                match op {
+                    Op::Constant(index) => {
+                        let value = match &function.chunk.constants[index as usize] {
+                            Constant::Number(n) => Value::Number(*n),
+                            Constant::String(s) => Value::String(Rc::clone(s)),
+                            constant => unreachable!("{:?} is not a value", constant),
+                        };
+                        self.stack.push(value);
+                    }
+                    Op::True => self.stack.push(Value::Bool(true)),
+                    Op::False => self.stack.push(Value::Bool(false)),
+                    Op::Void => self.stack.push(Value::Void),
+                    Op::Pop => {
+                        self.stack.pop();
+                    }
+                    Op::Reserve(slots) => {
+                        let len = self.stack.len() + slots as usize;
+                        self.stack.resize(len, Value::Undefined);
+                    }
+                    Op::EndScope(slots) => {
+                        let from = self.stack.len() - slots as usize;
+                        self.close_upvalues(from);
+                        self.stack.truncate(from);
+                    }
+                    Op::GetLocal(slot) => {
+                        let value = self.stack[base + slot as usize].clone();
+                        self.stack.push(value);
+                    }
+                    Op::SetLocal(slot) => {
+                        let value = self.stack.pop().unwrap();
+                        self.stack[base + slot as usize] = value;
+                    }
+                    Op::GetUpvalue(index) => {
+                        let value = match &*closure.upvalues[index as usize].borrow() {
+                            Upvalue::Open(slot) => self.stack[*slot].clone(),
+                            Upvalue::Closed(value) => value.clone(),
+                        };
+                        if let Value::Undefined = value {
+                            let name = function.captures[index as usize].1;
+                            return Err(fail(function, ip, format!("Undefined variable '{}'", name)));
+                        }
+                        self.stack.push(value);
+                    }
+                    Op::GetGlobal(index) => {
+                        let name = name(function, index);
+                        match self.globals.get(&name) {
+                            Some(value) => self.stack.push(value.clone()),
+                            None => return Err(fail(function, ip, format!("Undefined variable '{}'", name))),
+                        }
+                    }
+                    Op::DefineGlobal(index) => {
+                        let value = self.stack.pop().unwrap();
+                        self.globals.insert(name(function, index), value);
+                    }
+                    Op::GetMember(index) => {
+                        let name = name(function, index);
+                        let member = match self.stack.pop().unwrap() {
+                            Value::Module(module) => module.members.get(&name).cloned().ok_or_else(|| {
+                                format!("Module '{}' has no member '{}'", module.name, name)
+                            }),
+                            other => Err(format!("Cannot access '{}' on a {}", name, other.type_name())),
+                        };
+                        match member {
+                            Ok(member) => self.stack.push(member),
+                            Err(message) => return Err(fail(function, ip, message)),
+                        }
+                    }
+                    Op::Neg | Op::Not => {
+                        let value = match (op, self.stack.pop().unwrap()) {
+                            (Op::Neg, Value::Number(n)) => Value::Number(-n),
+                            (Op::Not, Value::Bool(b)) => Value::Bool(!b),
+                            (op, right) => {
+                                let message =
+                                    format!("Cannot apply '{}' to a {}", lexeme(op), right.type_name());
+                                return Err(fail(function, ip, message));
+                            }
+                        };
+                        self.stack.push(value);
+                    }
+                    Op::Add
+                    | Op::Sub
+                    | Op::Mul
+                    | Op::Div
+                    | Op::Eq
+                    | Op::NotEq
+                    | Op::Less
+                    | Op::LessEq
+                    | Op::Greater
+                    | Op::GreaterEq => {
+                        let right = self.stack.pop().unwrap();
+                        let left = self.stack.pop().unwrap();
+                        match binary(op, left, right) {
+                            Ok(value) => self.stack.push(value),
+                            Err(message) => return Err(fail(function, ip, message)),
+                        }
+                    }
+                    Op::Jump(target) => ip = target as usize,
+                    Op::JumpIfFalse(target) => match self.stack.pop().unwrap() {
+                        Value::Bool(true) => {}
+                        Value::Bool(false) => ip = target as usize,
+                        other => {
+                            let message = format!("Condition must be a bool, not a {}", other.type_name());
+                            return Err(fail(function, ip, message));
+                        }
+                    },
+                    Op::Call(args) => {
+                        let args = args as usize;
+                        let callee = self.stack.len() - args - 1;
+                        let paren = function.chunk.spans[ip - 1];
+
+                        match self.stack[callee].clone() {
+                            Value::Closure(callee) => {
+                                self.frames.last_mut().unwrap().ip = ip;
+                                self.call(callee, args, Some(paren))?;
+                                continue 'frames;
+                            }
+                            Value::Native(native) => {
+                                check_arity(native.arity, args, paren)?;
+                                let value = (native.func)(&self.stack[callee + 1..])
+                                    .map_err(|message| Error::new(paren, message))?;
+                                self.stack.truncate(callee);
+                                self.stack.push(value);
+                            }
+                            other => {
+                                return Err(Error::new(paren, format!("Cannot call a {}", other.type_name())));
+                            }
+                        }
+                    }
+                    Op::Closure(index) => {
+                        let Constant::Function(function) = &function.chunk.constants[index as usize] else {
+                            unreachable!("closures are made from function constants");
+                        };
+                        let upvalues = function
+                            .captures
+                            .iter()
+                            .map(|&(capture, _)| match capture {
+                                Capture::Local(slot) => self.capture(base + slot as usize),
+                                Capture::Upvalue(index) => Rc::clone(&closure.upvalues[index as usize]),
+                            })
+                            .collect();
+                        let function = Rc::clone(function);
+                        self.stack.push(Value::Closure(Rc::new(Closure { function, upvalues })));
+                    }
+                    Op::Return => {
+                        let value = self.stack.pop().unwrap();
+                        self.close_upvalues(base);
+                        self.stack.truncate(base);
+                        self.frames.pop();
+
+                        if self.frames.len() == entry {
+                            return Ok(value);
+                        }
+                        self.stack.push(value);
+                        continue 'frames;
+                    }
+                    Op::Import(index) => {
+                        let path = string(function, index);
+                        match stdlib::module(path) {
+                            Some(module) => self.stack.push(Value::Module(Rc::new(module))),
+                            None => return Err(fail(function, ip, format!("Unknown module '{}'", path))),
+                        }
+                    }
+                    Op::ImportFile(index) => {
+                        self.frames.last_mut().unwrap().ip = ip;
+                        self.import_file(string(function, index))?;
+                    }
+                }
+            }
+        }
+    }
+
+    /// The upvalue for a stack slot, shared by every closure capturing it.
+    fn capture(&mut self, slot: usize) -> Rc<RefCell<Upvalue>> {
+        let open = self
+            .open_upvalues
+            .iter()
+            .find(|upvalue| matches!(*upvalue.borrow(), Upvalue::Open(s) if s == slot));
+        if let Some(upvalue) = open {
+            return Rc::clone(upvalue);
+        }
+
+        let upvalue = Rc::new(RefCell::new(Upvalue::Open(slot)));
+        self.open_upvalues.push(Rc::clone(&upvalue));
+        upvalue
+    }
+
+    /// Moves the values of the slots from `from` up into their upvalues.
+    fn close_upvalues(&mut self, from: usize) {
+        let stack = &self.stack;
+        self.open_upvalues.retain(|upvalue| {
+            let mut upvalue = upvalue.borrow_mut();
+            match *upvalue {
+                Upvalue::Open(slot) if slot >= from => {
+                    *upvalue = Upvalue::Closed(stack[slot].clone());
+                    false
+                }
+                _ => true,
+            }
+        });
+    }
+
+    fn import_file(&mut self, path: &str) -> Result<(), Error> {
+        let full_path = self.base_dir.join(path);
+        let source = fs::read_to_string(&full_path).map_err(|e| {
+            Error::new_without_line_info(format!("Cannot import '{}': {}", path, e))
+        })?;
+
+        let in_file = |err: Error| {
+            Error::new_with_notes(
+                LineInfo::new(0, 0, 0),
+                format!("{}:{}: {}", path, err.line_info.line, err.message),
+                err.notes,
+            )
+        };
+
+        if let Some(function) = gkc::load_fresh(&full_path, &source) {
+            return self.run_function(function).map_err(in_file);
+        }
+
+        let tokens = Lexer::new(source).scan_tokens().map_err(in_file)?;
+        let ast = Parser::new(tokens).parse().map_err(in_file)?;
+        self.run(&ast).map_err(in_file)
+    }
+}
+
+/// An error at the instruction before `ip`.
+fn fail(function: &Function, ip: usize, message: String) -> Error {
+    Error::new(function.chunk.spans[ip - 1], message)
+}
+
+fn name(function: &Function, index: u32) -> Symbol {
+    match &function.chunk.constants[index as usize] {
+        Constant::Name(name) => *name,
+        constant => unreachable!("{:?} is not a name", constant),
+    }
+}
+
+fn string(function: &Function, index: u32) -> &str {
+    match &function.chunk.constants[index as usize] {
+        Constant::String(s) => s,
+        constant => unreachable!("{:?} is not a string", constant),
+    }
+}
+
+fn binary(op: Op, left: Value, right: Value) -> Result<Value, String> {
+    match (op, left, right) {
+        (Op::Eq, left, right) => Ok(Value::Bool(left.equals(&right))),
+        (Op::NotEq, left, right) => Ok(Value::Bool(!left.equals(&right))),
+        (Op::Add, Value::Number(a), Value::Number(b)) => Ok(Value::Number(a + b)),
+        (Op::Add, Value::String(a), Value::String(b)) => Ok(Value::String(Rc::from(format!("{}{}", a, b)))),
+        (Op::Sub, Value::Number(a), Value::Number(b)) => Ok(Value::Number(a - b)),
+        (Op::Mul, Value::Number(a), Value::Number(b)) => Ok(Value::Number(a * b)),
+        (Op::Div, Value::Number(_), Value::Number(0.0)) => Err(String::from("Division by zero")),
+        (Op::Div, Value::Number(a), Value::Number(b)) => Ok(Value::Number(a / b)),
+        (Op::Less, Value::Number(a), Value::Number(b)) => Ok(Value::Bool(a < b)),
+        (Op::LessEq, Value::Number(a), Value::Number(b)) => Ok(Value::Bool(a <= b)),
+        (Op::Greater, Value::Number(a), Value::Number(b)) => Ok(Value::Bool(a > b)),
+        (Op::GreaterEq, Value::Number(a), Value::Number(b)) => Ok(Value::Bool(a >= b)),
+        (op, left, right) => Err(format!(
+            "Cannot apply '{}' to a {} and a {}",
+            lexeme(op),
+            left.type_name(),
+            right.type_name()
+        )),
+    }
+}
+
+/// The source operator an arithmetic or comparison instruction came from.
+fn lexeme(op: Op) -> &'static str {
+    match op {
+        Op::Neg | Op::Sub => "-",
+        Op::Not => "!",
+        Op::Add => "+",
+        Op::Mul => "*",
+        Op::Div => "/",
+        Op::Eq => "==",
+        Op::NotEq => "!=",
+        Op::Less => "<",
+        Op::LessEq => "<=",
+        Op::Greater => ">",
+        Op::GreaterEq => ">=",
+        op => unreachable!("{:?} is not an operator", op),
+    }
+}
+
+fn check_arity(expected: usize, got: usize, paren: LineInfo) -> Result<(), Error> {
+    if expected == got {
+        Ok(())
+    } else {
+        Err(Error::new(
+            paren,
+            format!("Expected {} arguments but got {}", expected, got),
+        ))
+    }
+}
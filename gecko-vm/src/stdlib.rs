@@ -0,0 +1,44 @@
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+};
+
+use gecko_lexer::symbol::Symbol;
+
+use crate::value::{Module, NativeFn, Value};
+
+/// Looks up a built-in module by its import path, e.g. `"std.io"`.
+pub fn module(path: &str) -> Option<Module> {
+    match path {
+        "std.io" => Some(io()),
+        _ => None,
+    }
+}
+
+fn io() -> Module {
+    let mut members = HashMap::new();
+    members.insert(
+        Symbol::intern("println"),
+        Value::Native(NativeFn { name: "println", arity: 1, func: println }),
+    );
+    members.insert(
+        Symbol::intern("print"),
+        Value::Native(NativeFn { name: "print", arity: 1, func: print }),
+    );
+
+    Module {
+        name: Symbol::intern("io"),
+        members,
+    }
+}
+
+fn println(args: &[Value]) -> Result<Value, String> {
+    println!("{}", args[0]);
+    Ok(Value::Void)
+}
+
+fn print(args: &[Value]) -> Result<Value, String> {
+    print!("{}", args[0]);
+    io::stdout().flush().map_err(|e| e.to_string())?;
+    Ok(Value::Void)
+}
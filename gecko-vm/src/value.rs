@@ -0,0 +1,93 @@
+use std::{cell::RefCell, collections::HashMap, fmt, rc::Rc};
+
+use gecko_lexer::symbol::Symbol;
+
+use crate::chunk::Function;
+
+#[derive(Clone)]
+pub enum Value {
+    Number(f64),
+    String(Rc<str>),
+    Bool(bool),
+    Closure(Rc<Closure>),
+    Native(NativeFn),
+    Module(Rc<Module>),
+    Void,
+    /// The slot of a name whose declaration hasn't run yet. Only a closure
+    /// can see one, and reading it is an error.
+    Undefined,
+}
+
+pub struct Closure {
+    pub function: Rc<Function>,
+    pub upvalues: Vec<Rc<RefCell<Upvalue>>>,
+}
+
+/// A name a closure captured from an enclosing function. It refers to the
+/// stack slot while that function's block is running and holds the value
+/// once the block ends.
+pub enum Upvalue {
+    Open(usize),
+    Closed(Value),
+}
+
+#[derive(Clone)]
+pub struct NativeFn {
+    pub name: &'static str,
+    pub arity: usize,
+    pub func: fn(&[Value]) -> Result<Value, String>,
+}
+
+pub struct Module {
+    pub name: Symbol,
+    pub members: HashMap<Symbol, Value>,
+}
+
+impl Value {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Bool(_) => "bool",
+            Value::Closure(_) | Value::Native(_) => "function",
+            Value::Module(_) => "module",
+            Value::Void | Value::Undefined => "void",
+        }
+    }
+
+    pub fn equals(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Number(a), Value::Number(b)) => a == b,
+            (Value::String(a), Value::String(b)) => a == b,
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::Closure(a), Value::Closure(b)) => Rc::ptr_eq(a, b),
+            (Value::Native(a), Value::Native(b)) => a.name == b.name,
+            (Value::Module(a), Value::Module(b)) => Rc::ptr_eq(a, b),
+            (Value::Void, Value::Void) => true,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Number(n) => write!(f, "{}", n),
+            Value::String(s) => write!(f, "{}", s),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Closure(closure) => write!(f, "<fn {}>", closure.function.name),
+            Value::Native(native) => write!(f, "<native fn {}>", native.name),
+            Value::Module(module) => write!(f, "<module {}>", module.name),
+            Value::Void | Value::Undefined => write!(f, "void"),
+        }
+    }
+}
+
+impl fmt::Debug for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::String(s) => write!(f, "{:?}", s),
+            _ => write!(f, "{}", self),
+        }
+    }
+}
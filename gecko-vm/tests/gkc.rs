@@ -0,0 +1,215 @@
+use std::{env, fs, rc::Rc};
+
+use gecko_lexer::Lexer;
+use gecko_parser::Parser;
+use gecko_vm::{
+    chunk::{Capture, Constant, Function, Op},
+    compiler::compile,
+    disassemble::disassemble,
+    gkc, Vm,
+};
+
+fn compile_source(source: &str) -> Rc<Function> {
+    let tokens = Lexer::new(source.to_string()).scan_tokens().unwrap();
+    compile(&Parser::new(tokens).parse().unwrap()).unwrap()
+}
+
+const PROGRAM: &str = "import std.io;
+fn adder(n: number) {
+    fn add(x: number) -> number {
+        return x + n;
+    }
+    return add;
+}
+fn main() -> number {
+    if adder(0.5)(1) > 1 {
+        io.print(\"big\");
+    }
+    return adder(40)(2);
+}";
+
+#[test]
+fn programs_survive_a_round_trip() {
+    let function = compile_source(PROGRAM);
+    let bytes = gkc::encode(&function, PROGRAM);
+    assert!(bytes.starts_with(b"GKC\0\x01\x00"));
+
+    let compiled = gkc::decode(&bytes).unwrap();
+    assert_eq!(compiled.function, function);
+    assert_eq!(compiled.source_hash, gkc::source_hash(PROGRAM));
+
+    let mut vm = Vm::new();
+    vm.run_function(compiled.function).unwrap();
+    assert_eq!(vm.call_main().unwrap().unwrap().to_string(), "42");
+}
+
+#[test]
+fn bad_files_are_rejected() {
+    let bytes = gkc::encode(&compile_source(PROGRAM), PROGRAM);
+
+    assert_eq!(gkc::decode(b"fn main() {}").unwrap_err(), "not a compiled gecko program");
+
+    let mut newer = bytes.clone();
+    newer[4] = 2;
+    assert_eq!(
+        gkc::decode(&newer).unwrap_err(),
+        "compiled by a different version of gecko (format 2, expected 1)"
+    );
+
+    assert_eq!(gkc::decode(&bytes[..bytes.len() - 1]).unwrap_err(), "the file ends in the middle of the program");
+
+    let mut longer = bytes.clone();
+    longer.push(0);
+    assert_eq!(gkc::decode(&longer).unwrap_err(), "unexpected bytes after the program");
+
+    // A program whose first instruction loads a constant that isn't there.
+    let mut function = (*compile_source("let a = 1;")).clone();
+    function.chunk.constants.clear();
+    let bytes = gkc::encode(&function, "");
+    assert_eq!(gkc::decode(&bytes).unwrap_err(), "invalid instruction Constant 0 in '<script>'");
+}
+
+/// The error decoding `source` compiled and then changed by `patch`.
+/// An empty script with functions nested `depth` deep inside it.
+fn nested(depth: usize) -> Function {
+    let empty = (*compile_source("")).clone();
+    let mut function = empty.clone();
+    for _ in 0..depth {
+        let mut outer = empty.clone();
+        outer.chunk.constants.push(Constant::Function(Rc::new(function)));
+        function = outer;
+    }
+    function
+}
+
+#[test]
+fn deeply_nested_functions_are_rejected() {
+    let bytes = gkc::encode(&nested(gkc::MAX_NESTING), "");
+    assert!(gkc::decode(&bytes).is_ok());
+
+    let bytes = gkc::encode(&nested(gkc::MAX_NESTING + 1), "");
+    assert_eq!(gkc::decode(&bytes).unwrap_err(), "functions are nested more than 256 deep");
+}
+
+fn patched(source: &str, patch: impl FnOnce(&mut Function)) -> String {
+    let mut function = (*compile_source(source)).clone();
+    patch(&mut function);
+    gkc::decode(&gkc::encode(&function, source)).unwrap_err()
+}
+
+/// The function declared first in `function`.
+fn first_function(function: &mut Function) -> &mut Function {
+    let constant = function.chunk.constants.iter_mut().find(|c| matches!(c, Constant::Function(_)));
+    let Some(Constant::Function(function)) = constant else {
+        panic!("no function in '{}'", function.name);
+    };
+    Rc::make_mut(function)
+}
+
+#[test]
+fn operands_outside_the_frame_are_rejected() {
+    let source = "fn f(n: number) -> number {\n    return n;\n}";
+    let error = patched(source, |script| first_function(script).chunk.code[0] = Op::GetLocal(99));
+    assert_eq!(error, "invalid instruction GetLocal 99 in 'f'");
+    let error = patched(source, |script| first_function(script).chunk.code[0] = Op::SetLocal(1));
+    assert_eq!(error, "invalid instruction SetLocal 1 in 'f'");
+    let error = patched(source, |script| first_function(script).chunk.code[0] = Op::EndScope(3));
+    assert_eq!(error, "invalid instruction EndScope 3 in 'f'");
+    let error = patched(source, |script| first_function(script).chunk.code[0] = Op::Reserve(u32::MAX));
+    assert_eq!(error, format!("invalid instruction Reserve {} in 'f'", u32::MAX));
+    let error = patched(source, |script| first_function(script).chunk.code[0] = Op::Call(2));
+    assert_eq!(error, "invalid instruction Call 2 in 'f'");
+    let error = patched(source, |script| first_function(script).chunk.code[..2].copy_from_slice(&[Op::Pop, Op::Add]));
+    assert_eq!(error, "invalid instruction Add in 'f'");
+
+    // A jump that skips a push, so the stack differs where the paths meet.
+    let error = patched("let a = 1;", |script| {
+        script.chunk.code.insert(0, Op::JumpIfFalse(3));
+        script.chunk.code.insert(0, Op::True);
+        script.chunk.spans.splice(0..0, [script.chunk.spans[0]; 2]);
+    });
+    assert_eq!(error, "paths to instruction 3 in '<script>' disagree on the stack");
+
+    let source = "fn adder(n: number) {\n    fn add(x: number) -> number {\n        return x + n;\n    }\n    return add;\n}";
+    let error = patched(source, |script| {
+        let add = first_function(first_function(script));
+        add.captures[0].0 = Capture::Local(7);
+    });
+    assert!(error.starts_with("invalid instruction Closure ") && error.ends_with(" in 'adder'"), "{}", error);
+    let error = patched(source, |script| {
+        let add = first_function(first_function(script));
+        add.captures[0].0 = Capture::Upvalue(0);
+    });
+    assert!(error.starts_with("invalid instruction Closure ") && error.ends_with(" in 'adder'"), "{}", error);
+
+    let error = patched(source, |script| script.arity = 1);
+    assert_eq!(error, "'<script>' can't be run as a program");
+}
+
+#[test]
+fn stale_files_are_ignored() {
+    let dir = env::temp_dir().join(format!("gecko-vm-gkc-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("prog.gk");
+    assert_eq!(gkc::path_for(&path), dir.join("prog.gkc"));
+
+    assert!(gkc::load_fresh(&path, PROGRAM).is_none());
+    fs::write(gkc::path_for(&path), gkc::encode(&compile_source(PROGRAM), PROGRAM)).unwrap();
+    assert!(gkc::load_fresh(&path, PROGRAM).is_some());
+    assert!(gkc::load_fresh(&path, &PROGRAM.replace("40", "41")).is_none());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn imports_load_up_to_date_compiled_files() {
+    let dir = env::temp_dir().join(format!("gecko-vm-gkc-import-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let lib = "fn answer() -> number { return 1; }";
+    fs::write(dir.join("lib.gk"), lib).unwrap();
+
+    // A compiled file that disagrees with its source shows which one ran.
+    let other = compile_source("fn answer() -> number { return 2; }");
+    fs::write(dir.join("lib.gkc"), gkc::encode(&other, lib)).unwrap();
+
+    let run = || {
+        let mut vm = Vm::new().with_base_dir(dir.clone());
+        vm.run_function(compile_source("import \"lib.gk\";\nlet a = answer();")).unwrap();
+        vm.get_global("a").unwrap().to_string()
+    };
+    assert_eq!(run(), "2");
+
+    fs::write(dir.join("lib.gk"), format!("{}\n", lib)).unwrap();
+    assert_eq!(run(), "1");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn disassembly_shows_lines_and_operands() {
+    let source = "let a = 1;\nfn f(n: number) -> number {\n    if n > a {\n        return n;\n    }\n    return -n;\n}";
+    let expected = "\
+== <script> ==
+0000     1  Constant 0         1
+0001     |  DefineGlobal 1     a
+0002     2  Closure 2          <fn f>
+0003     |  DefineGlobal 3     f
+0004     -  Void
+0005     -  Return
+
+== f ==
+0000     3  GetLocal 1
+0001     |  GetGlobal 0        a
+0002     |  Greater
+0003     |  JumpIfFalse 6      -> 0006
+0004     4  GetLocal 1
+0005     |  Return
+0006     6  GetLocal 1
+0007     |  Neg
+0008     |  Return
+0009     2  Void
+0010     |  Return
+";
+
+    assert_eq!(disassemble(&compile_source(source)), expected);
+}
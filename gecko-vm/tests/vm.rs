@@ -0,0 +1,227 @@
+use std::{env, fs, path::PathBuf, rc::Rc};
+
+use gecko_interpreter::Interpreter;
+use gecko_lexer::Lexer;
+use gecko_parser::{ast::Ast, Parser};
+use gecko_vm::{
+    chunk::{Constant, Op},
+    compiler::compile,
+    Vm,
+};
+
+fn parse(source: &str) -> Ast {
+    let tokens = Lexer::new(source.to_string()).scan_tokens().unwrap();
+    Parser::new(tokens).parse().unwrap()
+}
+
+/// What `main` returned, or the error with the line it points at.
+fn run_vm(source: &str, base_dir: PathBuf) -> String {
+    let mut vm = Vm::new().with_base_dir(base_dir);
+    let result = vm.run(&parse(source)).and_then(|_| vm.call_main());
+    match result {
+        Ok(value) => format!("{:?}", value),
+        Err(err) => format!("{}: {}", err.line_info.line, err.message),
+    }
+}
+
+fn run_interpreter(source: &str, base_dir: PathBuf) -> String {
+    let mut interpreter = Interpreter::new().with_base_dir(base_dir);
+    let result = interpreter.run(Rc::new(parse(source))).and_then(|_| interpreter.call_main());
+    match result {
+        Ok(value) => format!("{:?}", value),
+        Err(err) => format!("{}: {}", err.line_info.line, err.message),
+    }
+}
+
+/// Runs `source` on both, asserting they agree, and returns the result.
+fn run(source: &str) -> String {
+    let vm = run_vm(source, PathBuf::from("."));
+    assert_eq!(vm, run_interpreter(source, PathBuf::from(".")), "for {}", source);
+    vm
+}
+
+#[test]
+fn arithmetic_and_comparisons() {
+    assert_eq!(run("fn main() -> number { return 1 + 2 * 3 - 8 / 4; }"), "Some(5)");
+    assert_eq!(run("fn main() -> string { return \"a\" + \"b\"; }"), "Some(\"ab\")");
+    assert_eq!(run("fn main() -> bool { return !(1 <= 2) == (3 > 4); }"), "Some(true)");
+    assert_eq!(run("fn main() -> bool { return 1 == \"1\"; }"), "Some(false)");
+}
+
+#[test]
+fn recursion_and_branches() {
+    let source = "fn fib(n: number) -> number {
+    if n < 2 {
+        return n;
+    } else if n == 2 {
+        return 1;
+    }
+    return fib(n - 1) + fib(n - 2);
+}
+fn main() -> number {
+    return fib(15);
+}
+";
+
+    assert_eq!(run(source), "Some(610)");
+}
+
+#[test]
+fn closures_capture_their_scope() {
+    let source = "fn adder(n: number) {
+    fn add(x: number) -> number {
+        return x + n + offset;
+    }
+    let offset = 100;
+    return add;
+}
+fn main() -> number {
+    let add_one = adder(1);
+    let add_two = adder(2);
+    return add_one(10) + add_two(20);
+}
+";
+
+    assert_eq!(run(source), "Some(233)");
+}
+
+#[test]
+fn functions_see_names_declared_after_them() {
+    let source = "fn main() -> number {
+    fn even(n: number) -> bool {
+        if n == 0 {
+            return true;
+        }
+        return odd(n - 1);
+    }
+    fn odd(n: number) -> bool {
+        if n == 0 {
+            return false;
+        }
+        return even(n - 1);
+    }
+    if even(10) {
+        return helper();
+    }
+    return 0;
+}
+fn helper() -> number {
+    return 7;
+}
+";
+
+    assert_eq!(run(source), "Some(7)");
+}
+
+#[test]
+fn blocks_are_scopes() {
+    let source = "let x = \"global\";
+fn main() -> string {
+    let y = x;
+    let x = \"outer\";
+    if true {
+        let early = x;
+        let x = \"inner\";
+        fn read() -> string {
+            return x;
+        }
+        return early + \" \" + y + \" \" + read();
+    }
+    return x;
+}
+";
+
+    assert_eq!(run(source), "Some(\"outer global inner\")");
+}
+
+#[test]
+fn top_level_returns_stop_the_program() {
+    assert_eq!(run("fn main() -> number { return 1; } return; fn main() -> number { return 2; }"), "Some(1)");
+}
+
+#[test]
+fn runtime_errors_match_the_interpreter() {
+    let errors = [
+        ("fn main() { let a = -\"x\"; }", "1: Cannot apply '-' to a string"),
+        ("fn main() { let a = 1 / 0; }", "1: Division by zero"),
+        ("fn main() {\n if 1 { }\n}", "2: Condition must be a bool, not a number"),
+        ("fn main() { let a = 1; a(); }", "1: Cannot call a number"),
+        ("fn f(a: number) {}\nfn main() { f(); }", "2: Expected 1 arguments but got 0"),
+        ("fn main() { missing; }", "1: Undefined variable 'missing'"),
+        ("fn main() {\n fn early() { return late; }\n early();\n let late = 1;\n}", "2: Undefined variable 'late'"),
+        ("import std.io; io.nothing;", "1: Module 'io' has no member 'nothing'"),
+        ("let a = 1; a.b;", "1: Cannot access 'b' on a number"),
+        ("import std.nothing;", "1: Unknown module 'std.nothing'"),
+        ("fn main(a: number) {}", "1: 'main' cannot take parameters"),
+        ("let main = 1;", "0: 'main' is not a function"),
+    ];
+
+    for (source, error) in errors {
+        assert_eq!(run(source), error);
+    }
+}
+
+#[test]
+fn deep_recursion_overflows_at_the_interpreters_limit() {
+    // The interpreter recurses on the native stack, which a test thread
+    // doesn't have enough of to reach its limit, so only the VM runs here.
+    let source = "fn f(n: number) -> number {\n return f(n + 1);\n}\nf(1);";
+    assert_eq!(run_vm(source, PathBuf::from(".")), "2: Stack overflow in 'f'");
+    assert_eq!(run_vm("fn f(n: number) -> number { if n == 0 { return 0; } return f(n - 1); }\nlet a = f(500);", PathBuf::from(".")), "None");
+}
+
+#[test]
+fn file_imports_define_globals() {
+    let dir = env::temp_dir().join(format!("gecko-vm-import-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("lib.gk"), "fn double(n: number) -> number { return n * 2; }\nlet base = 20;").unwrap();
+    fs::write(dir.join("broken.gk"), "let a = 1;\nlet b = -\"x\";").unwrap();
+
+    let source = "import \"lib.gk\";\nfn main() -> number { return double(base) + 2; }";
+    assert_eq!(run_vm(source, dir.clone()), "Some(42)");
+    assert_eq!(run_interpreter(source, dir.clone()), "Some(42)");
+
+    let source = "import \"broken.gk\";";
+    assert_eq!(run_vm(source, dir.clone()), "0: broken.gk:2: Cannot apply '-' to a string");
+    assert_eq!(run_interpreter(source, dir.clone()), "0: broken.gk:2: Cannot apply '-' to a string");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn globals_persist_between_runs() {
+    let mut vm = Vm::new();
+    vm.run(&parse("let a = 1; fn f() -> number { return a + 1; }")).unwrap();
+    vm.run(&parse("let b = f();")).unwrap();
+
+    assert_eq!(vm.get_global("b").unwrap().to_string(), "2");
+    assert!(vm.run(&parse("let c = -true;")).is_err());
+    vm.run(&parse("let c = b + 1;")).unwrap();
+    assert_eq!(vm.get_global("c").unwrap().to_string(), "3");
+}
+
+#[test]
+fn locals_live_in_slots() {
+    let function = compile(&parse("fn f(a: number) { let b = a; return b + 1.5 + 1.5; }")).unwrap();
+    let Some(Constant::Function(f)) = function.chunk.constants.iter().find(|c| matches!(c, Constant::Function(_))) else {
+        panic!("no function constant");
+    };
+
+    assert_eq!(
+        f.chunk.code,
+        [
+            Op::Reserve(1),
+            Op::GetLocal(1),
+            Op::SetLocal(2),
+            Op::GetLocal(2),
+            Op::Constant(0),
+            Op::Add,
+            Op::Constant(0),
+            Op::Add,
+            Op::Return,
+            Op::Void,
+            Op::Return,
+        ]
+    );
+    assert_eq!(f.chunk.constants, [Constant::Number(1.5)]);
+}